@@ -14,6 +14,7 @@
 
 use std::cmp;
 use std::io;
+use std::mem;
 use std::io::BufRead;
 use std::io::BufReader;
 
@@ -21,7 +22,12 @@ use std::io::BufReader;
 use std::io::Cursor;
 
 use super::adifutil;
+use super::AdifInputError;
+use super::AdifInputErrorKind;
 use super::AdifParseError;
+use super::AdifParseOptions;
+use super::AdifParseWarning;
+use super::AdifPosition;
 
 //
 // Special strings
@@ -30,19 +36,14 @@ const ADI_STR_EOH : &'static str = "eoh";   // end-of-header marker
 const ADI_STR_EOR : &'static str = "eor";   // end-of-record marker
 
 //
-// We impose a maximum size for each value, primarily to ensure graceful failure
-// when given bad input.  It should be safe to increase this provided there will
-// be enough memory available to store the whole contents of the file.
-//
-const ADI_MAX_FIELDLEN : usize = 1024;
-
-//
-// AdiFile: represents a complete ADI file.  This structure is not compatible
-// with a streaming parser, but we're not looking to build one here.
+// AdiFile: represents a complete ADI file.  This structure holds every record
+// in memory.  For large files, use AdiReader (see adi_reader()) instead, which
+// returns one record at a time.
 //
 pub struct AdiFile {
     pub adi_header : Option<AdiHeader>,         // file header, if present
-    pub adi_records : Vec<AdiRecord>            // list of records in the file
+    pub adi_records : Vec<AdiRecord>,           // list of records in the file
+    pub adi_warnings : Vec<AdifParseWarning>    // input skipped (lenient mode)
 }
 
 //
@@ -76,7 +77,8 @@ pub struct AdiDataSpecifier {
     pub adif_name_canon : String,   // canonicalized name (lowercase)
     pub adif_length : usize,        // size in bytes of the field's value
     pub adif_bytes : Vec<u8>,       // contents of the field's value
-    pub adif_type : Option<String>  // type specifier for the field, if provided
+    pub adif_type : Option<String>, // type specifier for the field, if provided
+    pub adif_position : AdifPosition    // position of the "<" in the input
 }
 
 //
@@ -142,7 +144,7 @@ fn adi_dump_record(rec : &AdiRecord, output: &mut String)
 pub fn adi_parse_string(source: &str) -> Result<AdiFile, AdifParseError>
 {
     let mut source_reader = Cursor::new(source);
-    adi_parse(&mut source_reader)
+    adi_parse(&mut source_reader, &AdifParseOptions::default())
 }
 
 //
@@ -204,13 +206,12 @@ fn adi_token_text(token: &AdiToken) -> String
 
 //
 // Given a text token that must contain only ASCII bytes, return a String
-// representation of the token.
+// representation of the token.  "what" describes the token for error messages.
 //
-fn adi_token_string(token: &AdiToken, label : &str) ->
+fn adi_token_string(aps: &AdiParseState, token: &AdiToken, what: &str) ->
     Result<String, AdifParseError>
 {
     if let AdiToken::ADI_TOK_BYTES(buf) = token {
-        let mut i = 0;
         for &cb in buf.iter() {
             let c = cb as char;
 
@@ -225,10 +226,9 @@ fn adi_token_string(token: &AdiToken, label : &str) ->
             //
             if !c.is_ascii() ||
                (c.is_ascii_control() && c != '\r' && c != '\n') {
-                // TODO add byte offset
-                return Err(AdifParseError::ADIF_EBADINPUT(format!(
-                    "{}: expected ASCII character, but found byte 0x{:x}",
-                    label, buf[i])));
+                return Err(adi_error(aps, AdifInputErrorKind::ADIE_ENCODING,
+                    format!("{}: expected ASCII character, but found byte \
+                    0x{:x}", what, cb)));
             }
         }
 
@@ -239,8 +239,8 @@ fn adi_token_string(token: &AdiToken, label : &str) ->
         //
         return Ok(String::from_utf8(buf.clone()).unwrap());
     } else {
-        return Err(AdifParseError::ADIF_EBADINPUT(format!(
-            "{}: expected ASCII string, but found {}", label,
+        return Err(adi_error(aps, AdifInputErrorKind::ADIE_SYNTAX, format!(
+            "{}: expected ASCII string, but found {}", what,
             adi_token_text(token))));
     }
 }
@@ -349,12 +349,17 @@ fn adi_import_read_token(source : &mut BufRead) ->
 //      these functions use adi_parse_advance_tokens() to read tokens as needed
 //      from the underlying input.
 //
-//  This module exports one primary interface:
+//  This module exports two primary interfaces:
+//
+//      adi_reader() takes a buffered input stream, parses the header, and
+//      returns an AdiReader, which is an iterator over the records that
+//      follow.  Only one record is held in memory at a time.
+//
+//      adi_parse() takes an input stream and returns a parsed AdiFile.  This
+//      is just an AdiReader whose records have been collected into a vector.
 //
-//      adi_parse() takes an input stream and returns a parsed AdiFile.
-//      Under the hood, this uses functions like adi_parse_header(),
-//      adi_parse_records(), etc., which in turn use the above mid-level
-//      interface.
+//  Under the hood, these use functions like adi_parse_header() and
+//  adi_parse_record(), which in turn use the above mid-level interface.
 //
 //
 struct AdiParseState<'a> {
@@ -362,7 +367,10 @@ struct AdiParseState<'a> {
     aps_tokens : Vec<AdiToken>,         // next unconsumed tokens
     aps_error : bool,                   // if true, we've encountered an error
     aps_done : bool,                    // if true, we've read EOF
-    aps_bytes_consumed : usize          // bytes of input consumed
+    aps_position : AdifPosition,        // position of first unconsumed byte
+    aps_options : AdifParseOptions,     // limits and recovery behavior
+    aps_warnings : Vec<AdifParseWarning>,   // input skipped so far
+    aps_nrecords : usize                // records started so far
 }
 
 //
@@ -429,16 +437,41 @@ fn adi_parse_consume_tokens(aps : &mut AdiParseState, howmany : u8)
     while count < howmany {
         let removed = aps.aps_tokens.remove(0);
         count += 1;
-        aps.aps_bytes_consumed += match removed {
-            AdiToken::ADI_TOK_LAB => "<".len(),
-            AdiToken::ADI_TOK_RAB => ">".len(),
-            AdiToken::ADI_TOK_COLON => ":".len(),
-            AdiToken::ADI_TOK_BYTES(b) => b.len(),
+        let position = &mut aps.aps_position;
+        match removed {
+            AdiToken::ADI_TOK_LAB |
+            AdiToken::ADI_TOK_RAB |
+            AdiToken::ADI_TOK_COLON => {
+                position.adpp_offset += 1;
+                position.adpp_column += 1;
+            },
+            AdiToken::ADI_TOK_BYTES(b) => {
+                position.adpp_offset += b.len();
+                match b.iter().rposition(|&c| c == b'\n') {
+                    Some(i) => {
+                        position.adpp_line +=
+                            b.iter().filter(|&&c| c == b'\n').count();
+                        position.adpp_column = b.len() - i;
+                    },
+                    None => position.adpp_column += b.len()
+                }
+            },
             AdiToken::ADI_TOK_EOF => panic!("attempted to consume EOF")
         }
     }
 }
 
+//
+// Returns an error describing invalid input at the current position (i.e., the
+// start of the unconsumed input).
+//
+fn adi_error(aps: &AdiParseState, kind: AdifInputErrorKind, message: String) ->
+    AdifParseError
+{
+    AdifParseError::ADIF_EBADINPUT(
+        AdifInputError::new(kind, message).with_position(aps.aps_position))
+}
+
 /*
  * Examine the Nth token from the start of unconsumed input.  If callers process
  * this token, they should call adi_parse_consume_tokens().
@@ -466,14 +499,53 @@ fn adi_parse_peek_token<'a>(aps : &'a mut AdiParseState, which : u8) ->
 //
 // General entry point for parsing an ADI file from an input source.
 //
-pub fn adi_parse(source: &mut io::Read) -> Result<AdiFile, AdifParseError>
+pub fn adi_parse(source: &mut dyn io::Read, options: &AdifParseOptions) ->
+    Result<AdiFile, AdifParseError>
+{
+    let mut reader = adi_reader(BufReader::new(source), options)?;
+    let header = reader.adr_header.take();
+    let records = reader.by_ref()
+        .collect::<Result<Vec<AdiRecord>, AdifParseError>>()?;
+
+    Ok(AdiFile {
+        adi_header: header,
+        adi_records: records,
+        adi_warnings: reader.take_warnings()
+    })
+}
+
+//
+// AdiReader: an iterator over the records of an ADI file.  The header (if any)
+// has already been parsed when the reader is created.  Each call to next()
+// parses one more record from the underlying input.  After an error, the
+// iterator returns None, since there's no reliable way to resynchronize with
+// the input.
+//
+// In lenient mode, malformed data specifiers are skipped rather than ending the
+// iteration.  Use take_warnings() to find out what was skipped.
+//
+pub struct AdiReader<'a> {
+    pub adr_header : Option<AdiHeader>,     // file header, if present
+    adr_aps : AdiParseState<'a>,            // parser state for the input
+    adr_done : bool                         // if true, no more records
+}
+
+//
+// Create an AdiReader for the given input.  This reads and parses the header
+// before returning.
+//
+pub fn adi_reader<'a, R: BufRead + 'a>(source: R, options: &AdifParseOptions)
+    -> Result<AdiReader<'a>, AdifParseError>
 {
     let mut aps = AdiParseState {
-        aps_source: Box::new(BufReader::new(source)),
+        aps_source: Box::new(source),
         aps_tokens: Vec::new(),
         aps_error: false,
         aps_done: false,
-        aps_bytes_consumed: 0
+        aps_position: AdifPosition::default(),
+        aps_options: options.clone(),
+        aps_warnings: Vec::new(),
+        aps_nrecords: 0
     };
 
     let header = match adi_parse_peek_token(&mut aps, 0)? {
@@ -483,14 +555,53 @@ pub fn adi_parse(source: &mut io::Read) -> Result<AdiFile, AdifParseError>
         }
     };
 
-    let records = adi_parse_records(&mut aps)?;
+    adi_parse_consume_until_lab(&mut aps)?;
 
-    Ok(AdiFile {
-        adi_header: header,
-        adi_records: records
+    Ok(AdiReader {
+        adr_header: header,
+        adr_aps: aps,
+        adr_done: false
     })
 }
 
+impl<'a> AdiReader<'a> {
+    //
+    // Returns the warnings recorded since the last call.  These are only ever
+    // recorded in lenient mode.
+    //
+    pub fn take_warnings(&mut self) -> Vec<AdifParseWarning> {
+        mem::take(&mut self.adr_aps.aps_warnings)
+    }
+}
+
+impl<'a> Iterator for AdiReader<'a> {
+    type Item = Result<AdiRecord, AdifParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.adr_done {
+            let result = match adi_parse_peek_token(&mut self.adr_aps, 0) {
+                Ok(AdiToken::ADI_TOK_EOF) => {
+                    self.adr_done = true;
+                    return None;
+                },
+                Ok(_) => adi_parse_record(&mut self.adr_aps),
+                Err(e) => Err(e)
+            };
+
+            match result {
+                Ok(Some(record)) => return Some(Ok(record)),
+                Ok(None) => continue,   // skipped in lenient mode
+                Err(e) => {
+                    self.adr_done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        None
+    }
+}
+
 //
 // Parse the header of an ADI file.
 //
@@ -545,12 +656,13 @@ fn adi_parse_header(aps: &mut AdiParseState) -> Result<AdiHeader, AdifParseError
                 // "eoh", which is pretty dubious, but appears to be technically
                 // allowed.
                 //
-                let spec = adi_parse_data_specifier(aps)?;
-                header_fields.push(spec);
+                if let Some(spec) = adi_parse_field(aps, None)? {
+                    header_fields.push(spec);
+                }
             },
 
             AdiToken::ADI_TOK_EOF => {
-                return Err(AdifParseError::ADIF_EBADINPUT(
+                return Err(adi_error(aps, AdifInputErrorKind::ADIE_TRUNCATED,
                     "unexpected end of input while reading header".to_string()));
             }
         }
@@ -562,6 +674,44 @@ fn adi_parse_header(aps: &mut AdiParseState) -> Result<AdiHeader, AdifParseError
     })
 }
 
+//
+// Parses a data specifier, as adi_parse_data_specifier() does.  In lenient
+// mode, if the data specifier is malformed, this records a warning, skips ahead
+// to the next "<", and returns None.  "record" is the 1-based index of the
+// record containing the data specifier, or None for the header.
+//
+fn adi_parse_field(aps: &mut AdiParseState, record: Option<usize>) ->
+    Result<Option<AdiDataSpecifier>, AdifParseError>
+{
+    let start = aps.aps_position;
+    let error = match adi_parse_data_specifier(aps) {
+        Ok(spec) => return Ok(Some(spec)),
+        Err(AdifParseError::ADIF_EBADINPUT(error)) => match record {
+            Some(which) => error.with_record(which),
+            None => error
+        },
+        Err(e) => return Err(e)
+    };
+
+    if !aps.aps_options.adpo_lenient || aps.aps_error {
+        return Err(AdifParseError::ADIF_EBADINPUT(error));
+    }
+
+    aps.aps_warnings.push(error);
+
+    //
+    // If the data specifier itself was malformed, we haven't consumed
+    // anything yet.  Skip the "<" so that we don't find the same data
+    // specifier again.
+    //
+    if aps.aps_position == start &&
+        adi_parse_peek_token(aps, 0)? == AdiToken::ADI_TOK_LAB {
+        adi_parse_consume_tokens(aps, 1);
+    }
+    adi_parse_consume_until_lab(aps)?;
+    Ok(None)
+}
+
 //
 // Parses a data specifier.  The caller is responsible for ensuring that the
 // first token is a left angle bracket before invoking this function.
@@ -581,69 +731,84 @@ fn adi_parse_header(aps: &mut AdiParseState) -> Result<AdiHeader, AdifParseError
 //                                     + 7 (LAB)
 //
 // ADI also allows an additional colon (COLON) and type specifier (STRING)
-// directly after the field length.  We do not yet support this, so we only
-// handle the sequence above.
+// directly after the field length:
+//
+//   <FIELDNAME:FIELDLEN:T>FIELDVALUE_...<
+//             ^       ^^^
+//             |       ||+ 6 (RAB)
+//             |       |+- 5 (STRING) T
+//             |       +-- 4 (COLON)
+//             +---------- 2 (COLON)
+//
+// In that case, the value starts after token 6 rather than token 4.  We record
+// the type indicator exactly as it appears and leave it to the higher-level
+// parser to interpret it.
 //
 fn adi_parse_data_specifier(aps : &mut AdiParseState) ->
     Result<AdiDataSpecifier, AdifParseError>
 {
     assert_eq!(adi_parse_peek_token(aps, 0).unwrap(), AdiToken::ADI_TOK_LAB);
+    let position = aps.aps_position;
+    let max_fieldlen = aps.aps_options.adpo_max_fieldlen;
 
     let t_fieldname   = adi_parse_peek_token(aps, 1)?;
     let t_colon       = adi_parse_peek_token(aps, 2)?;
     let t_fieldlength = adi_parse_peek_token(aps, 3)?;
     let t_rab         = adi_parse_peek_token(aps, 4)?;
 
-    let fieldname = adi_token_string(&t_fieldname, "parsing data specifier")?;
-    match t_colon {
-        AdiToken::ADI_TOK_COLON => (),
-        _ => {
-            return Err(AdifParseError::ADIF_EBADINPUT(format!(
-                "parsing data specifier (near byte {}): expected {}, \
-                but found {}",
-                aps.aps_bytes_consumed,
-                adi_token_text(&AdiToken::ADI_TOK_COLON),
-                adi_token_text(&t_colon))));
-        }
+    let fieldname = adi_token_string(aps, &t_fieldname, "field name")?;
+    let bad = |kind: AdifInputErrorKind, message: String| {
+        AdifParseError::ADIF_EBADINPUT(AdifInputError::new(kind, message)
+            .with_position(position)
+            .with_field(&fieldname))
     };
 
-    let fieldlength_str = adi_token_string(&t_fieldlength,
-        &format!("parsing data specifier (near byte {}) length",
-        aps.aps_bytes_consumed))?;
-    let fieldlength_result = fieldlength_str.parse::<usize>();
-    let fieldlength = match fieldlength_result {
-        Ok(n) if n <= ADI_MAX_FIELDLEN => n,
+    if t_colon != AdiToken::ADI_TOK_COLON {
+        return Err(bad(AdifInputErrorKind::ADIE_SYNTAX, format!(
+            "expected {}, but found {}",
+            adi_token_text(&AdiToken::ADI_TOK_COLON),
+            adi_token_text(&t_colon))));
+    }
+
+    let fieldlength_str = adi_token_string(aps, &t_fieldlength, "length")
+        .map_err(|_| bad(AdifInputErrorKind::ADIE_SYNTAX, format!(
+            "expected length, but found {}", adi_token_text(&t_fieldlength))))?;
+    let fieldlength = match fieldlength_str.parse::<usize>() {
+        Ok(n) if n <= max_fieldlen => n,
         Ok(_) => {
-            //
-            // This limit is not intrinsic to our approach, but it's intended to
-            // ensure that we fail gracefully if given something that would
-            // otherwise attempt to use lots of memory.
-            //
-            return Err(AdifParseError::ADIF_EBADINPUT(format!(
-                "parsing data specifier (near byte {}): \
-                max supported size is {} bytes",
-                aps.aps_bytes_consumed,
-                ADI_MAX_FIELDLEN)));
+            return Err(bad(AdifInputErrorKind::ADIE_TOO_LONG, format!(
+                "max supported size is {} bytes", max_fieldlen)));
         }
         Err(s) => {
-            return Err(AdifParseError::ADIF_EBADINPUT(format!(
-                "parsing data specifier (near byte {}) length: {}",
-                aps.aps_bytes_consumed, s)));
+            return Err(bad(AdifInputErrorKind::ADIE_SYNTAX, format!(
+                "length \"{}\": {}", fieldlength_str, s)));
         }
     };
 
-    match t_rab {
-        AdiToken::ADI_TOK_RAB => (),
+    let (fieldtype, ntokens) = match t_rab {
+        AdiToken::ADI_TOK_RAB => (None, 5),
         AdiToken::ADI_TOK_COLON => {
-            // TODO
-            return Err(AdifParseError::ADIF_ENOT_YET_IMPLEMENTED(format!(
-                "parsing data specifier (near byte {}): \
-                typed values are not supported", aps.aps_bytes_consumed)));
+            let t_type = adi_parse_peek_token(aps, 5)?;
+            let t_rab = adi_parse_peek_token(aps, 6)?;
+            let fieldtype = adi_token_string(aps, &t_type, "type indicator")
+                .map_err(|_| bad(AdifInputErrorKind::ADIE_SYNTAX, format!(
+                "expected type indicator, but found {}",
+                adi_token_text(&t_type))))?;
+            if fieldtype.is_empty() {
+                return Err(bad(AdifInputErrorKind::ADIE_SYNTAX,
+                    "empty type indicator".to_string()));
+            }
+            if t_rab != AdiToken::ADI_TOK_RAB {
+                return Err(bad(AdifInputErrorKind::ADIE_SYNTAX, format!(
+                    "expected {}, but found {}",
+                    adi_token_text(&AdiToken::ADI_TOK_RAB),
+                    adi_token_text(&t_rab))));
+            }
+            (Some(fieldtype), 7)
         },
         _ => {
-            return Err(AdifParseError::ADIF_EBADINPUT(format!(
-                "parsing data specifier (near byte {}): \
-                expected {}, but found {}", aps.aps_bytes_consumed,
+            return Err(bad(AdifInputErrorKind::ADIE_SYNTAX, format!(
+                "expected {}, but found {}",
                 adi_token_text(&AdiToken::ADI_TOK_RAB),
                 adi_token_text(&t_rab))));
         }
@@ -653,11 +818,13 @@ fn adi_parse_data_specifier(aps : &mut AdiParseState) ->
     // TODO this could be more efficient in the common case that the token
     // contains at least the entire string that we care about.
     //
-    adi_parse_consume_tokens(aps, 5);
+    adi_parse_consume_tokens(aps, ntokens);
     let mut fieldvalue : Vec<u8> = Vec::with_capacity(fieldlength);
     while fieldlength > fieldvalue.len() {
         let t_value = adi_parse_peek_token(aps, 0)?;
-        adi_parse_consume_tokens(aps, 1);
+        if t_value != AdiToken::ADI_TOK_EOF {
+            adi_parse_consume_tokens(aps, 1);
+        }
         match t_value {
             AdiToken::ADI_TOK_COLON => {
                 fieldvalue.push(':' as u8);
@@ -673,10 +840,10 @@ fn adi_parse_data_specifier(aps : &mut AdiParseState) ->
                 fieldvalue.extend(buf[0..nbytes].iter());
             }
             AdiToken::ADI_TOK_EOF => {
-                return Err(AdifParseError::ADIF_EBADINPUT(format!(
-                    "parsing data specifier (near byte {}): \
-                    unexpected {} in value", aps.aps_bytes_consumed,
-                    adi_token_text(&AdiToken::ADI_TOK_EOF))));
+                return Err(bad(AdifInputErrorKind::ADIE_TRUNCATED, format!(
+                    "unexpected {} in value ({} of {} bytes read)",
+                    adi_token_text(&AdiToken::ADI_TOK_EOF), fieldvalue.len(),
+                    fieldlength)));
             }
         }
     }
@@ -694,45 +861,43 @@ fn adi_parse_data_specifier(aps : &mut AdiParseState) ->
         adif_name: fieldname.to_string(), // TODO extra copy?
         adif_length: fieldlength,
         adif_bytes: fieldvalue,
-        adif_type: None
+        adif_type: fieldtype,
+        adif_position: position
     })
 }
 
 //
-// Parse the body of the ADI input (i.e., everything after the header).
-//
-fn adi_parse_records(aps: &mut AdiParseState) ->
-    Result<Vec<AdiRecord>, AdifParseError>
-{
-    let mut records : Vec<AdiRecord> = Vec::new();
-
-    adi_parse_consume_until_lab(aps)?;
-
-    loop {
-        match adi_parse_peek_token(aps, 0)? {
-            AdiToken::ADI_TOK_EOF => {
-                break;
-            },
-            _ => {
-                records.push(adi_parse_record(aps)?);
-            }
-        }
-    }
-
-    return Ok(records);
-}
-
-//
-// Parse a single record from the ADI file, including any trailing bytes.
+// Parse a single record from the ADI file, including any trailing bytes.  In
+// lenient mode, this returns None if the input ends before the end of the
+// record, since that usually means some value's length was wrong and the rest
+// of the record was swallowed into it.
 //
 fn adi_parse_record(aps: &mut AdiParseState) ->
-    Result<AdiRecord, AdifParseError>
+    Result<Option<AdiRecord>, AdifParseError>
 {
     let mut record = AdiRecord {
         adir_fields: vec![]
     };
 
+    aps.aps_nrecords += 1;
+    let which = aps.aps_nrecords;
+    let start = aps.aps_position;
+
     loop {
+        if adi_parse_peek_token(aps, 0)? == AdiToken::ADI_TOK_EOF {
+            let error = AdifInputError::new(AdifInputErrorKind::ADIE_TRUNCATED,
+                format!("unexpected {} in record",
+                adi_token_text(&AdiToken::ADI_TOK_EOF)))
+                .with_position(start)
+                .with_record(which);
+            if !aps.aps_options.adpo_lenient {
+                return Err(AdifParseError::ADIF_EBADINPUT(error));
+            }
+
+            aps.aps_warnings.push(error);
+            return Ok(None);
+        }
+
         let t_lab = adi_parse_peek_token(aps, 0)?;
         let t_fieldname = adi_parse_peek_token(aps, 1)?;
         let t_indicator = adi_parse_peek_token(aps, 2)?;
@@ -747,12 +912,14 @@ fn adi_parse_record(aps: &mut AdiParseState) ->
                 break;
             }
             _ => {
-                record.adir_fields.push(adi_parse_data_specifier(aps)?);
+                if let Some(spec) = adi_parse_field(aps, Some(which))? {
+                    record.adir_fields.push(spec);
+                }
             }
         }
     }
 
-    return Ok(record);
+    Ok(Some(record))
 }
 
 //
@@ -775,6 +942,80 @@ fn adi_parse_consume_until_lab(aps: &mut AdiParseState) ->
     return Ok(());
 }
 
+//
+// ADI Export
+//
+// These functions are used to export an ADI file.  They're the inverse of the
+// import functions above: given the low-level ADI elements, they write out the
+// corresponding bytes.  The output is intended to be read back by adi_parse()
+// without losing any value content.
+//
+
+//
+// Write the given ADI file to the output stream.  If the header content is
+// empty or begins with "<", a reader would mistake the first header data
+// specifier for the start of a record, so we emit a newline first in that case.
+//
+pub fn adi_write(adf: &AdiFile, sink: &mut dyn io::Write) -> io::Result<()>
+{
+    if let Some(ref adh) = adf.adi_header {
+        if let None | Some(b'<') = adh.adih_content.first() {
+            sink.write_all(b"\n")?;
+        }
+        sink.write_all(&adh.adih_content)?;
+        for field in &adh.adih_fields {
+            adi_write_data_specifier(field, sink)?;
+            sink.write_all(b"\n")?;
+        }
+        writeln!(sink, "<{}>", ADI_STR_EOH)?;
+    }
+
+    for rec in &adf.adi_records {
+        for field in &rec.adir_fields {
+            adi_write_data_specifier(field, sink)?;
+            sink.write_all(b" ")?;
+        }
+        writeln!(sink, "<{}>", ADI_STR_EOR)?;
+    }
+
+    Ok(())
+}
+
+//
+// Write a single data specifier.  The length is always computed from the value
+// itself rather than taken from "adif_length", since the two can only disagree
+// if a caller modified the value after parsing it.
+//
+fn adi_write_data_specifier(field: &AdiDataSpecifier, sink: &mut dyn io::Write)
+    -> io::Result<()>
+{
+    //
+    // Field names can't contain any of the characters that delimit a data
+    // specifier, and neither can type indicators.  There's no way to escape
+    // these, so the best we can do is refuse to write them.
+    //
+    let bad_name = field.adif_name.is_empty() ||
+        field.adif_name.chars().any(|c| !c.is_ascii() || c.is_ascii_control() ||
+            c == '<' || c == '>' || c == ':' || c == ',' || c == '{' || c == '}');
+    if bad_name {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!(
+            "cannot write data specifier with field name \"{}\"",
+            field.adif_name)));
+    }
+
+    write!(sink, "<{}:{}", field.adif_name, field.adif_bytes.len())?;
+    if let Some(ref t) = field.adif_type {
+        if t.len() != 1 || !t.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!(
+                "field \"{}\": cannot write type indicator \"{}\"",
+                field.adif_name, t)));
+        }
+        write!(sink, ":{}", t)?;
+    }
+    sink.write_all(b">")?;
+    sink.write_all(&field.adif_bytes)
+}
+
 //
 // Currently, the test module is mostly used for ad hoc tests to exercise the
 // code we have so far.  This is far from exhaustive.
@@ -790,7 +1031,8 @@ mod test {
         let records = vec![];
         return super::AdiFile {
             adi_header: header,
-            adi_records: records
+            adi_records: records,
+            adi_warnings: vec![]
         }
     }
 
@@ -804,7 +1046,8 @@ mod test {
         let records = vec![];
         return super::AdiFile {
             adi_header: Some(header),
-            adi_records: records
+            adi_records: records,
+            adi_warnings: vec![]
         }
     }
 
@@ -819,7 +1062,8 @@ mod test {
                 adif_name_canon: String::from("adif_version"),
                 adif_length: 3,
                 adif_bytes: String::from("1.0").as_bytes().to_vec(),
-                adif_type: None
+                adif_type: None,
+                adif_position: Default::default()
             } ]
         };
         let records = vec![
@@ -830,7 +1074,8 @@ mod test {
                         adif_name_canon: String::from("call"),
                         adif_length: 6,
                         adif_bytes: String::from("KK6ZBI").as_bytes().to_vec(),
-                        adif_type: None
+                        adif_type: None,
+                        adif_position: Default::default()
                     },
 
                     super::AdiDataSpecifier {
@@ -838,7 +1083,8 @@ mod test {
                         adif_name_canon: String::from("qso_date"),
                         adif_length: 8,
                         adif_bytes: String::from("20181129").as_bytes().to_vec(),
-                        adif_type: None
+                        adif_type: None,
+                        adif_position: Default::default()
                     }
                 ]
             },
@@ -849,7 +1095,8 @@ mod test {
                         adif_name_canon: String::from("call"),
                         adif_length: 6,
                         adif_bytes: String::from("KB1HCN").as_bytes().to_vec(),
-                        adif_type: Some(String::from("S"))
+                        adif_type: Some(String::from("S")),
+                        adif_position: Default::default()
                     },
 
                     super::AdiDataSpecifier {
@@ -857,14 +1104,16 @@ mod test {
                         adif_name_canon: String::from("qso_date"),
                         adif_length: 8,
                         adif_bytes: String::from("20181130").as_bytes().to_vec(),
-                        adif_type: None
+                        adif_type: None,
+                        adif_position: Default::default()
                     }
                 ]
             }
         ];
         return super::AdiFile {
             adi_header: Some(header),
-            adi_records: records
+            adi_records: records,
+            adi_warnings: vec![]
         }
     }
 
@@ -951,6 +1200,155 @@ mod test {
         // XXX test something
     }
 
+    #[test]
+    fn write_roundtrip() {
+        let input = r"preamble<foo:3>12345<eoh>
+            <call:6>kk6zbi
+            <comment:9>a<b>c:d e
+            <eor>
+            <call:6>kb1hcn
+            <eor>";
+        let adf = super::adi_parse_string(input).unwrap();
+        let mut output : Vec<u8> = Vec::new();
+        super::adi_write(&adf, &mut output).unwrap();
+
+        let mut reader = io::Cursor::new(output);
+        let reparsed = super::adi_parse(&mut reader,
+            &super::AdifParseOptions::default()).unwrap();
+        assert_eq!(super::adi_dump(&adf), super::adi_dump(&reparsed));
+        assert_eq!(reparsed.adi_records[0].adir_fields[1].adif_bytes,
+            b"a<b>c:d e".to_vec());
+
+        // A header with no free-form content must still be read as a header.
+        let input = "\n<adif_ver:5>3.1.0<eoh><call:4>W1AW<eor>";
+        let mut adf = super::adi_parse_string(input).unwrap();
+        adf.adi_header.as_mut().unwrap().adih_content.clear();
+        let mut output : Vec<u8> = Vec::new();
+        super::adi_write(&adf, &mut output).unwrap();
+        let mut reader = io::Cursor::new(output);
+        let reparsed = super::adi_parse(&mut reader,
+            &super::AdifParseOptions::default()).unwrap();
+        assert_eq!(reparsed.adi_header.unwrap().adih_fields.len(), 1);
+        assert_eq!(reparsed.adi_records.len(), 1);
+    }
+
+    #[test]
+    fn parse_typed() {
+        let adf = super::adi_parse_string(r"header<adif_ver:5:S>3.1.0<eoh>
+            <call:6:s>KK6ZBI<qso_date:8:D>20181129<freq:6:N>14.074
+            <eor>").unwrap();
+        let header = adf.adi_header.unwrap();
+        assert_eq!(header.adih_fields[0].adif_type.as_deref(), Some("S"));
+        let fields = &adf.adi_records[0].adir_fields;
+        assert_eq!(fields[0].adif_type.as_deref(), Some("s"));
+        assert_eq!(fields[0].adif_bytes, b"KK6ZBI".to_vec());
+        assert_eq!(fields[1].adif_type.as_deref(), Some("D"));
+        assert_eq!(fields[1].adif_bytes, b"20181129".to_vec());
+        assert_eq!(fields[2].adif_type.as_deref(), Some("N"));
+        assert_eq!(fields[2].adif_bytes, b"14.074".to_vec());
+
+        // The records in the complex example (one typed) round-trip.
+        let mut adf = make_file_complex();
+        adf.adi_header = None;
+        let mut output : Vec<u8> = Vec::new();
+        super::adi_write(&adf, &mut output).unwrap();
+        let mut reader = io::Cursor::new(output);
+        let reparsed = super::adi_parse(&mut reader,
+            &super::AdifParseOptions::default()).unwrap();
+        assert_eq!(super::adi_dump(&adf), super::adi_dump(&reparsed));
+
+        assert!(super::adi_parse_string("<call:6:>KK6ZBI<eor>").is_err());
+        assert!(super::adi_parse_string("<call:6:S:X>KK6ZBI<eor>").is_err());
+    }
+
+    #[test]
+    fn parse_lenient() {
+        let lenient = super::AdifParseOptions {
+            adpo_lenient: true,
+            ..Default::default()
+        };
+        let parse = |input: &str, options: &super::AdifParseOptions| {
+            super::adi_parse(&mut io::Cursor::new(input), options)
+        };
+
+        // Malformed data specifiers are skipped, with a warning for each.
+        let input = "header<bad:x>1<eoh>\
+            <call:4>W1AW<band:x>20m<mode:2>CW<eor>\
+            <call:4>K1AB<:3>abc<eor>";
+        assert!(parse(input, &Default::default()).is_err());
+        let adf = parse(input, &lenient).unwrap();
+        assert_eq!(adf.adi_header.unwrap().adih_fields.len(), 0);
+        assert_eq!(adf.adi_records.len(), 2);
+        let names : Vec<_> = adf.adi_records[0].adir_fields.iter()
+            .map(|f| f.adif_name.as_str()).collect();
+        assert_eq!(names, vec!["call", "mode"]);
+        assert_eq!(adf.adi_records[0].adir_fields[1].adif_position,
+            super::AdifPosition {
+                adpp_offset: 42,
+                adpp_line: 1,
+                adpp_column: 43
+            });
+        assert_eq!(adf.adi_warnings.len(), 3);
+        assert_eq!(adf.adi_warnings[0].adie_position.unwrap().adpp_offset, 6);
+        assert_eq!(adf.adi_warnings[0].adie_record, None);
+        assert_eq!(adf.adi_warnings[1].adie_position.unwrap().adpp_offset, 31);
+        assert_eq!(adf.adi_warnings[1].adie_record, Some(1));
+        assert_eq!(adf.adi_warnings[2].adie_record, Some(2));
+
+        // A value whose length runs past the end of the input swallows the
+        // rest of its record.
+        let input = "header<eoh><call:4>W1AW<eor><call:40>K1AB<eor>";
+        assert!(parse(input, &Default::default()).is_err());
+        let adf = parse(input, &lenient).unwrap();
+        assert_eq!(adf.adi_records.len(), 1);
+        assert_eq!(adf.adi_warnings.len(), 2);
+        assert_eq!(adf.adi_warnings[1].adie_position.unwrap().adpp_offset, 28);
+        assert_eq!(adf.adi_warnings[1].adie_record, Some(2));
+        assert!(parse("header<eoh><call:4>W1AW", &lenient).unwrap()
+            .adi_records.is_empty());
+
+        // The maximum value length is configurable.
+        let small = super::AdifParseOptions {
+            adpo_max_fieldlen: 3,
+            ..Default::default()
+        };
+        assert!(parse("header<eoh><call:3>W1A<eor>", &small).is_ok());
+        assert!(parse("header<eoh><call:4>W1AW<eor>", &small).is_err());
+    }
+
+    #[test]
+    fn parse_error_locations() {
+        use super::AdifInputErrorKind;
+
+        let error = |input: &str| match super::adi_parse_string(input) {
+            Err(AdifParseError::ADIF_EBADINPUT(e)) => e,
+            _ => panic!("expected input error")
+        };
+
+        let e = error("header<eoh>\n<call:4>W1AW<eor>\n\
+            <call:4>K1AB <band:x>20m<eor>");
+        assert_eq!(e.adie_kind, AdifInputErrorKind::ADIE_SYNTAX);
+        assert_eq!(e.adie_record, Some(2));
+        assert_eq!(e.adie_field.as_deref(), Some("band"));
+        let position = e.adie_position.unwrap();
+        assert_eq!(position.adpp_offset, 43);
+        assert_eq!((position.adpp_line, position.adpp_column), (3, 14));
+        assert_eq!(e.to_string(), "line 3, column 14, record 2, \
+            field \"band\": length \"x\": invalid digit found in string");
+
+        let e = error("header\r\n<eoh>\r\n<notes:40>too short<eor>\r\n");
+        assert_eq!(e.adie_kind, AdifInputErrorKind::ADIE_TRUNCATED);
+        assert_eq!(e.adie_record, Some(1));
+        assert_eq!(e.adie_position.unwrap().adpp_line, 3);
+
+        let e = error("header<call:4>W1AW");
+        assert_eq!(e.adie_kind, AdifInputErrorKind::ADIE_TRUNCATED);
+        assert_eq!(e.adie_record, None);
+
+        let e = error("<call:99999999>W1AW<eor>");
+        assert_eq!(e.adie_kind, AdifInputErrorKind::ADIE_TOO_LONG);
+    }
+
     fn parse_test_string(s : &str) {
         println!("test input:\n{}\n", s);
         test_print(super::adi_parse_string(s));
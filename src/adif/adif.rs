@@ -5,11 +5,32 @@
 // file into a more useful interface for consumers.
 //
 
+use crate::adif::adi::adi_reader;
 use crate::adif::adi::AdiFile;
+use crate::adif::adi::AdiHeader;
+use crate::adif::adi::AdiReader;
+use crate::adif::adi::AdiRecord;
 use crate::adif::adi::AdiDataSpecifier;
+use crate::adif::adifenums::*;
+use crate::adif::adiftypes::adif_value;
+use crate::adif::adiftypes::AdifType;
+use crate::adif::adiftypes::AdifValue;
+use crate::countries::Country;
+use crate::adif::adiftypes::AdifValueError;
+use crate::Grid;
+use super::adifutil;
+use super::AdifCharset;
+use super::AdifDuplicatePolicy;
+use super::AdifInputError;
+use super::AdifInputErrorKind;
 use super::AdifParseError;
+use super::AdifParseOptions;
+use super::AdifParseWarning;
+use chrono::prelude::*;
 use std::collections::BTreeMap;
 use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
 
 // Well-known header fields
 const ADIF_HEADER_ADIF_VER : &'static str = "adif_ver";
@@ -18,6 +39,17 @@ const ADIF_HEADER_PROGRAMID : &'static str = "programid";
 const ADIF_HEADER_PROGRAMVERSION : &'static str = "programversion";
 const ADIF_HEADER_USERDEF : &'static str = "userdef";
 
+// Prefix of application-defined fields (canonical form)
+const ADIF_FIELD_APP_PREFIX : &'static str = "app_";
+
+// Suffix of fields that hold the international version of another field
+const ADIF_FIELD_INTL_SUFFIX : &'static str = "_intl";
+
+// Defaults used for header fields when exporting a file that doesn't have them
+const ADIF_EXPORT_ADIF_VER : &'static str = "3.1.4";
+const ADIF_EXPORT_PROGRAMID : &'static str = "ham-rs";
+const ADIF_EXPORT_PROGRAMVERSION : &'static str = env!("CARGO_PKG_VERSION");
+
 pub struct AdifFile {
     // Well-known header fields
     pub adif_adif_version : Option<String>,     // XXX semver type?
@@ -28,11 +60,80 @@ pub struct AdifFile {
     // Metadata
     pub adif_label : String,    // label for this file (e.g., filename)
 
-    // XXX map of application-defined field metadata and values?
-    // XXX map of user-defined field metadata and values?
+    // User-defined fields declared in the header ("USERDEFn"), in the order
+    // they were declared.  Application-defined fields ("APP_...") have no
+    // declarations; their values are stored in each record like any other
+    // field and can be grouped with AdifRecord::app_fields().
+    pub adif_userdefs : Vec<AdifUserDef>,
 
     // File contents
     pub adif_records : Vec<AdifRecord>,     // list of records in the file
+
+    // Input that was skipped because it couldn't be parsed.  This is only ever
+    // non-empty when the file was parsed in lenient mode.
+    pub adif_warnings : Vec<AdifParseWarning>,
+}
+
+//
+// AdifUserDef: the declaration of a user-defined field.  In ADI, these appear
+// in the header as, e.g.,
+//
+//     <USERDEF1:3:N>EPC
+//     <USERDEF2:19:E>SWEATERSIZE,{S,M,L}
+//     <USERDEF3:15:N>SHOESIZE,{5:20}
+//
+// The type indicator is required.  A declaration may restrict the field's
+// values to an enumeration or (for numbers) an inclusive range.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdifUserDef {
+    pub adud_id : u32,                      // "n" in "USERDEFn"
+    pub adud_name : String,                 // field name, as declared
+    pub adud_type : AdifType,               // declared type
+    pub adud_enum : Option<Vec<String>>,    // allowed values, if restricted
+    pub adud_range : Option<(f64, f64)>     // allowed range, if restricted
+}
+
+impl AdifUserDef {
+    //
+    // Check a value of this field against the declaration.  Enumerated values
+    // are compared case-insensitively.
+    //
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        let converted = match adif_value(&self.adud_name, self.adud_type, value) {
+            Ok(v) => v,
+            Err(_) => return Err(format!("\"{}\" is not a valid {}", value,
+                self.adud_type))
+        };
+
+        if let Some(ref values) = self.adud_enum {
+            if !values.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+                return Err(format!(
+                    "\"{}\" is not one of the declared values {{{}}}",
+                    value, values.join(",")));
+            }
+        }
+
+        if let (Some((min, max)), AdifValue::Number(n)) = (self.adud_range, converted) {
+            if n < min || n > max {
+                return Err(format!(
+                    "{} is outside the declared range {{{}:{}}}", value, min, max));
+            }
+        }
+
+        Ok(())
+    }
+
+    //
+    // Returns the value of the "USERDEFn" header field for this declaration.
+    //
+    fn declaration(&self) -> String {
+        match (&self.adud_enum, self.adud_range) {
+            (Some(values), _) => format!("{},{{{}}}", self.adud_name, values.join(",")),
+            (None, Some((min, max))) => format!("{},{{{}:{}}}", self.adud_name, min, max),
+            (None, None) => self.adud_name.clone()
+        }
+    }
 }
 
 #[allow(non_camel_case_types)]
@@ -59,7 +160,11 @@ impl fmt::Debug for AdifFile {
                 Some(v) => format!("version \"{}\"", v),
                 None => String::from("unknown version")
             })?;
-        write!(f, "Total records: {}\n", self.adif_records.len())
+        writeln!(f, "Total records: {}", self.adif_records.len())?;
+        if !self.adif_warnings.is_empty() {
+            writeln!(f, "Warnings: {}", self.adif_warnings.len())?;
+        }
+        Ok(())
     }
 }
 
@@ -122,8 +227,305 @@ fn adif_dump_one(rec : &AdifRecord, filterspec: &Option<Vec<(String, String)>>,
     print!("\n");
 }
 
+//
+// AdifRecord: a single record.  Values are stored as text, exactly as they
+// appeared in the file, keyed by canonical field name.  The typed accessors
+// below convert a value to its ADIF data type on demand.  That type comes from
+// the data specifier's type indicator if one was given ("adir_field_types"),
+// and otherwise from the field name.
+//
+// When a file is parsed with AdifCharset::ADC_RAW, values that aren't valid
+// UTF-8 are stored in "adir_field_values" with the invalid bytes replaced by
+// U+FFFD, and the original bytes are kept in "adir_field_bytes" so that they
+// can be written back out unchanged.
+//
 pub struct AdifRecord {
-    pub adir_field_values : BTreeMap<String, String> // XXX value type?
+    pub adir_field_values : BTreeMap<String, String>,
+    pub adir_field_types : BTreeMap<String, AdifType>,  // explicit types only
+    pub adir_field_bytes : BTreeMap<String, Vec<u8>>    // undecodable values
+}
+
+impl AdifRecord {
+    pub fn new() -> AdifRecord {
+        AdifRecord {
+            adir_field_values: BTreeMap::new(),
+            adir_field_types: BTreeMap::new(),
+            adir_field_bytes: BTreeMap::new()
+        }
+    }
+
+    //
+    // Builds a record from (field name, value) pairs, as if they had been
+    // read from a file without explicit types.
+    //
+    pub fn from_pairs(pairs: &[(&str, &str)]) -> AdifRecord {
+        let mut record = AdifRecord::new();
+        for (name, value) in pairs {
+            record.adir_field_values.insert(name.to_lowercase(), value.to_string());
+        }
+        record
+    }
+
+    //
+    // Returns the named field's value exactly as it appeared in the file.
+    //
+    pub fn value_bytes(&self, name: &str) -> Option<&[u8]> {
+        let name = name.to_lowercase();
+        match self.adir_field_bytes.get(&name) {
+            Some(bytes) => Some(bytes),
+            None => self.adir_field_values.get(&name).map(|v| v.as_bytes())
+        }
+    }
+
+    //
+    // Replace the value of each field that has an "_INTL" counterpart (e.g.,
+    // "NAME" and "NAME_INTL") with the counterpart's value, and remove the
+    // "_INTL" field.  The base field takes on the Intl data type so that its
+    // value is still interpreted correctly.
+    //
+    pub fn map_intl_fields(&mut self) {
+        let names : Vec<String> = self.adir_field_values.keys()
+            .filter(|name| name.len() > ADIF_FIELD_INTL_SUFFIX.len() &&
+                name.ends_with(ADIF_FIELD_INTL_SUFFIX))
+            .cloned()
+            .collect();
+
+        for name in names {
+            let base = &name[..name.len() - ADIF_FIELD_INTL_SUFFIX.len()];
+            let adif_type = match self.field_type(&name) {
+                Some(t @ AdifType::IntlMultilineString) => t,
+                _ => AdifType::IntlString
+            };
+
+            self.adir_field_types.remove(&name);
+            self.adir_field_types.insert(base.to_string(), adif_type);
+            match self.adir_field_bytes.remove(&name) {
+                Some(bytes) => {
+                    self.adir_field_bytes.insert(base.to_string(), bytes);
+                },
+                None => {
+                    self.adir_field_bytes.remove(base);
+                }
+            }
+            if let Some(value) = self.adir_field_values.remove(&name) {
+                self.adir_field_values.insert(base.to_string(), value);
+            }
+        }
+    }
+
+    //
+    // Returns the data type of the named field, or None if there's neither a
+    // type indicator nor a type defined by ADIF for it.
+    //
+    pub fn field_type(&self, name: &str) -> Option<AdifType> {
+        let name = name.to_lowercase();
+        self.adir_field_types.get(&name).copied()
+            .or_else(|| AdifType::for_field(&name))
+    }
+
+    //
+    // Returns the named field's value converted according to its data type.
+    // Fields with no known type are treated as strings.
+    //
+    pub fn value(&self, name: &str) -> Result<Option<AdifValue>, AdifValueError> {
+        let adif_type = self.field_type(name).unwrap_or(AdifType::String);
+        self.typed(name, adif_type)
+    }
+
+    pub fn boolean(&self, name: &str) -> Result<Option<bool>, AdifValueError> {
+        match self.typed(name, AdifType::Boolean)? {
+            Some(AdifValue::Boolean(b)) => Ok(Some(b)),
+            _ => Ok(None)
+        }
+    }
+
+    pub fn date(&self, name: &str) -> Result<Option<NaiveDate>, AdifValueError> {
+        match self.typed(name, AdifType::Date)? {
+            Some(AdifValue::Date(d)) => Ok(Some(d)),
+            _ => Ok(None)
+        }
+    }
+
+    pub fn time(&self, name: &str) -> Result<Option<NaiveTime>, AdifValueError> {
+        match self.typed(name, AdifType::Time)? {
+            Some(AdifValue::Time(t)) => Ok(Some(t)),
+            _ => Ok(None)
+        }
+    }
+
+    pub fn number(&self, name: &str) -> Result<Option<f64>, AdifValueError> {
+        match self.typed(name, AdifType::Number)? {
+            Some(AdifValue::Number(n)) => Ok(Some(n)),
+            _ => Ok(None)
+        }
+    }
+
+    pub fn integer(&self, name: &str) -> Result<Option<i64>, AdifValueError> {
+        match self.typed(name, AdifType::Integer)? {
+            Some(AdifValue::Integer(n)) => Ok(Some(n)),
+            _ => Ok(None)
+        }
+    }
+
+    pub fn location(&self, name: &str) -> Result<Option<f64>, AdifValueError> {
+        match self.typed(name, AdifType::Location)? {
+            Some(AdifValue::Location(l)) => Ok(Some(l)),
+            _ => Ok(None)
+        }
+    }
+
+    pub fn gridsquare(&self, name: &str) -> Result<Option<Grid>, AdifValueError> {
+        match self.typed(name, AdifType::GridSquare)? {
+            Some(AdifValue::GridSquare(g)) => Ok(Some(g)),
+            _ => Ok(None)
+        }
+    }
+
+    //
+    // Returns the application-defined fields in this record, grouped by
+    // program id.  A field called "APP_N1MM_EXCHANGE1" appears as field
+    // "exchange1" for program "n1mm".  (Program ids can't contain underscores,
+    // so the first underscore after "APP_" ends the program id.)
+    //
+    pub fn app_fields(&self) -> BTreeMap<String, BTreeMap<String, String>> {
+        let mut programs : BTreeMap<String, BTreeMap<String, String>> =
+            BTreeMap::new();
+
+        for (name, value) in &self.adir_field_values {
+            let rest = match name.strip_prefix(ADIF_FIELD_APP_PREFIX) {
+                Some(rest) => rest,
+                None => continue
+            };
+            if let Some(i) = rest.find('_') {
+                if i > 0 && i + 1 < rest.len() {
+                    programs.entry(rest[..i].to_string()).or_default()
+                        .insert(rest[i + 1..].to_string(), value.clone());
+                }
+            }
+        }
+
+        programs
+    }
+
+    //
+    // Check the values of fields whose type is an ADIF enumeration (MODE, BAND,
+    // QSL_RCVD, and so on).  Besides checking each value on its own, this
+    // checks that SUBMODE belongs to MODE, that FREQ and FREQ_RX fall within
+    // BAND and BAND_RX, that DXCC and MY_DXCC are entity codes, and that STATE
+    // and MY_STATE are subdivisions of DXCC and MY_DXCC (for the entities in
+    // ADIF_SUBDIVISION_ENTITIES).  CONTEST_ID isn't
+    // checked because ADIF allows values outside its enumeration.  This returns
+    // one error for each invalid value.
+    //
+    pub fn validate_enumerations(&self) -> Vec<AdifInputError> {
+        let mut errors : Vec<AdifInputError> = Vec::new();
+
+        let mode = match self.adir_field_values.get("mode") {
+            None => None,
+            Some(value) => match AdifMode::from_import(value) {
+                Ok((mode, _)) => Some(mode),
+                Err(error) => {
+                    errors.push(adif_enum_error("mode", error.to_string()));
+                    None
+                }
+            }
+        };
+        let submode = adif_enum_field::<AdifSubmode>(self, "submode", &mut errors);
+        if let (Some(mode), Some(submode)) = (mode, submode) {
+            if submode.mode() != mode {
+                errors.push(adif_enum_error("submode", format!(
+                    "submode \"{}\" does not belong to mode \"{}\"",
+                    submode, mode)));
+            }
+        }
+
+        for (band_field, freq_field) in &[("band", "freq"), ("band_rx", "freq_rx")] {
+            let band = adif_enum_field::<AdifBand>(self, band_field, &mut errors);
+            if let (Some(band), Ok(Some(freq))) = (band, self.number(freq_field)) {
+                if !band.contains(freq) {
+                    errors.push(adif_enum_error(freq_field, format!(
+                        "{} MHz is not within band \"{}\"", freq, band)));
+                }
+            }
+        }
+
+        adif_enum_field::<AdifPropagationMode>(self, "prop_mode", &mut errors);
+        adif_enum_field::<AdifAntPath>(self, "ant_path", &mut errors);
+        adif_enum_field::<AdifContinent>(self, "cont", &mut errors);
+        adif_enum_field::<AdifRegion>(self, "region", &mut errors);
+        for name in &["qsl_rcvd", "lotw_qsl_rcvd", "eqsl_qsl_rcvd"] {
+            adif_enum_field::<AdifQslRcvd>(self, name, &mut errors);
+        }
+        for name in &["qsl_sent", "lotw_qsl_sent", "eqsl_qsl_sent"] {
+            adif_enum_field::<AdifQslSent>(self, name, &mut errors);
+        }
+        for name in &["qsl_rcvd_via", "qsl_sent_via"] {
+            adif_enum_field::<AdifQslVia>(self, name, &mut errors);
+        }
+        for name in &["arrl_sect", "my_arrl_sect"] {
+            adif_enum_field::<AdifArrlSection>(self, name, &mut errors);
+        }
+
+        for name in &["dxcc", "my_dxcc"] {
+            match self.integer(name) {
+                Ok(Some(0)) | Ok(None) | Err(_) => {},
+                Ok(Some(dxcc)) => if dxcc < 0 || Country::from_dxcc(dxcc as u32).is_none() {
+                    errors.push(adif_enum_error(name, format!(
+                        "\"{}\" is not a valid DXCC_Entity_Code value", dxcc)));
+                }
+            }
+        }
+
+        for (state_field, dxcc_field) in &[("state", "dxcc"), ("my_state", "my_dxcc")] {
+            let state = match self.adir_field_values.get(*state_field) {
+                Some(state) => state,
+                None => continue
+            };
+            let dxcc = match self.integer(dxcc_field) {
+                Ok(Some(dxcc)) if dxcc >= 0 => dxcc as u32,
+                _ => continue
+            };
+            if ADIF_SUBDIVISION_ENTITIES.contains(&dxcc) &&
+                adif_subdivision(dxcc, state).is_none() {
+                errors.push(adif_enum_error(state_field, format!(
+                    "\"{}\" is not a valid Primary_Administrative_Subdivision \
+                    value for DXCC entity {}", state, dxcc)));
+            }
+        }
+
+        //
+        // Credit lists look like "DXCC_BAND:CARD&LOTW,WAS": only the awards
+        // (not the QSL media) are checked here.
+        //
+        for name in &["credit_submitted", "credit_granted"] {
+            if let Some(value) = self.adir_field_values.get(*name) {
+                for credit in value.split(',') {
+                    let award = credit.split(':').next().unwrap_or("").trim();
+                    if let Err(error) = award.parse::<AdifCredit>() {
+                        errors.push(adif_enum_error(name, error.to_string()));
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
+    fn typed(&self, name: &str, adif_type: AdifType) ->
+        Result<Option<AdifValue>, AdifValueError>
+    {
+        let name = name.to_lowercase();
+        match self.adir_field_values.get(&name) {
+            None => Ok(None),
+            Some(value) => adif_value(&name, adif_type, value).map(Some)
+        }
+    }
+}
+
+impl Default for AdifRecord {
+    fn default() -> Self {
+        AdifRecord::new()
+    }
 }
 
 impl fmt::Debug for AdifRecord {
@@ -138,8 +540,62 @@ impl fmt::Debug for AdifRecord {
     }
 }
 
-// TODO Would this be better off accepting an iterator?
-pub fn adif_parse_adi(label: &str, adi: &AdiFile) ->
+pub fn adif_parse_adi(label: &str, adi: &AdiFile, options: &AdifParseOptions)
+    -> Result<AdifFile, AdifParseError>
+{
+    let mut warnings = adi.adi_warnings.clone();
+    let mut adif = adif_parse_adi_header(label, adi.adi_header.as_ref(),
+        options, &mut warnings)?;
+    adif.adif_records.reserve(adi.adi_records.len());
+
+    for (i, adr) in adi.adi_records.iter().enumerate() {
+        adif.adif_records.push(adif_parse_adi_record(i + 1, adr, options,
+            &mut warnings)?);
+    }
+
+    adif.adif_warnings.append(&mut warnings);
+    Ok(adif)
+}
+
+//
+// Add the record number "which" (if any) to an error about a field.  Then, in
+// lenient mode, record the error as a warning so that the caller can skip the
+// field.  Otherwise, just return the error.
+//
+fn adif_recover(error: AdifParseError, which: Option<usize>,
+    options: &AdifParseOptions, warnings: &mut Vec<AdifParseWarning>) ->
+    Result<(), AdifParseError>
+{
+    let error = match (error, which) {
+        (AdifParseError::ADIF_EBADINPUT(e), Some(which)) => e.with_record(which),
+        (AdifParseError::ADIF_EBADINPUT(e), None) => e,
+        (e, _) => return Err(e)
+    };
+
+    if options.adpo_lenient {
+        warnings.push(error);
+        Ok(())
+    } else {
+        Err(AdifParseError::ADIF_EBADINPUT(error))
+    }
+}
+
+//
+// Returns an error about the value of field "adf".
+//
+fn adif_field_error(adf: &AdiDataSpecifier, kind: AdifInputErrorKind,
+    message: String) -> AdifParseError
+{
+    AdifParseError::ADIF_EBADINPUT(AdifInputError::new(kind, message)
+        .with_position(adf.adif_position)
+        .with_field(&adf.adif_name))
+}
+
+//
+// Interpret the header of an ADI file.  The returned AdifFile has no records.
+//
+fn adif_parse_adi_header(label: &str, adih: Option<&AdiHeader>,
+    options: &AdifParseOptions, warnings: &mut Vec<AdifParseWarning>) ->
     Result<AdifFile, AdifParseError>
 {
     let mut adif = AdifFile {
@@ -148,77 +604,874 @@ pub fn adif_parse_adi(label: &str, adi: &AdiFile) ->
         adif_program_version: None,
         adif_created_timestamp: None,
         adif_label: String::from(label), // XXX clone needed?
-        adif_records: Vec::with_capacity(adi.adi_records.len()),
+        adif_userdefs: Vec::new(),
+        adif_records: Vec::new(),
+        adif_warnings: Vec::new(),
     };
 
-    if let Some(ref adih) = adi.adi_header {
+    if let Some(adih) = adih {
         // TODO can this be made table-based?
         for adf in &adih.adih_fields {
-            if adf.adif_name_canon == ADIF_HEADER_ADIF_VER {
-                adif.adif_adif_version = Some(adif_string(&adf)?);
+            let string = || adif_decode(adf, options).map(|(s, _)| s);
+            let result = if adf.adif_name_canon == ADIF_HEADER_ADIF_VER {
+                string().map(|s| adif.adif_adif_version = Some(s))
             } else if adf.adif_name_canon == ADIF_HEADER_PROGRAMID {
-                adif.adif_program_id = Some(adif_string(&adf)?);
+                string().map(|s| adif.adif_program_id = Some(s))
             } else if adf.adif_name_canon == ADIF_HEADER_PROGRAMVERSION {
-                adif.adif_program_version = Some(adif_string(&adf)?);
+                string().map(|s| adif.adif_program_version = Some(s))
             } else if adf.adif_name_canon == ADIF_HEADER_CREATED_TIMESTAMP {
-                adif.adif_created_timestamp = Some(adif_string(&adf)?);
+                string().map(|s| adif.adif_created_timestamp = Some(s))
+            } else if let Some(id) = adif_userdef_id(adf) {
+                adif_parse_userdef(id, adf, options)
+                    .map(|u| adif.adif_userdefs.push(u))
+            } else {
+                Ok(())
+            };
+
+            if let Err(e) = result {
+                adif_recover(e, None, options, warnings)?;
             }
         }
     }
 
-    let mut which = 1;
-    for adr in &adi.adi_records {
-        let mut record_values : BTreeMap<String, String> = BTreeMap::new();
+    Ok(adif)
+}
+
+//
+// If this header field is a "USERDEFn" declaration, return "n".
+//
+fn adif_userdef_id(adf: &AdiDataSpecifier) -> Option<u32>
+{
+    adf.adif_name_canon.strip_prefix(ADIF_HEADER_USERDEF)
+        .and_then(|id| id.parse().ok())
+}
+
+//
+// Parse the value of a "USERDEFn" header field: the field name, optionally
+// followed by a comma and either an enumeration ("{A,B,C}") or a range
+// ("{min:max}").
+//
+fn adif_parse_userdef(id: u32, adf: &AdiDataSpecifier,
+    options: &AdifParseOptions) -> Result<AdifUserDef, AdifParseError>
+{
+    let bad = |message: &str| adif_field_error(adf,
+        AdifInputErrorKind::ADIE_BAD_USERDEF, message.to_string());
+
+    let adif_type = match adif_type(adf)? {
+        Some(t) => t,
+        None => return Err(bad("user-defined field has no type indicator"))
+    };
+
+    let (value, _) = adif_decode(adf, options)?;
+    let (name, restriction) = match value.find(',') {
+        Some(i) => (value[..i].trim(), Some(value[i + 1..].trim())),
+        None => (value.trim(), None)
+    };
+
+    if name.is_empty() {
+        return Err(bad("user-defined field has no name"));
+    }
+
+    let mut userdef = AdifUserDef {
+        adud_id: id,
+        adud_name: name.to_string(),
+        adud_type: adif_type,
+        adud_enum: None,
+        adud_range: None
+    };
 
-        for adf in &adr.adir_fields {
-            // TODO presumably this is not legal ADIF?
-            if record_values.contains_key(&adf.adif_name_canon) {
-                return Err(AdifParseError::ADIF_EBADINPUT(format!(
-                    "record {}: duplicate value for field \"{}\"", which,
-                    adf.adif_name_canon)));
+    if let Some(restriction) = restriction {
+        let inner = match restriction.strip_prefix('{')
+            .and_then(|r| r.strip_suffix('}')) {
+            Some(inner) => inner,
+            None => return Err(bad("expected \"{\" and \"}\" around \
+                enumeration or range"))
+        };
+
+        if let Some(i) = inner.find(':') {
+            let min = inner[..i].trim().parse::<f64>();
+            let max = inner[i + 1..].trim().parse::<f64>();
+            match (min, max) {
+                (Ok(min), Ok(max)) if min <= max => {
+                    userdef.adud_range = Some((min, max));
+                },
+                _ => return Err(bad("invalid range"))
             }
+        } else {
+            userdef.adud_enum = Some(inner.split(',')
+                .map(|v| v.trim().to_string())
+                .collect());
+        }
+    }
+
+    Ok(userdef)
+}
 
-            let value = adif_string(&adf)?;
-            record_values.insert(adf.adif_name_canon.clone(), value);
+//
+// Check each record's values for user-defined fields against the declarations
+// in the header.  This returns one error for each invalid value.
+//
+pub fn adif_validate_userdefs(adif: &AdifFile) -> Vec<AdifParseError>
+{
+    let mut errors : Vec<AdifParseError> = Vec::new();
+
+    for (i, rec) in adif.adif_records.iter().enumerate() {
+        for userdef in &adif.adif_userdefs {
+            let name = userdef.adud_name.to_lowercase();
+            if let Some(value) = rec.adir_field_values.get(&name) {
+                if let Err(message) = userdef.validate(value) {
+                    errors.push(AdifParseError::ADIF_EBADINPUT(
+                        AdifInputError::new(AdifInputErrorKind::ADIE_BAD_VALUE,
+                        message)
+                        .with_record(i + 1)
+                        .with_field(&userdef.adud_name)));
+                }
+            }
         }
+    }
 
-        which += 1;
-        adif.adif_records.push(AdifRecord {
-            adir_field_values : record_values
-        });
+    errors
+}
+
+//
+// Check each record's enumerated values (see
+// AdifRecord::validate_enumerations()).  This returns one error for each
+// invalid value.
+//
+pub fn adif_validate_enumerations(adif: &AdifFile) -> Vec<AdifParseError>
+{
+    let mut errors : Vec<AdifParseError> = Vec::new();
+
+    for (i, rec) in adif.adif_records.iter().enumerate() {
+        for error in rec.validate_enumerations() {
+            errors.push(AdifParseError::ADIF_EBADINPUT(error.with_record(i + 1)));
+        }
     }
 
-    Ok(adif)
+    errors
+}
+
+//
+// Parse the value of the named field as a member of an ADIF enumeration.  If
+// the value is invalid, this records an error and returns None.
+//
+fn adif_enum_field<T>(rec: &AdifRecord, name: &str,
+    errors: &mut Vec<AdifInputError>) -> Option<T>
+where
+    T: FromStr<Err = AdifEnumError>,
+{
+    let value = rec.adir_field_values.get(name)?;
+    match value.parse::<T>() {
+        Ok(parsed) => Some(parsed),
+        Err(error) => {
+            errors.push(adif_enum_error(name, error.to_string()));
+            None
+        }
+    }
+}
+
+fn adif_enum_error(name: &str, message: String) -> AdifInputError
+{
+    AdifInputError::new(AdifInputErrorKind::ADIE_BAD_VALUE, message)
+        .with_field(name)
+}
+
+//
+// Interpret a single ADI record.  "which" is the 1-based index of the record in
+// the file, used for error messages.  Fields that appear more than once are
+// handled according to "adpo_duplicates".  In lenient mode, fields that can't
+// be interpreted are skipped.
+//
+fn adif_parse_adi_record(which: usize, adr: &AdiRecord,
+    options: &AdifParseOptions, warnings: &mut Vec<AdifParseWarning>) ->
+    Result<AdifRecord, AdifParseError>
+{
+    let mut record = AdifRecord::new();
+
+    for adf in &adr.adir_fields {
+        if record.adir_field_values.contains_key(&adf.adif_name_canon) {
+            match options.adpo_duplicates {
+                AdifDuplicatePolicy::ADP_KEEP_FIRST => continue,
+                AdifDuplicatePolicy::ADP_KEEP_LAST => (),
+                AdifDuplicatePolicy::ADP_ERROR => {
+                    let error = adif_field_error(adf,
+                        AdifInputErrorKind::ADIE_DUPLICATE,
+                        "duplicate value for field".to_string());
+                    adif_recover(error, Some(which), options, warnings)?;
+                    continue;
+                }
+            }
+        }
+
+        let (value, bytes, adif_type) = match adif_decode(adf, options)
+            .and_then(|(value, bytes)| Ok((value, bytes, adif_type(adf)?))) {
+            Ok(result) => result,
+            Err(e) => {
+                adif_recover(e, Some(which), options, warnings)?;
+                continue;
+            }
+        };
+
+        match adif_type {
+            Some(adif_type) => {
+                record.adir_field_types.insert(adf.adif_name_canon.clone(),
+                    adif_type);
+            },
+            None => {
+                record.adir_field_types.remove(&adf.adif_name_canon);
+            }
+        }
+        match bytes {
+            Some(bytes) => {
+                record.adir_field_bytes.insert(adf.adif_name_canon.clone(),
+                    bytes);
+            },
+            None => {
+                record.adir_field_bytes.remove(&adf.adif_name_canon);
+            }
+        }
+        record.adir_field_values.insert(adf.adif_name_canon.clone(), value);
+    }
+
+    if options.adpo_intl_to_base {
+        record.map_intl_fields();
+    }
+
+    Ok(record)
 }
 
 //
-// Given a data specifier describing a string-valued field, return a new String
-// containing the field's contents.  This returns an error if the field is not
-// string-valued or the value cannot be processed as UTF-8.
+// AdifReader: an iterator over the records of an ADI file that parses one
+// record at a time, so memory use doesn't depend on the size of the file.  The
+// header fields are available in "adrd_header" as soon as the reader is
+// created.  Its "adif_records" is always empty.
+//
+// A record that's well-formed but can't be interpreted (e.g., because it has a
+// duplicate field) is returned as an error, and iteration continues with the
+// next record.  Malformed input ends the iteration, as with AdiReader.  In
+// lenient mode, both kinds of problems are skipped instead, and the warnings
+// are available from take_warnings().  The header's warnings are stored in
+// "adrd_header" like any other AdifFile's.
 //
-fn adif_string(adf: &AdiDataSpecifier) ->
-    Result<String, AdifParseError>
+pub struct AdifReader<'a> {
+    pub adrd_header : AdifFile,         // header fields (no records)
+    adrd_adi : AdiReader<'a>,           // underlying ADI reader
+    adrd_options : AdifParseOptions,    // options for interpreting records
+    adrd_warnings : Vec<AdifParseWarning>,  // record warnings not yet taken
+    adrd_which : usize                  // number of records read so far
+}
+
+pub fn adif_reader<'a, R: BufRead + 'a>(label: &str, source: R) ->
+    Result<AdifReader<'a>, AdifParseError>
+{
+    adif_reader_with_options(label, source, &AdifParseOptions::default())
+}
+
+pub fn adif_reader_with_options<'a, R: BufRead + 'a>(label: &str, source: R,
+    options: &AdifParseOptions) -> Result<AdifReader<'a>, AdifParseError>
 {
+    let mut adi = adi_reader(source, options)?;
+    let mut warnings = adi.take_warnings();
+    let mut header = adif_parse_adi_header(label, adi.adr_header.as_ref(),
+        options, &mut warnings)?;
+    header.adif_warnings = warnings;
+
+    Ok(AdifReader {
+        adrd_header: header,
+        adrd_adi: adi,
+        adrd_options: options.clone(),
+        adrd_warnings: Vec::new(),
+        adrd_which: 0
+    })
+}
+
+impl<'a> AdifReader<'a> {
     //
-    // XXX We need to check the type specified with data specifier, but this
-    // doesn't seem like the right way to do it.  Is it case-sensitive?  Are
-    // there other string types?
+    // Returns the warnings about records recorded since the last call.
     //
-    if let Some(ref typestr) = adf.adif_type {
-        if typestr != "S" {
-            return Err(AdifParseError::ADIF_EBADINPUT(format!(
-                "field \"{}\": expected string value, but found type \"{}\"",
-                adf.adif_name, typestr)))
+    pub fn take_warnings(&mut self) -> Vec<AdifParseWarning> {
+        let mut warnings = self.adrd_adi.take_warnings();
+        warnings.append(&mut self.adrd_warnings);
+        warnings.sort_by_key(|w| w.adie_position.map(|p| p.adpp_offset));
+        warnings
+    }
+}
+
+impl<'a> Iterator for AdifReader<'a> {
+    type Item = Result<AdifRecord, AdifParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let adr = match self.adrd_adi.next()? {
+            Ok(adr) => adr,
+            Err(e) => return Some(Err(e))
+        };
+
+        self.adrd_which += 1;
+        Some(adif_parse_adi_record(self.adrd_which, &adr, &self.adrd_options,
+            &mut self.adrd_warnings))
+    }
+}
+
+//
+// Given a data specifier, return the data type given by its type indicator, if
+// it has one.  This returns an error if the indicator is not one defined by
+// ADIF.
+//
+fn adif_type(adf: &AdiDataSpecifier) ->
+    Result<Option<AdifType>, AdifParseError>
+{
+    match adf.adif_type {
+        None => Ok(None),
+        Some(ref typestr) => match AdifType::from_indicator(typestr) {
+            Some(t) => Ok(Some(t)),
+            None => Err(adif_field_error(adf, AdifInputErrorKind::ADIE_BAD_TYPE,
+                format!("unknown data type indicator \"{}\"", typestr)))
+        }
+    }
+}
+
+//
+// Given a data specifier, return a new String containing the field's contents,
+// decoded according to "adpo_charset".  Every ADIF data type has a textual
+// representation, so this works for all of them; interpreting the text is up
+// to the consumer.  With ADC_RAW, values that aren't valid UTF-8 are decoded
+// lossily, and the original bytes are returned as well.  This returns an error
+// if the type indicator is not one defined by ADIF or the value cannot be
+// decoded.
+//
+fn adif_decode(adf: &AdiDataSpecifier, options: &AdifParseOptions) ->
+    Result<(String, Option<Vec<u8>>), AdifParseError>
+{
+    let adif_type = adif_type(adf)?
+        .or_else(|| AdifType::for_field(&adf.adif_name_canon));
+    let intl = matches!(adif_type,
+        Some(AdifType::IntlString) | Some(AdifType::IntlMultilineString));
+
+    if options.adpo_charset == AdifCharset::ADC_ASCII && !intl {
+        if let Some(i) = adf.adif_bytes.iter().position(|b| !b.is_ascii()) {
+            return Err(adif_field_error(adf, AdifInputErrorKind::ADIE_ENCODING,
+                format!("expected ASCII value, but found byte 0x{:x} \
+                (at byte {} of the value)", adf.adif_bytes[i], i)));
         }
     }
 
-    // TODO is there a better pattern for the error handling pattern?
     // TODO extra copy
-    match String::from_utf8(adf.adif_bytes.clone()) {
-        Ok(s) => Ok(s),
-        // TODO is there more useful information in this error?
-        Err(_) => Err(AdifParseError::ADIF_EBADINPUT(format!(
-                "field \"{}\": value contained invalid bytes for UTF-8 string",
-                adf.adif_name)))
-    }
-}
\ No newline at end of file
+    let error = match String::from_utf8(adf.adif_bytes.clone()) {
+        Ok(s) => return Ok((s, None)),
+        Err(e) => e
+    };
+
+    match options.adpo_charset {
+        AdifCharset::ADC_LATIN1 => {
+            Ok((adifutil::cp1252_decode(&adf.adif_bytes), None))
+        },
+        AdifCharset::ADC_RAW => {
+            let value = String::from_utf8_lossy(&adf.adif_bytes).into_owned();
+            Ok((value, Some(error.into_bytes())))
+        },
+        _ => Err(adif_field_error(adf, AdifInputErrorKind::ADIE_ENCODING,
+                format!("value contained invalid bytes for UTF-8 string \
+                (at byte {} of the value)", error.utf8_error().valid_up_to())))
+    }
+}
+
+//
+// Convert the logical representation of an ADIF file back into the low-level
+// ADI elements.  This is the inverse of adif_parse_adi().  Header fields that
+// were present in the original file are preserved, and missing ones are filled
+// in so that the exported file always identifies its version, creator, and
+// creation time.  The header text is a fixed line naming this library rather
+// than the file's label, which could contain a "<" and so be read back as a
+// field or even a record.
+//
+// The Intl data types and the "_INTL" fields are only allowed in ADX files.
+// Unless "intl" is true, the Intl types are downgraded to their ASCII
+// counterparts and the "_INTL" fields are left out.
+//
+pub fn adif_to_adi(adif: &AdifFile, intl: bool) -> AdiFile
+{
+    let indicator = |adif_type: &AdifType| if intl {
+        adif_type.indicator()
+    } else {
+        adif_type.adi_indicator()
+    };
+    let created = Utc::now().format("%Y%m%d %H%M%S").to_string();
+    let header_values = [
+        (ADIF_HEADER_ADIF_VER, adif.adif_adif_version.as_deref()
+            .unwrap_or(ADIF_EXPORT_ADIF_VER)),
+        (ADIF_HEADER_PROGRAMID, adif.adif_program_id.as_deref()
+            .unwrap_or(ADIF_EXPORT_PROGRAMID)),
+        (ADIF_HEADER_PROGRAMVERSION, adif.adif_program_version.as_deref()
+            .unwrap_or(ADIF_EXPORT_PROGRAMVERSION)),
+        (ADIF_HEADER_CREATED_TIMESTAMP, adif.adif_created_timestamp.as_deref()
+            .unwrap_or(&created)),
+    ];
+
+    let mut header = AdiHeader {
+        adih_content: format!("Generated by {} {}\n", ADIF_EXPORT_PROGRAMID,
+            ADIF_EXPORT_PROGRAMVERSION).into_bytes(),
+        adih_fields: header_values.iter()
+            .map(|(name, value)| adif_data_specifier(name, value))
+            .collect()
+    };
+
+    for userdef in &adif.adif_userdefs {
+        let mut adf = adif_data_specifier(
+            &format!("{}{}", ADIF_HEADER_USERDEF, userdef.adud_id),
+            &userdef.declaration());
+        adf.adif_type = indicator(&userdef.adud_type).map(|t| t.to_string());
+        header.adih_fields.push(adf);
+    }
+
+    let records = adif.adif_records.iter().map(|rec| {
+        AdiRecord {
+            adir_fields: rec.adir_field_values.iter()
+                .filter(|(name, _)| intl ||
+                    !AdifType::for_field(name).is_some_and(|t| t.is_intl()))
+                .map(|(name, value)| {
+                    let mut adf = adif_data_specifier(name, value);
+                    if let Some(bytes) = rec.adir_field_bytes.get(name) {
+                        adf.adif_length = bytes.len();
+                        adf.adif_bytes = bytes.clone();
+                    }
+                    adf.adif_type = rec.adir_field_types.get(name)
+                        .and_then(indicator)
+                        .map(|t| t.to_string());
+                    adf
+                })
+                .collect()
+        }
+    }).collect();
+
+    AdiFile {
+        adi_header: Some(header),
+        adi_records: records,
+        adi_warnings: Vec::new()
+    }
+}
+
+fn adif_data_specifier(name: &str, value: &str) -> AdiDataSpecifier
+{
+    AdiDataSpecifier {
+        adif_name: name.to_string(),
+        adif_name_canon: name.to_lowercase(),
+        adif_length: value.len(),
+        adif_bytes: value.as_bytes().to_vec(),
+        adif_type: None,
+        adif_position: Default::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io;
+
+    //
+    // Generates an ADI file with the given number of records on the fly, so
+    // the test doesn't need to hold the whole input in memory either.
+    //
+    struct GeneratedAdi {
+        records_left : usize,
+        pending : Vec<u8>
+    }
+
+    impl io::Read for GeneratedAdi {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pending.is_empty() && self.records_left > 0 {
+                self.pending = format!("<call:6>W1A{:03}<band:3>20m<eor>\n",
+                    self.records_left % 1000).into_bytes();
+                self.records_left -= 1;
+            }
+            let n = std::cmp::min(buf.len(), self.pending.len());
+            buf[..n].copy_from_slice(&self.pending[..n]);
+            self.pending.drain(..n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn streaming_reader() {
+        let source = GeneratedAdi {
+            records_left: 50000,
+            pending: b"generated<programid:4>TEST<eoh>\n".to_vec()
+        };
+        let reader = crate::adif::adif_reader("generated",
+            io::BufReader::new(source)).unwrap();
+        assert_eq!(reader.adrd_header.adif_program_id.as_deref(), Some("TEST"));
+        assert_eq!(reader.adrd_header.adif_records.len(), 0);
+
+        let mut count = 0;
+        for rec in reader {
+            let rec = rec.unwrap();
+            assert_eq!(rec.adir_field_values.get("band").map(|s| s.as_str()),
+                Some("20m"));
+            count += 1;
+        }
+        assert_eq!(count, 50000);
+
+        // A bad record doesn't prevent reading the ones after it...
+        let source = io::Cursor::new(
+            "<call:4>W1AW<eor><call:4>K1AB<call:4>K1AB<eor><call:4>N1XX<eor>");
+        let results : Vec<_> = crate::adif::adif_reader("bad", source)
+            .unwrap().collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+
+        // ... but malformed input is reported once, and then iteration stops.
+        let source = io::Cursor::new(
+            "<call:4>W1AW<eor><call:x>K1AB<eor><call:4>N1XX<eor>");
+        let results : Vec<_> = crate::adif::adif_reader("bad", source)
+            .unwrap().collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn parse_options() {
+        use crate::adif::{AdifDuplicatePolicy, AdifParseOptions};
+
+        let parse = |input: &str, options: &AdifParseOptions| {
+            crate::adif::adif_parse_with_options("options",
+                &mut io::Cursor::new(input), options)
+        };
+
+        // Duplicate fields are handled according to the policy.
+        let input = "<call:4>W1AW<band:3>20m<band:3>40m<eor>";
+        assert!(parse(input, &Default::default()).is_err());
+        for (policy, band) in &[(AdifDuplicatePolicy::ADP_KEEP_FIRST, "20m"),
+            (AdifDuplicatePolicy::ADP_KEEP_LAST, "40m")] {
+            let options = AdifParseOptions {
+                adpo_duplicates: *policy,
+                ..Default::default()
+            };
+            let adif = parse(input, &options).unwrap();
+            assert_eq!(adif.adif_records[0].adir_field_values["band"], *band);
+            assert!(adif.adif_warnings.is_empty());
+        }
+
+        // In lenient mode, fields that can't be interpreted are skipped.
+        let lenient = AdifParseOptions {
+            adpo_lenient: true,
+            ..Default::default()
+        };
+        let input = "header<programid:4:X>TEST<eoh>\
+            <call:4>W1AW<band:3>20m<band:3>40m<mode:2:X>CW<eor>\
+            <call:4>K1AB<band:2>2m<eor>";
+        let adif = parse(input, &lenient).unwrap();
+        assert_eq!(adif.adif_program_id, None);
+        assert_eq!(adif.adif_records.len(), 2);
+        assert_eq!(adif.adif_records[0].adir_field_values["band"], "20m");
+        assert!(!adif.adif_records[0].adir_field_values.contains_key("mode"));
+        let warnings : Vec<_> = adif.adif_warnings.iter()
+            .map(|w| (w.adie_position.unwrap().adpp_offset, w.adie_record)).collect();
+        assert_eq!(warnings, vec![(6, None), (53, Some(1)), (64, Some(1))]);
+
+        // The streaming reader reports the same warnings as it goes.
+        let mut reader = crate::adif::adif_reader_with_options("options",
+            io::Cursor::new(input), &lenient).unwrap();
+        assert_eq!(reader.adrd_header.adif_warnings.len(), 1);
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(reader.take_warnings().len(), 2);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().is_none());
+        assert!(reader.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn charsets_and_intl_fields() {
+        use crate::adif::{AdifCharset, AdifParseOptions};
+
+        let parse = |input: &[u8], charset: AdifCharset| {
+            let options = AdifParseOptions {
+                adpo_charset: charset,
+                ..Default::default()
+            };
+            crate::adif::adif_parse_with_options("charset",
+                &mut io::Cursor::new(input.to_vec()), &options)
+        };
+
+        // "Jos\xe9" is Latin-1, "Jos\xc3\xa9" is UTF-8.
+        let latin1 = b"<call:4>EA1A<name:4>Jos\xe9<qslmsg:4>\x93hi\x94<eor>";
+        let utf8 = "<call:4>EA1A<name:5>José<name_intl:5>José<eor>";
+
+        assert!(parse(latin1, AdifCharset::ADC_UTF8).is_err());
+        assert!(parse(utf8.as_bytes(), AdifCharset::ADC_UTF8).is_ok());
+        assert!(parse(utf8.as_bytes(), AdifCharset::ADC_ASCII).is_err());
+        assert!(parse(b"<call:4>EA1A<name_intl:5>Jos\xc3\xa9<eor>",
+            AdifCharset::ADC_ASCII).is_ok());
+
+        let adif = parse(latin1, AdifCharset::ADC_LATIN1).unwrap();
+        let rec = &adif.adif_records[0];
+        assert_eq!(rec.adir_field_values["name"], "José");
+        assert_eq!(rec.adir_field_values["qslmsg"], "\u{201c}hi\u{201d}");
+        let adif = parse(utf8.as_bytes(), AdifCharset::ADC_LATIN1).unwrap();
+        assert_eq!(adif.adif_records[0].adir_field_values["name"], "José");
+
+        // Raw bytes survive a round trip.
+        let adif = parse(latin1, AdifCharset::ADC_RAW).unwrap();
+        let rec = &adif.adif_records[0];
+        assert_eq!(rec.adir_field_values["name"], "Jos\u{fffd}");
+        assert_eq!(rec.value_bytes("NAME"), Some(&b"Jos\xe9"[..]));
+        assert_eq!(rec.value_bytes("call"), Some(&b"EA1A"[..]));
+        let mut output : Vec<u8> = Vec::new();
+        crate::adif::adif_write_adi(&adif, &mut output).unwrap();
+        let reparsed = parse(&output, AdifCharset::ADC_RAW).unwrap();
+        assert_eq!(reparsed.adif_records[0].value_bytes("name"),
+            Some(&b"Jos\xe9"[..]));
+
+        // "_INTL" fields can replace their base fields.
+        let options = AdifParseOptions {
+            adpo_intl_to_base: true,
+            ..Default::default()
+        };
+        let input = "<call:4>EA1A<name:4>Jose<name_intl:5>José\
+            <notes_intl:7>a\r\nb ñ<eor>";
+        let adif = crate::adif::adif_parse_with_options("intl",
+            &mut io::Cursor::new(input), &options).unwrap();
+        let rec = &adif.adif_records[0];
+        assert_eq!(rec.adir_field_values.len(), 3);
+        assert_eq!(rec.adir_field_values["name"], "José");
+        assert_eq!(rec.value("name").unwrap(),
+            Some(AdifValue::IntlString(String::from("José"))));
+        assert_eq!(rec.field_type("notes"), Some(AdifType::IntlMultilineString));
+        assert!(rec.value("notes").is_ok());
+    }
+
+    #[test]
+    fn parse_typed_values() {
+        let input = "header<adif_ver:5:s>3.1.0<eoh>\n\
+            <call:4:S>W1AW<qso_date:8:D>20200101<time_on:4:T>1200\
+            <freq:5:N>7.074<qsl_rcvd:1:E>Y<swl:1:B>N<lat:11:L>N041 42.000\
+            <notes:6:M>a\r\nb c<eor>";
+        let mut reader = io::Cursor::new(input);
+        let adif = crate::adif::adif_parse("typed", &mut reader).unwrap();
+        assert_eq!(adif.adif_adif_version.as_deref(), Some("3.1.0"));
+        let rec = &adif.adif_records[0].adir_field_values;
+        assert_eq!(rec.get("qso_date").map(|s| s.as_str()), Some("20200101"));
+        assert_eq!(rec.get("freq").map(|s| s.as_str()), Some("7.074"));
+        assert_eq!(rec.get("lat").map(|s| s.as_str()), Some("N041 42.000"));
+        assert_eq!(rec.get("notes").map(|s| s.as_str()), Some("a\r\nb c"));
+
+        let mut reader = io::Cursor::new("<call:4:X>W1AW<eor>");
+        assert!(crate::adif::adif_parse("typed", &mut reader).is_err());
+    }
+
+    #[test]
+    fn userdefs_and_app_fields() {
+        let input = "header\n\
+            <USERDEF1:3:N>EPC\n\
+            <USERDEF2:19:E>SweaterSize,{S,M,L}\n\
+            <USERDEF3:15:N>ShoeSize,{5:20}\n\
+            <eoh>\n\
+            <call:4>W1AW<epc:5>12345<sweatersize:1>m<shoesize:2>11\
+            <APP_N1MM_EXCHANGE1:2>5A<APP_N1MM_RADIO_NR:1>1\
+            <APP_LOTW_2XQSL:1:S>Y<eor>\n\
+            <call:4>K1AB<sweatersize:2>XL<shoesize:2>21<epc:3>abc<eor>\n";
+        let mut reader = io::Cursor::new(input);
+        let adif = crate::adif::adif_parse("userdef", &mut reader).unwrap();
+
+        assert_eq!(adif.adif_userdefs.len(), 3);
+        assert_eq!(adif.adif_userdefs[1], AdifUserDef {
+            adud_id: 2,
+            adud_name: String::from("SweaterSize"),
+            adud_type: AdifType::Enumeration,
+            adud_enum: Some(vec![String::from("S"), String::from("M"),
+                String::from("L")]),
+            adud_range: None
+        });
+        assert_eq!(adif.adif_userdefs[2].adud_range, Some((5.0, 20.0)));
+
+        let apps = adif.adif_records[0].app_fields();
+        assert_eq!(apps.len(), 2);
+        assert_eq!(apps["n1mm"]["exchange1"], "5A");
+        assert_eq!(apps["n1mm"]["radio_nr"], "1");
+        assert_eq!(apps["lotw"]["2xqsl"], "Y");
+
+        let errors = adif_validate_userdefs(&adif);
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().all(|e| e.to_string().contains("record 2")));
+
+        // Declarations and APP fields survive export in both formats.
+        for adx in &[false, true] {
+            let mut output : Vec<u8> = Vec::new();
+            if *adx {
+                crate::adif::adif_write_adx(&adif, &mut output).unwrap();
+            } else {
+                crate::adif::adif_write_adi(&adif, &mut output).unwrap();
+            }
+            let mut reader = io::Cursor::new(output);
+            let parsed = crate::adif::adif_parse("again", &mut reader).unwrap();
+            assert_eq!(parsed.adif_userdefs, adif.adif_userdefs);
+            assert_eq!(parsed.adif_records[0].app_fields(), apps);
+            assert_eq!(parsed.adif_records[1].adir_field_values,
+                adif.adif_records[1].adir_field_values);
+        }
+
+        let mut reader = io::Cursor::new("header<USERDEF1:3>EPC<eoh>");
+        assert!(crate::adif::adif_parse("bad", &mut reader).is_err());
+        let mut reader = io::Cursor::new("header<USERDEF1:9:N>EPC,{1:x}<eoh>");
+        assert!(crate::adif::adif_parse("bad", &mut reader).is_err());
+    }
+
+    #[test]
+    fn validate_enumerations() {
+        let input = "header<eoh>\n\
+            <call:4>W1AW<mode:3>SSB<submode:3>usb<band:3>20m<freq:6>14.250\
+            <qsl_rcvd:1>Y<state:2>CT<dxcc:3>291<cont:2>NA\
+            <credit_granted:18>DXCC_BAND:CARD,WAS<eor>\n\
+            <call:4>K1AB<mode:5>PSK31<band:3>40m<freq:6>14.070\
+            <qsl_sent:1>X<state:2>ON<dxcc:3>291<my_state:2>ON<my_dxcc:1>1\
+            <credit_submitted:10>DXCC,BOGUS<eor>\n\
+            <call:4>N0CL<mode:2>CW<submode:3>FT4<band_rx:3>11m<dxcc:3>999<eor>\n";
+        let mut reader = io::Cursor::new(input);
+        let adif = crate::adif::adif_parse("enums", &mut reader).unwrap();
+
+        assert!(adif.adif_records[0].validate_enumerations().is_empty());
+
+        let errors = adif.adif_records[1].validate_enumerations();
+        let fields : Vec<&str> = errors.iter()
+            .map(|e| e.adie_field.as_deref().unwrap())
+            .collect();
+        assert_eq!(fields, vec!["freq", "qsl_sent", "state", "credit_submitted"]);
+        assert!(errors.iter()
+            .all(|e| e.adie_kind == AdifInputErrorKind::ADIE_BAD_VALUE));
+        assert_eq!(errors[0].adie_message,
+            "14.07 MHz is not within band \"40m\"");
+        assert_eq!(errors[1].adie_message,
+            "\"X\" is not a valid QSL_Sent value");
+
+        let errors = adif_validate_enumerations(&adif);
+        assert_eq!(errors.len(), 7);
+        assert!(errors[4].to_string().contains("record 3"));
+        assert!(errors[4].to_string()
+            .contains("submode \"FT4\" does not belong to mode \"CW\""));
+        assert!(errors[5].to_string().contains("\"11m\" is not a valid Band"));
+        assert!(errors[6].to_string()
+            .contains("\"999\" is not a valid DXCC_Entity_Code value"));
+    }
+
+    #[test]
+    fn typed_accessors() {
+        let input = "header<eoh><call:4>W1AW<qso_date:8>20200101\
+            <time_on:6>123456<freq:6>14.074<cqz:2>05<swl:1>N\
+            <gridsquare:6>FN31pr<lat:11>N041 42.875<app_test_count:3:N>1.5\
+            <qso_date_off:8>20201301<eor>";
+        let mut reader = io::Cursor::new(input);
+        let adif = crate::adif::adif_parse("typed", &mut reader).unwrap();
+        let rec = &adif.adif_records[0];
+
+        assert_eq!(rec.date("qso_date").unwrap(),
+            NaiveDate::from_ymd_opt(2020, 1, 1));
+        assert_eq!(rec.time("TIME_ON").unwrap(),
+            NaiveTime::from_hms_opt(12, 34, 56));
+        assert_eq!(rec.number("freq").unwrap(), Some(14.074));
+        assert_eq!(rec.integer("cqz").unwrap(), Some(5));
+        assert_eq!(rec.boolean("swl").unwrap(), Some(false));
+        assert_eq!(rec.gridsquare("gridsquare").unwrap().unwrap().full(),
+            "FN31pr");
+        assert_eq!(rec.location("lat").unwrap(), Some(41.0 + 42.875 / 60.0));
+        assert_eq!(rec.date("missing").unwrap(), None);
+
+        // Explicit type indicators take precedence over the field name.
+        assert_eq!(rec.field_type("app_test_count"), Some(AdifType::Number));
+        assert_eq!(rec.value("app_test_count").unwrap(),
+            Some(AdifValue::Number(1.5)));
+        assert_eq!(rec.value("call").unwrap(),
+            Some(AdifValue::String(String::from("W1AW"))));
+
+        let err = rec.date("qso_date_off").unwrap_err();
+        assert_eq!(err.adve_field, "qso_date_off");
+        assert_eq!(err.adve_type, AdifType::Date);
+        assert!(rec.number("call").is_err());
+
+        // Explicit types are written back out on export.
+        let mut output : Vec<u8> = Vec::new();
+        crate::adif::adif_write_adi(&adif, &mut output).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("<app_test_count:3:N>1.5"));
+        assert!(text.contains("<call:4>W1AW"));
+    }
+
+    #[test]
+    fn export_roundtrip() {
+        let adif = AdifFile {
+            adif_adif_version: None,
+            adif_program_id: Some(String::from("TESTPROG")),
+            adif_program_version: None,
+            adif_created_timestamp: None,
+            adif_label: String::from("<eoh><call:4>FAKE<eor>"),
+            adif_userdefs: vec![],
+            adif_warnings: vec![],
+            adif_records: vec![
+                AdifRecord::from_pairs(&[("call", "KK6ZBI"), ("qso_date", "20181129"),
+                    ("comment", "<tricky>: value with ünïcode")]),
+                AdifRecord::from_pairs(&[("call", "KB1HCN"), ("notes", "line 1\r\nline 2")]),
+            ]
+        };
+
+        let mut output : Vec<u8> = Vec::new();
+        crate::adif::adif_write_adi(&adif, &mut output).unwrap();
+        let mut reader = io::Cursor::new(output);
+        let parsed = crate::adif::adif_parse("reparsed", &mut reader).unwrap();
+
+        assert_eq!(parsed.adif_adif_version.as_deref(), Some(ADIF_EXPORT_ADIF_VER));
+        assert_eq!(parsed.adif_program_id.as_deref(), Some("TESTPROG"));
+        assert_eq!(parsed.adif_program_version.as_deref(),
+            Some(ADIF_EXPORT_PROGRAMVERSION));
+        assert!(parsed.adif_created_timestamp.is_some());
+        assert!(parsed.adif_warnings.is_empty());
+        assert_eq!(parsed.adif_records.len(), adif.adif_records.len());
+        for (orig, new) in adif.adif_records.iter().zip(&parsed.adif_records) {
+            assert_eq!(orig.adir_field_values, new.adir_field_values);
+        }
+
+        let mut output : Vec<u8> = Vec::new();
+        crate::adif::adif_write_adx(&adif, &mut output).unwrap();
+        let mut reader = io::Cursor::new(output);
+        let parsed = crate::adif::adif_parse("reparsed", &mut reader).unwrap();
+        assert_eq!(parsed.adif_program_id.as_deref(), Some("TESTPROG"));
+        for (orig, new) in adif.adif_records.iter().zip(&parsed.adif_records) {
+            assert_eq!(orig.adir_field_values, new.adir_field_values);
+        }
+    }
+
+    #[test]
+    fn export_intl_fields() {
+        use crate::adif::AdifParseOptions;
+
+        let write = |adif: &AdifFile, adx: bool| {
+            let mut output : Vec<u8> = Vec::new();
+            if adx {
+                crate::adif::adif_write_adx(adif, &mut output).unwrap();
+            } else {
+                crate::adif::adif_write_adi(adif, &mut output).unwrap();
+            }
+            String::from_utf8(output).unwrap()
+        };
+
+        // "_INTL" fields are left out of ADI files, but not ADX files.
+        let input = "<call:4>EA1A<name:4>Jose<name_intl:5>José<eor>";
+        let adif = crate::adif::adif_parse("intl",
+            &mut io::Cursor::new(input)).unwrap();
+        let adi = write(&adif, false);
+        assert!(adi.contains("<name:4>Jose"));
+        assert!(!adi.contains("name_intl"));
+        assert!(write(&adif, true).contains("<NAME_INTL>José</NAME_INTL>"));
+
+        // Fields of the Intl types are written with the ASCII type indicators.
+        let options = AdifParseOptions {
+            adpo_intl_to_base: true,
+            ..Default::default()
+        };
+        let input = "<call:4>EA1A<name_intl:5>José<notes_intl:4>a\r\nb<eor>";
+        let adif = crate::adif::adif_parse_with_options("intl",
+            &mut io::Cursor::new(input), &options).unwrap();
+        let adi = write(&adif, false);
+        assert!(adi.contains("<name:5:S>José"));
+        assert!(adi.contains("<notes:4:M>a\r\nb"));
+    }
+}
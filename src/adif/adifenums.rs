@@ -0,0 +1,1332 @@
+//
+// src/adif/adifenums.rs: ADIF enumerations
+//
+// ADIF defines a number of enumerations: fixed sets of values that a field of
+// type Enumeration may take.  Each one is represented here as a Rust enum whose
+// variants are named after the ADIF values (with punctuation replaced by "_"
+// and a letter prefixed to values that begin with a digit).  Values are parsed
+// case-insensitively, as ADIF requires, and are always displayed and serialized
+// using the exact spelling from the specification (ADIF 3.1.4).
+//
+
+use crate::Band;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+//
+// AdifEnumError: a value is not a member of the given ADIF enumeration.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdifEnumError {
+    pub adee_enumeration: &'static str, // ADIF name of the enumeration
+    pub adee_value: String,             // the value that couldn't be parsed
+}
+
+impl fmt::Display for AdifEnumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\" is not a valid {} value", self.adee_value,
+            self.adee_enumeration)
+    }
+}
+
+impl error::Error for AdifEnumError {}
+
+//
+// adif_enumeration! defines an enum for an ADIF enumeration, given the ADIF name
+// of the enumeration and each variant's spelling in the specification.  Each
+// enum gets:
+//
+//     ALL          every value, in the order listed in the specification
+//     ENUMERATION  the ADIF name of the enumeration (e.g., "Ant_Path")
+//     as_str()     the value's ADIF spelling
+//
+// along with Display, FromStr, Serialize, and Deserialize implementations that
+// use that spelling.
+//
+macro_rules! adif_enumeration {
+    ($name:ident, $adif:literal { $($variant:ident => $text:expr),* $(,)? }) => {
+        #[allow(non_camel_case_types)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum $name {
+            $($variant),*
+        }
+
+        impl $name {
+            pub const ALL : &'static [$name] = &[$($name::$variant),*];
+            pub const ENUMERATION : &'static str = $adif;
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $text),*
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = AdifEnumError;
+
+            fn from_str(value: &str) -> Result<$name, AdifEnumError> {
+                $name::ALL.iter()
+                    .find(|v| v.as_str().eq_ignore_ascii_case(value))
+                    .copied()
+                    .ok_or_else(|| AdifEnumError {
+                        adee_enumeration: $adif,
+                        adee_value: value.to_string(),
+                    })
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let v : String = Deserialize::deserialize(deserializer)?;
+                v.parse().map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+//
+// Mode and Submode.  Every submode belongs to exactly one mode (see
+// AdifSubmode::mode()).  Modes that were removed from the Mode enumeration by
+// later versions of ADIF are "import-only": they may still appear in a MODE
+// field, and each corresponds to a submode of the same name.
+//
+adif_enumeration! {
+    AdifMode, "Mode" {
+        AM => "AM",
+        ARDOP => "ARDOP",
+        ATV => "ATV",
+        C4FM => "C4FM",
+        CHIP => "CHIP",
+        CLO => "CLO",
+        CONTESTI => "CONTESTI",
+        CW => "CW",
+        DIGITALVOICE => "DIGITALVOICE",
+        DOMINO => "DOMINO",
+        DSTAR => "DSTAR",
+        DYNAMIC => "DYNAMIC",
+        FAX => "FAX",
+        FM => "FM",
+        FSK => "FSK",
+        FSK441 => "FSK441",
+        FT8 => "FT8",
+        HELL => "HELL",
+        ISCAT => "ISCAT",
+        JT4 => "JT4",
+        JT6M => "JT6M",
+        JT9 => "JT9",
+        JT44 => "JT44",
+        JT65 => "JT65",
+        MFSK => "MFSK",
+        MSK144 => "MSK144",
+        MT63 => "MT63",
+        OLIVIA => "OLIVIA",
+        OPERA => "OPERA",
+        PAC => "PAC",
+        PAX => "PAX",
+        PKT => "PKT",
+        PSK => "PSK",
+        PSK2K => "PSK2K",
+        Q15 => "Q15",
+        QRA64 => "QRA64",
+        ROS => "ROS",
+        RTTY => "RTTY",
+        RTTYM => "RTTYM",
+        SSB => "SSB",
+        SSTV => "SSTV",
+        T10 => "T10",
+        THOR => "THOR",
+        THRB => "THRB",
+        TOR => "TOR",
+        V4 => "V4",
+        VOI => "VOI",
+        WINMOR => "WINMOR",
+        WSPR => "WSPR"
+    }
+}
+
+adif_enumeration! {
+    AdifSubmode, "Submode" {
+        CHIP64 => "CHIP64",
+        CHIP128 => "CHIP128",
+        PCW => "PCW",
+        FREEDV => "FREEDV",
+        M17 => "M17",
+        DOM_M => "DOM-M",
+        DOM4 => "DOM4",
+        DOM5 => "DOM5",
+        DOM8 => "DOM8",
+        DOM11 => "DOM11",
+        DOM16 => "DOM16",
+        DOM22 => "DOM22",
+        DOM44 => "DOM44",
+        DOM88 => "DOM88",
+        DOMINOEX => "DOMINOEX",
+        DOMINOF => "DOMINOF",
+        VARA_HF => "VARA HF",
+        VARA_SATELLITE => "VARA SATELLITE",
+        VARA_FM_1200 => "VARA FM 1200",
+        VARA_FM_9600 => "VARA FM 9600",
+        SCAMP_FAST => "SCAMP_FAST",
+        SCAMP_SLOW => "SCAMP_SLOW",
+        SCAMP_VSLOW => "SCAMP_VSLOW",
+        FMHELL => "FMHELL",
+        FSKHELL => "FSKHELL",
+        HELL80 => "HELL80",
+        HELLX5 => "HELLX5",
+        HELLX9 => "HELLX9",
+        HFSK => "HFSK",
+        PSKHELL => "PSKHELL",
+        SLOWHELL => "SLOWHELL",
+        ISCAT_A => "ISCAT-A",
+        ISCAT_B => "ISCAT-B",
+        JT4A => "JT4A",
+        JT4B => "JT4B",
+        JT4C => "JT4C",
+        JT4D => "JT4D",
+        JT4E => "JT4E",
+        JT4F => "JT4F",
+        JT4G => "JT4G",
+        JT9_1 => "JT9-1",
+        JT9_2 => "JT9-2",
+        JT9_5 => "JT9-5",
+        JT9_10 => "JT9-10",
+        JT9_30 => "JT9-30",
+        JT9A => "JT9A",
+        JT9B => "JT9B",
+        JT9C => "JT9C",
+        JT9D => "JT9D",
+        JT9E => "JT9E",
+        JT9E_FAST => "JT9E FAST",
+        JT9F => "JT9F",
+        JT9F_FAST => "JT9F FAST",
+        JT9G => "JT9G",
+        JT9G_FAST => "JT9G FAST",
+        JT9H => "JT9H",
+        JT9H_FAST => "JT9H FAST",
+        JT65A => "JT65A",
+        JT65B => "JT65B",
+        JT65B2 => "JT65B2",
+        JT65C => "JT65C",
+        JT65C2 => "JT65C2",
+        FSQCALL => "FSQCALL",
+        FST4 => "FST4",
+        FST4W => "FST4W",
+        FT4 => "FT4",
+        JS8 => "JS8",
+        JTMS => "JTMS",
+        MFSK4 => "MFSK4",
+        MFSK8 => "MFSK8",
+        MFSK11 => "MFSK11",
+        MFSK16 => "MFSK16",
+        MFSK22 => "MFSK22",
+        MFSK31 => "MFSK31",
+        MFSK32 => "MFSK32",
+        MFSK64 => "MFSK64",
+        MFSK64L => "MFSK64L",
+        MFSK128 => "MFSK128",
+        MFSK128L => "MFSK128L",
+        Q65 => "Q65",
+        OLIVIA_4_125 => "OLIVIA 4/125",
+        OLIVIA_4_250 => "OLIVIA 4/250",
+        OLIVIA_8_250 => "OLIVIA 8/250",
+        OLIVIA_8_500 => "OLIVIA 8/500",
+        OLIVIA_16_500 => "OLIVIA 16/500",
+        OLIVIA_16_1000 => "OLIVIA 16/1000",
+        OLIVIA_32_1000 => "OLIVIA 32/1000",
+        OPERA_BEACON => "OPERA-BEACON",
+        OPERA_QSO => "OPERA-QSO",
+        PAC2 => "PAC2",
+        PAC3 => "PAC3",
+        PAC4 => "PAC4",
+        PAX2 => "PAX2",
+        S8PSK125 => "8PSK125",
+        S8PSK125F => "8PSK125F",
+        S8PSK125FL => "8PSK125FL",
+        S8PSK250 => "8PSK250",
+        S8PSK250F => "8PSK250F",
+        S8PSK250FL => "8PSK250FL",
+        S8PSK500 => "8PSK500",
+        S8PSK500F => "8PSK500F",
+        S8PSK1000 => "8PSK1000",
+        S8PSK1000F => "8PSK1000F",
+        S8PSK1200F => "8PSK1200F",
+        FSK31 => "FSK31",
+        PSK10 => "PSK10",
+        PSK31 => "PSK31",
+        PSK63 => "PSK63",
+        PSK63F => "PSK63F",
+        PSK63RC4 => "PSK63RC4",
+        PSK63RC5 => "PSK63RC5",
+        PSK63RC10 => "PSK63RC10",
+        PSK63RC20 => "PSK63RC20",
+        PSK63RC32 => "PSK63RC32",
+        PSK125 => "PSK125",
+        PSK125C12 => "PSK125C12",
+        PSK125R => "PSK125R",
+        PSK125RC10 => "PSK125RC10",
+        PSK125RC12 => "PSK125RC12",
+        PSK125RC16 => "PSK125RC16",
+        PSK125RC4 => "PSK125RC4",
+        PSK125RC5 => "PSK125RC5",
+        PSK250 => "PSK250",
+        PSK250C6 => "PSK250C6",
+        PSK250R => "PSK250R",
+        PSK250RC2 => "PSK250RC2",
+        PSK250RC3 => "PSK250RC3",
+        PSK250RC5 => "PSK250RC5",
+        PSK250RC6 => "PSK250RC6",
+        PSK250RC7 => "PSK250RC7",
+        PSK500 => "PSK500",
+        PSK500C2 => "PSK500C2",
+        PSK500C4 => "PSK500C4",
+        PSK500R => "PSK500R",
+        PSK500RC2 => "PSK500RC2",
+        PSK500RC3 => "PSK500RC3",
+        PSK500RC4 => "PSK500RC4",
+        PSK800C2 => "PSK800C2",
+        PSK800RC2 => "PSK800RC2",
+        PSK1000 => "PSK1000",
+        PSK1000C2 => "PSK1000C2",
+        PSK1000R => "PSK1000R",
+        PSK1000RC2 => "PSK1000RC2",
+        PSKAM10 => "PSKAM10",
+        PSKAM31 => "PSKAM31",
+        PSKAM50 => "PSKAM50",
+        PSKFEC31 => "PSKFEC31",
+        QPSK31 => "QPSK31",
+        QPSK63 => "QPSK63",
+        QPSK125 => "QPSK125",
+        QPSK250 => "QPSK250",
+        QPSK500 => "QPSK500",
+        SIM31 => "SIM31",
+        QRA64A => "QRA64A",
+        QRA64B => "QRA64B",
+        QRA64C => "QRA64C",
+        QRA64D => "QRA64D",
+        QRA64E => "QRA64E",
+        ROS_EME => "ROS-EME",
+        ROS_HF => "ROS-HF",
+        ROS_MF => "ROS-MF",
+        ASCI => "ASCI",
+        LSB => "LSB",
+        USB => "USB",
+        THOR_M => "THOR-M",
+        THOR4 => "THOR4",
+        THOR5 => "THOR5",
+        THOR8 => "THOR8",
+        THOR11 => "THOR11",
+        THOR16 => "THOR16",
+        THOR22 => "THOR22",
+        THOR25X4 => "THOR25X4",
+        THOR50X1 => "THOR50X1",
+        THOR50X2 => "THOR50X2",
+        THOR100 => "THOR100",
+        THRBX => "THRBX",
+        THRBX1 => "THRBX1",
+        THRBX2 => "THRBX2",
+        THRBX4 => "THRBX4",
+        THROB1 => "THROB1",
+        THROB2 => "THROB2",
+        THROB4 => "THROB4",
+        AMTORFEC => "AMTORFEC",
+        GTOR => "GTOR",
+        NAVTEX => "NAVTEX",
+        SITORB => "SITORB"
+    }
+}
+
+//
+// Submodes that may appear as import-only values of the MODE field.
+//
+const ADIF_IMPORT_ONLY_MODES : &[AdifSubmode] = &[
+    AdifSubmode::AMTORFEC, AdifSubmode::ASCI, AdifSubmode::CHIP64,
+    AdifSubmode::CHIP128, AdifSubmode::DOMINOF, AdifSubmode::FMHELL,
+    AdifSubmode::FSK31, AdifSubmode::GTOR, AdifSubmode::HELL80,
+    AdifSubmode::HFSK, AdifSubmode::JT4A, AdifSubmode::JT65A,
+    AdifSubmode::JT65B, AdifSubmode::JT65C, AdifSubmode::MFSK8,
+    AdifSubmode::MFSK16, AdifSubmode::PAC2, AdifSubmode::PAC3,
+    AdifSubmode::PAX2, AdifSubmode::PCW, AdifSubmode::PSK10, AdifSubmode::PSK31,
+    AdifSubmode::PSK63, AdifSubmode::PSK63F, AdifSubmode::PSK125,
+    AdifSubmode::PSKAM10, AdifSubmode::PSKAM31, AdifSubmode::PSKAM50,
+    AdifSubmode::PSKFEC31, AdifSubmode::PSKHELL, AdifSubmode::QPSK31,
+    AdifSubmode::QPSK63, AdifSubmode::QPSK125, AdifSubmode::THRBX,
+];
+
+impl AdifSubmode {
+    //
+    // Returns the mode that this submode belongs to.
+    //
+    pub fn mode(&self) -> AdifMode {
+        match self {
+            AdifSubmode::CHIP64 | AdifSubmode::CHIP128 =>
+                AdifMode::CHIP,
+            AdifSubmode::PCW =>
+                AdifMode::CW,
+            AdifSubmode::FREEDV | AdifSubmode::M17 =>
+                AdifMode::DIGITALVOICE,
+            AdifSubmode::DOM_M | AdifSubmode::DOM4 | AdifSubmode::DOM5 |
+            AdifSubmode::DOM8 | AdifSubmode::DOM11 | AdifSubmode::DOM16 |
+            AdifSubmode::DOM22 | AdifSubmode::DOM44 | AdifSubmode::DOM88 |
+            AdifSubmode::DOMINOEX | AdifSubmode::DOMINOF =>
+                AdifMode::DOMINO,
+            AdifSubmode::VARA_HF | AdifSubmode::VARA_SATELLITE |
+            AdifSubmode::VARA_FM_1200 | AdifSubmode::VARA_FM_9600 =>
+                AdifMode::DYNAMIC,
+            AdifSubmode::SCAMP_FAST | AdifSubmode::SCAMP_SLOW |
+            AdifSubmode::SCAMP_VSLOW =>
+                AdifMode::FSK,
+            AdifSubmode::FMHELL | AdifSubmode::FSKHELL | AdifSubmode::HELL80 |
+            AdifSubmode::HELLX5 | AdifSubmode::HELLX9 | AdifSubmode::HFSK |
+            AdifSubmode::PSKHELL | AdifSubmode::SLOWHELL =>
+                AdifMode::HELL,
+            AdifSubmode::ISCAT_A | AdifSubmode::ISCAT_B =>
+                AdifMode::ISCAT,
+            AdifSubmode::JT4A | AdifSubmode::JT4B | AdifSubmode::JT4C |
+            AdifSubmode::JT4D | AdifSubmode::JT4E | AdifSubmode::JT4F |
+            AdifSubmode::JT4G =>
+                AdifMode::JT4,
+            AdifSubmode::JT9_1 | AdifSubmode::JT9_2 | AdifSubmode::JT9_5 |
+            AdifSubmode::JT9_10 | AdifSubmode::JT9_30 | AdifSubmode::JT9A |
+            AdifSubmode::JT9B | AdifSubmode::JT9C | AdifSubmode::JT9D |
+            AdifSubmode::JT9E | AdifSubmode::JT9E_FAST | AdifSubmode::JT9F |
+            AdifSubmode::JT9F_FAST | AdifSubmode::JT9G |
+            AdifSubmode::JT9G_FAST | AdifSubmode::JT9H | AdifSubmode::JT9H_FAST =>
+                AdifMode::JT9,
+            AdifSubmode::JT65A | AdifSubmode::JT65B | AdifSubmode::JT65B2 |
+            AdifSubmode::JT65C | AdifSubmode::JT65C2 =>
+                AdifMode::JT65,
+            AdifSubmode::FSQCALL | AdifSubmode::FST4 | AdifSubmode::FST4W |
+            AdifSubmode::FT4 | AdifSubmode::JS8 | AdifSubmode::JTMS |
+            AdifSubmode::MFSK4 | AdifSubmode::MFSK8 | AdifSubmode::MFSK11 |
+            AdifSubmode::MFSK16 | AdifSubmode::MFSK22 | AdifSubmode::MFSK31 |
+            AdifSubmode::MFSK32 | AdifSubmode::MFSK64 | AdifSubmode::MFSK64L |
+            AdifSubmode::MFSK128 | AdifSubmode::MFSK128L | AdifSubmode::Q65 =>
+                AdifMode::MFSK,
+            AdifSubmode::OLIVIA_4_125 | AdifSubmode::OLIVIA_4_250 |
+            AdifSubmode::OLIVIA_8_250 | AdifSubmode::OLIVIA_8_500 |
+            AdifSubmode::OLIVIA_16_500 | AdifSubmode::OLIVIA_16_1000 |
+            AdifSubmode::OLIVIA_32_1000 =>
+                AdifMode::OLIVIA,
+            AdifSubmode::OPERA_BEACON | AdifSubmode::OPERA_QSO =>
+                AdifMode::OPERA,
+            AdifSubmode::PAC2 | AdifSubmode::PAC3 | AdifSubmode::PAC4 =>
+                AdifMode::PAC,
+            AdifSubmode::PAX2 =>
+                AdifMode::PAX,
+            AdifSubmode::S8PSK125 | AdifSubmode::S8PSK125F |
+            AdifSubmode::S8PSK125FL | AdifSubmode::S8PSK250 |
+            AdifSubmode::S8PSK250F | AdifSubmode::S8PSK250FL |
+            AdifSubmode::S8PSK500 | AdifSubmode::S8PSK500F |
+            AdifSubmode::S8PSK1000 | AdifSubmode::S8PSK1000F |
+            AdifSubmode::S8PSK1200F | AdifSubmode::FSK31 | AdifSubmode::PSK10 |
+            AdifSubmode::PSK31 | AdifSubmode::PSK63 | AdifSubmode::PSK63F |
+            AdifSubmode::PSK63RC4 | AdifSubmode::PSK63RC5 |
+            AdifSubmode::PSK63RC10 | AdifSubmode::PSK63RC20 |
+            AdifSubmode::PSK63RC32 | AdifSubmode::PSK125 |
+            AdifSubmode::PSK125C12 | AdifSubmode::PSK125R |
+            AdifSubmode::PSK125RC10 | AdifSubmode::PSK125RC12 |
+            AdifSubmode::PSK125RC16 | AdifSubmode::PSK125RC4 |
+            AdifSubmode::PSK125RC5 | AdifSubmode::PSK250 |
+            AdifSubmode::PSK250C6 | AdifSubmode::PSK250R |
+            AdifSubmode::PSK250RC2 | AdifSubmode::PSK250RC3 |
+            AdifSubmode::PSK250RC5 | AdifSubmode::PSK250RC6 |
+            AdifSubmode::PSK250RC7 | AdifSubmode::PSK500 |
+            AdifSubmode::PSK500C2 | AdifSubmode::PSK500C4 |
+            AdifSubmode::PSK500R | AdifSubmode::PSK500RC2 |
+            AdifSubmode::PSK500RC3 | AdifSubmode::PSK500RC4 |
+            AdifSubmode::PSK800C2 | AdifSubmode::PSK800RC2 |
+            AdifSubmode::PSK1000 | AdifSubmode::PSK1000C2 |
+            AdifSubmode::PSK1000R | AdifSubmode::PSK1000RC2 |
+            AdifSubmode::PSKAM10 | AdifSubmode::PSKAM31 |
+            AdifSubmode::PSKAM50 | AdifSubmode::PSKFEC31 |
+            AdifSubmode::QPSK31 | AdifSubmode::QPSK63 | AdifSubmode::QPSK125 |
+            AdifSubmode::QPSK250 | AdifSubmode::QPSK500 | AdifSubmode::SIM31 =>
+                AdifMode::PSK,
+            AdifSubmode::QRA64A | AdifSubmode::QRA64B | AdifSubmode::QRA64C |
+            AdifSubmode::QRA64D | AdifSubmode::QRA64E =>
+                AdifMode::QRA64,
+            AdifSubmode::ROS_EME | AdifSubmode::ROS_HF | AdifSubmode::ROS_MF =>
+                AdifMode::ROS,
+            AdifSubmode::ASCI =>
+                AdifMode::RTTY,
+            AdifSubmode::LSB | AdifSubmode::USB =>
+                AdifMode::SSB,
+            AdifSubmode::THOR_M | AdifSubmode::THOR4 | AdifSubmode::THOR5 |
+            AdifSubmode::THOR8 | AdifSubmode::THOR11 | AdifSubmode::THOR16 |
+            AdifSubmode::THOR22 | AdifSubmode::THOR25X4 |
+            AdifSubmode::THOR50X1 | AdifSubmode::THOR50X2 |
+            AdifSubmode::THOR100 =>
+                AdifMode::THOR,
+            AdifSubmode::THRBX | AdifSubmode::THRBX1 | AdifSubmode::THRBX2 |
+            AdifSubmode::THRBX4 | AdifSubmode::THROB1 | AdifSubmode::THROB2 |
+            AdifSubmode::THROB4 =>
+                AdifMode::THRB,
+            AdifSubmode::AMTORFEC | AdifSubmode::GTOR | AdifSubmode::NAVTEX |
+            AdifSubmode::SITORB =>
+                AdifMode::TOR,
+        }
+    }
+
+    //
+    // Returns true if this submode's name was once a mode, and so may appear
+    // as an import-only value of the MODE field.
+    //
+    pub fn is_import_only_mode(&self) -> bool {
+        ADIF_IMPORT_ONLY_MODES.contains(self)
+    }
+}
+
+impl AdifMode {
+    //
+    // Parses the value of a MODE field, which may be either a mode or an
+    // import-only mode.  For the latter, this returns the mode that the value
+    // now belongs to along with the corresponding submode.
+    //
+    pub fn from_import(value: &str) ->
+        Result<(AdifMode, Option<AdifSubmode>), AdifEnumError>
+    {
+        if let Ok(mode) = value.parse::<AdifMode>() {
+            return Ok((mode, None));
+        }
+
+        match value.parse::<AdifSubmode>() {
+            Ok(submode) if submode.is_import_only_mode() =>
+                Ok((submode.mode(), Some(submode))),
+            _ => Err(AdifEnumError {
+                adee_enumeration: AdifMode::ENUMERATION,
+                adee_value: value.to_string(),
+            })
+        }
+    }
+
+    //
+    // Returns the submodes that belong to this mode.
+    //
+    pub fn submodes(&self) -> Vec<AdifSubmode> {
+        AdifSubmode::ALL.iter().filter(|s| s.mode() == *self).copied().collect()
+    }
+}
+
+//
+// Band.  Band edges are given in MHz, and both edges are included in the band.
+//
+adif_enumeration! {
+    AdifBand, "Band" {
+        B2190M => "2190m",
+        B630M => "630m",
+        B560M => "560m",
+        B160M => "160m",
+        B80M => "80m",
+        B60M => "60m",
+        B40M => "40m",
+        B30M => "30m",
+        B20M => "20m",
+        B17M => "17m",
+        B15M => "15m",
+        B12M => "12m",
+        B10M => "10m",
+        B8M => "8m",
+        B6M => "6m",
+        B5M => "5m",
+        B4M => "4m",
+        B2M => "2m",
+        B1_25M => "1.25m",
+        B70CM => "70cm",
+        B33CM => "33cm",
+        B23CM => "23cm",
+        B13CM => "13cm",
+        B9CM => "9cm",
+        B6CM => "6cm",
+        B3CM => "3cm",
+        B1_25CM => "1.25cm",
+        B6MM => "6mm",
+        B4MM => "4mm",
+        B2_5MM => "2.5mm",
+        B2MM => "2mm",
+        B1MM => "1mm",
+        SUBMM => "submm"
+    }
+}
+
+impl AdifBand {
+    //
+    // Returns the lower and upper edges of the band, in MHz.  The edges are
+    // kept with the rest of the band data in crate::band.
+    //
+    pub fn edges(&self) -> (f64, f64) {
+        let (lower, upper) = Band::from_adif_str(self.as_str())
+            .and_then(|band| band.edges())
+            .expect("every ADIF band has a Band");
+        (lower.mhz(), upper.mhz())
+    }
+
+    //
+    // Returns true if the given frequency (in MHz) falls within the band.
+    //
+    pub fn contains(&self, mhz: f64) -> bool {
+        let (lower, upper) = self.edges();
+        mhz >= lower && mhz <= upper
+    }
+
+    //
+    // Returns the band containing the given frequency (in MHz), if any.
+    //
+    pub fn for_frequency(mhz: f64) -> Option<AdifBand> {
+        AdifBand::ALL.iter().find(|b| b.contains(mhz)).copied()
+    }
+}
+
+adif_enumeration! {
+    AdifPropagationMode, "Propagation_Mode" {
+        AS => "AS",              // Aircraft Scatter
+        AUE => "AUE",            // Aurora-E
+        AUR => "AUR",            // Aurora
+        BS => "BS",              // Back scatter
+        ECH => "ECH",            // EchoLink
+        EME => "EME",            // Earth-Moon-Earth
+        ES => "ES",              // Sporadic E
+        F2 => "F2",              // F2 Reflection
+        FAI => "FAI",            // Field Aligned Irregularities
+        GWAVE => "GWAVE",        // Ground Wave
+        INTERNET => "INTERNET",  // Internet-assisted
+        ION => "ION",            // Ionoscatter
+        IRL => "IRL",            // IRLP
+        LOS => "LOS",            // Line of Sight
+        MS => "MS",              // Meteor scatter
+        RPT => "RPT",            // Terrestrial or atmospheric repeater or transponder
+        RS => "RS",              // Rain scatter
+        SAT => "SAT",            // Satellite
+        TEP => "TEP",            // Trans-equatorial
+        TR => "TR"               // Tropospheric ducting
+    }
+}
+
+//
+// QSL_Rcvd and QSL_Sent are used for paper QSLs as well as for LoTW and eQSL
+// confirmations.
+//
+adif_enumeration! {
+    AdifQslRcvd, "QSL_Rcvd" {
+        Y => "Y",  // yes (confirmed)
+        N => "N",  // no
+        R => "R",  // requested
+        I => "I",  // ignore or invalid
+        V => "V"   // verified (import-only)
+    }
+}
+
+adif_enumeration! {
+    AdifQslSent, "QSL_Sent" {
+        Y => "Y",  // yes
+        N => "N",  // no
+        R => "R",  // requested
+        Q => "Q",  // queued
+        I => "I"   // ignore or invalid
+    }
+}
+
+adif_enumeration! {
+    AdifQslVia, "QSL_Via" {
+        B => "B",  // bureau
+        D => "D",  // direct
+        E => "E",  // electronic
+        M => "M"   // manager (import-only)
+    }
+}
+
+//
+// Contest_ID.  Unlike the other enumerations here, the CONTEST_ID field is a
+// String: these values are recommended for interoperability, but others are
+// allowed.
+//
+adif_enumeration! {
+    AdifContestId, "Contest_ID" {
+        C070_160M_SPRINT => "070-160M-SPRINT",
+        C070_3_DAY => "070-3-DAY",
+        C070_31_FLAVORS => "070-31-FLAVORS",
+        C070_40M_SPRINT => "070-40M-SPRINT",
+        C070_80M_SPRINT => "070-80M-SPRINT",
+        C070_PSKFEST => "070-PSKFEST",
+        C070_ST_PATS_DAY => "070-ST-PATS-DAY",
+        C070_VALENTINE_SPRINT => "070-VALENTINE-SPRINT",
+        C10_RTTY => "10-RTTY",
+        C1010_OPEN_SEASON => "1010-OPEN-SEASON",
+        C7QP => "7QP",
+        AL_QSO_PARTY => "AL-QSO-PARTY",
+        ALL_ASIAN_DX_CW => "ALL-ASIAN-DX-CW",
+        ALL_ASIAN_DX_PHONE => "ALL-ASIAN-DX-PHONE",
+        ANARTS_RTTY => "ANARTS-RTTY",
+        ANATOLIAN_RTTY => "ANATOLIAN-RTTY",
+        AP_SPRINT => "AP-SPRINT",
+        AR_QSO_PARTY => "AR-QSO-PARTY",
+        ARI_DX => "ARI-DX",
+        ARRL_10 => "ARRL-10",
+        ARRL_10_GHZ => "ARRL-10-GHZ",
+        ARRL_160 => "ARRL-160",
+        ARRL_222 => "ARRL-222",
+        ARRL_DIGI => "ARRL-DIGI",
+        ARRL_DX_CW => "ARRL-DX-CW",
+        ARRL_DX_SSB => "ARRL-DX-SSB",
+        ARRL_EME => "ARRL-EME",
+        ARRL_FIELD_DAY => "ARRL-FIELD-DAY",
+        ARRL_RR_CW => "ARRL-RR-CW",
+        ARRL_RR_DIG => "ARRL-RR-DIG",
+        ARRL_RR_SSB => "ARRL-RR-SSB",
+        ARRL_RTTY => "ARRL-RTTY",
+        ARRL_SCR => "ARRL-SCR",
+        ARRL_SS_CW => "ARRL-SS-CW",
+        ARRL_SS_SSB => "ARRL-SS-SSB",
+        ARRL_UHF_AUG => "ARRL-UHF-AUG",
+        ARRL_VHF_JAN => "ARRL-VHF-JAN",
+        ARRL_VHF_JUN => "ARRL-VHF-JUN",
+        ARRL_VHF_SEP => "ARRL-VHF-SEP",
+        AZ_QSO_PARTY => "AZ-QSO-PARTY",
+        BARTG_RTTY => "BARTG-RTTY",
+        BARTG_SPRINT => "BARTG-SPRINT",
+        BC_QSO_PARTY => "BC-QSO-PARTY",
+        CA_QSO_PARTY => "CA-QSO-PARTY",
+        CIS_DX => "CIS-DX",
+        CO_QSO_PARTY => "CO-QSO-PARTY",
+        CQ_160_CW => "CQ-160-CW",
+        CQ_160_SSB => "CQ-160-SSB",
+        CQ_M => "CQ-M",
+        CQ_VHF => "CQ-VHF",
+        CQ_WPX_CW => "CQ-WPX-CW",
+        CQ_WPX_RTTY => "CQ-WPX-RTTY",
+        CQ_WPX_SSB => "CQ-WPX-SSB",
+        CQ_WW_CW => "CQ-WW-CW",
+        CQ_WW_RTTY => "CQ-WW-RTTY",
+        CQ_WW_SSB => "CQ-WW-SSB",
+        CT_QSO_PARTY => "CT-QSO-PARTY",
+        CVA_DX_CW => "CVA-DX-CW",
+        CVA_DX_SSB => "CVA-DX-SSB",
+        CWOPS_CW_OPEN => "CWOPS-CW-OPEN",
+        CWOPS_CWT => "CWOPS-CWT",
+        DARC_WAEDC_CW => "DARC-WAEDC-CW",
+        DARC_WAEDC_RTTY => "DARC-WAEDC-RTTY",
+        DARC_WAEDC_SSB => "DARC-WAEDC-SSB",
+        DARC_WAG => "DARC-WAG",
+        DE_QSO_PARTY => "DE-QSO-PARTY",
+        DL_DX_RTTY => "DL-DX-RTTY",
+        DMC_RTTY => "DMC-RTTY",
+        EA_CNCW => "EA-CNCW",
+        EA_DME => "EA-DME",
+        EA_MAJESTAD_CW => "EA-MAJESTAD-CW",
+        EA_MAJESTAD_SSB => "EA-MAJESTAD-SSB",
+        EA_PSK63 => "EA-PSK63",
+        EA_RTTY => "EA-RTTY",
+        EA_SMRE_CW => "EA-SMRE-CW",
+        EA_SMRE_SSB => "EA-SMRE-SSB",
+        EA_VHF_ATLANTIC => "EA-VHF-ATLANTIC",
+        EA_VHF_COM => "EA-VHF-COM",
+        EA_VHF_COSTA_SOL => "EA-VHF-COSTA-SOL",
+        EA_VHF_EA => "EA-VHF-EA",
+        EA_VHF_EA1RCS => "EA-VHF-EA1RCS",
+        EA_VHF_QSL => "EA-VHF-QSL",
+        EA_VHF_SADURNI => "EA-VHF-SADURNI",
+        EA_WW_RTTY => "EA-WW-RTTY",
+        EPC_PSK63 => "EPC-PSK63",
+        EU_SPRINT => "EU SPRINT",
+        EU_HF => "EU-HF",
+        EU_PSK_DX => "EU-PSK-DX",
+        EUCW160M => "EUCW160M",
+        FALL_SPRINT => "FALL SPRINT",
+        FL_QSO_PARTY => "FL-QSO-PARTY",
+        GA_QSO_PARTY => "GA-QSO-PARTY",
+        HA_DX => "HA-DX",
+        HELVETIA => "HELVETIA",
+        HI_QSO_PARTY => "HI-QSO-PARTY",
+        HOLYLAND => "HOLYLAND",
+        IA_QSO_PARTY => "IA-QSO-PARTY",
+        IARU_FIELD_DAY => "IARU-FIELD-DAY",
+        IARU_HF => "IARU-HF",
+        ICWC_MST => "ICWC-MST",
+        ID_QSO_PARTY => "ID-QSO-PARTY",
+        IL_QSO_PARTY => "IL QSO PARTY",
+        IN_QSO_PARTY => "IN-QSO-PARTY",
+        JARTS_WW_RTTY => "JARTS-WW-RTTY",
+        JIDX_CW => "JIDX-CW",
+        JIDX_SSB => "JIDX-SSB",
+        JT_DX_RTTY => "JT-DX-RTTY",
+        K1USN_SSO => "K1USN-SSO",
+        K1USN_SST => "K1USN-SST",
+        KS_QSO_PARTY => "KS-QSO-PARTY",
+        KY_QSO_PARTY => "KY-QSO-PARTY",
+        LA_QSO_PARTY => "LA-QSO-PARTY",
+        LDC_RTTY => "LDC-RTTY",
+        LZ_DX => "LZ DX",
+        MAR_QSO_PARTY => "MAR-QSO-PARTY",
+        MD_QSO_PARTY => "MD-QSO-PARTY",
+        ME_QSO_PARTY => "ME-QSO-PARTY",
+        MI_QSO_PARTY => "MI-QSO-PARTY",
+        MIDATLANTIC_QSO_PARTY => "MIDATLANTIC-QSO-PARTY",
+        MN_QSO_PARTY => "MN-QSO-PARTY",
+        MO_QSO_PARTY => "MO-QSO-PARTY",
+        MS_QSO_PARTY => "MS-QSO-PARTY",
+        MT_QSO_PARTY => "MT-QSO-PARTY",
+        NA_SPRINT_CW => "NA-SPRINT-CW",
+        NA_SPRINT_RTTY => "NA-SPRINT-RTTY",
+        NA_SPRINT_SSB => "NA-SPRINT-SSB",
+        NAQP_CW => "NAQP-CW",
+        NAQP_RTTY => "NAQP-RTTY",
+        NAQP_SSB => "NAQP-SSB",
+        NAVAL => "NAVAL",
+        NC_QSO_PARTY => "NC-QSO-PARTY",
+        ND_QSO_PARTY => "ND-QSO-PARTY",
+        NE_QSO_PARTY => "NE-QSO-PARTY",
+        NEQP => "NEQP",
+        NH_QSO_PARTY => "NH-QSO-PARTY",
+        NJ_QSO_PARTY => "NJ-QSO-PARTY",
+        NM_QSO_PARTY => "NM-QSO-PARTY",
+        NRAU_BALTIC_CW => "NRAU-BALTIC-CW",
+        NRAU_BALTIC_SSB => "NRAU-BALTIC-SSB",
+        NV_QSO_PARTY => "NV-QSO-PARTY",
+        NY_QSO_PARTY => "NY-QSO-PARTY",
+        OCEANIA_DX_CW => "OCEANIA-DX-CW",
+        OCEANIA_DX_SSB => "OCEANIA-DX-SSB",
+        OH_QSO_PARTY => "OH-QSO-PARTY",
+        OK_DX_RTTY => "OK-DX-RTTY",
+        OK_OM_DX => "OK-OM-DX",
+        OK_QSO_PARTY => "OK-QSO-PARTY",
+        OMISS_QSO_PARTY => "OMISS-QSO-PARTY",
+        ON_QSO_PARTY => "ON-QSO-PARTY",
+        OR_QSO_PARTY => "OR-QSO-PARTY",
+        PA_QSO_PARTY => "PA-QSO-PARTY",
+        PACC => "PACC",
+        PCC => "PCC",
+        PSK_DEATHMATCH => "PSK-DEATHMATCH",
+        QC_QSO_PARTY => "QC-QSO-PARTY",
+        RAC => "RAC",
+        RAC_CANADA_DAY => "RAC-CANADA-DAY",
+        RAC_CANADA_WINTER => "RAC-CANADA-WINTER",
+        RDAC => "RDAC",
+        RDXC => "RDXC",
+        REF_160M => "REF-160M",
+        REF_CW => "REF-CW",
+        REF_SSB => "REF-SSB",
+        REP_PORTUGAL_DAY_HF => "REP-PORTUGAL-DAY-HF",
+        RI_QSO_PARTY => "RI-QSO-PARTY",
+        RSGB_160 => "RSGB-160",
+        RSGB_21_28_CW => "RSGB-21/28-CW",
+        RSGB_21_28_SSB => "RSGB-21/28-SSB",
+        RSGB_80M_CC => "RSGB-80M-CC",
+        RSGB_AFS_CW => "RSGB-AFS-CW",
+        RSGB_AFS_SSB => "RSGB-AFS-SSB",
+        RSGB_CLUB_CALLS => "RSGB-CLUB-CALLS",
+        RSGB_COMMONWEALTH => "RSGB-COMMONWEALTH",
+        RSGB_IOTA => "RSGB-IOTA",
+        RSGB_LOW_POWER => "RSGB-LOW-POWER",
+        RSGB_NFD => "RSGB-NFD",
+        RSGB_ROPOCO => "RSGB-ROPOCO",
+        RSGB_SSB_FD => "RSGB-SSB-FD",
+        RUSSIAN_RTTY => "RUSSIAN-RTTY",
+        SAC_CW => "SAC-CW",
+        SAC_SSB => "SAC-SSB",
+        SARTG_RTTY => "SARTG-RTTY",
+        SC_QSO_PARTY => "SC-QSO-PARTY",
+        SCC_RTTY => "SCC-RTTY",
+        SD_QSO_PARTY => "SD-QSO-PARTY",
+        SMP_AUG => "SMP-AUG",
+        SMP_MAY => "SMP-MAY",
+        SP_DX_RTTY => "SP-DX-RTTY",
+        SPAR_WINTER_FD => "SPAR-WINTER-FD",
+        SPDX => "SPDX",
+        SPRING_SPRINT => "SPRING SPRINT",
+        SR_MARATHON => "SR-MARATHON",
+        STEW_PERRY => "STEW-PERRY",
+        TARA_GRID_DIP => "TARA-GRID-DIP",
+        TARA_RTTY => "TARA-RTTY",
+        TN_QSO_PARTY => "TN-QSO-PARTY",
+        TX_QSO_PARTY => "TX-QSO-PARTY",
+        UBA_DX_CW => "UBA-DX-CW",
+        UBA_DX_SSB => "UBA-DX-SSB",
+        UK_DX_BPSK63 => "UK-DX-BPSK63",
+        UK_DX_RTTY => "UK-DX-RTTY",
+        UKRAINIAN_DX => "UKRAINIAN DX",
+        UKSMG_6M_MARATHON => "UKSMG-6M-MARATHON",
+        UKSMG_SUMMER_ES => "UKSMG-SUMMER-ES",
+        URE_DX => "URE-DX",
+        US_COUNTIES_QSO => "US-COUNTIES-QSO",
+        UT_QSO_PARTY => "UT-QSO-PARTY",
+        VA_QSO_PARTY => "VA-QSO-PARTY",
+        VENEZ_IND_DAY => "VENEZ-IND-DAY",
+        VIRGINIA_QSO_PARTY => "VIRGINIA QSO PARTY",
+        VOLTA_RTTY => "VOLTA-RTTY",
+        VT_QSO_PARTY => "VT-QSO-PARTY",
+        WA_QSO_PARTY => "WA-QSO-PARTY",
+        WFD => "WFD",
+        WI_QSO_PARTY => "WI-QSO-PARTY",
+        WIA_HARRY_ANGEL => "WIA-HARRY ANGEL",
+        WIA_JMMFD => "WIA-JMMFD",
+        WIA_OCDX => "WIA-OCDX",
+        WIA_REMEMBRANCE => "WIA-REMEMBRANCE",
+        WIA_ROSS_HULL => "WIA-ROSS HULL",
+        WIA_TRANS_TASMAN => "WIA-TRANS TASMAN",
+        WIA_VHF_UHF_FD => "WIA-VHF/UHF FD",
+        WIA_VK_SHIRES => "WIA-VK SHIRES",
+        WINTER_FIELD_DAY => "WINTER FIELD DAY",
+        WV_QSO_PARTY => "WV-QSO-PARTY",
+        WW_DIGI => "WW-DIGI",
+        WY_QSO_PARTY => "WY-QSO-PARTY",
+        XE_INTL_DX => "XE-INTL-DX",
+        YOHFDX => "YOHFDX",
+        YUDXC => "YUDXC"
+    }
+}
+
+adif_enumeration! {
+    AdifAntPath, "Ant_Path" {
+        G => "G",  // grayline
+        O => "O",  // other
+        S => "S",  // short path
+        L => "L"   // long path
+    }
+}
+
+adif_enumeration! {
+    AdifContinent, "Continent" {
+        NA => "NA",  // North America
+        SA => "SA",  // South America
+        EU => "EU",  // Europe
+        AF => "AF",  // Africa
+        OC => "OC",  // Oceania
+        AS => "AS",  // Asia
+        AN => "AN"   // Antarctica
+    }
+}
+
+//
+// Region: WAE and CQ regions within DXCC entities.
+//
+adif_enumeration! {
+    AdifRegion, "Region" {
+        NONE => "NONE",  // not within a WAE or CQ region that is within a DXCC entity
+        IV => "IV",      // ITU Vienna
+        AI => "AI",      // African Italy
+        SY => "SY",      // Sicily
+        BI => "BI",      // Bear Island
+        SI => "SI",      // Shetland Islands
+        KO => "KO",      // Kosovo
+        ET => "ET"       // European Turkey
+    }
+}
+
+adif_enumeration! {
+    AdifArrlSection, "ARRL_Section" {
+        AB => "AB",
+        AK => "AK",
+        AL => "AL",
+        AR => "AR",
+        AZ => "AZ",
+        BC => "BC",
+        CO => "CO",
+        CT => "CT",
+        DE => "DE",
+        EB => "EB",
+        EMA => "EMA",
+        ENY => "ENY",
+        EPA => "EPA",
+        EWA => "EWA",
+        GA => "GA",
+        GH => "GH",
+        GTA => "GTA",
+        IA => "IA",
+        ID => "ID",
+        IL => "IL",
+        IN => "IN",
+        KS => "KS",
+        KY => "KY",
+        LA => "LA",
+        LAX => "LAX",
+        MAR => "MAR",
+        MB => "MB",
+        MDC => "MDC",
+        ME => "ME",
+        MI => "MI",
+        MN => "MN",
+        MO => "MO",
+        MS => "MS",
+        MT => "MT",
+        NC => "NC",
+        ND => "ND",
+        NE => "NE",
+        NFL => "NFL",
+        NH => "NH",
+        NL => "NL",
+        NLI => "NLI",
+        NM => "NM",
+        NNJ => "NNJ",
+        NNY => "NNY",
+        NT => "NT",
+        NTX => "NTX",
+        NV => "NV",
+        OH => "OH",
+        OK => "OK",
+        ONE => "ONE",
+        ONN => "ONN",
+        ONS => "ONS",
+        OR => "OR",
+        ORG => "ORG",
+        PAC => "PAC",
+        PE => "PE",
+        PR => "PR",
+        QC => "QC",
+        RI => "RI",
+        SB => "SB",
+        SC => "SC",
+        SCV => "SCV",
+        SD => "SD",
+        SDG => "SDG",
+        SF => "SF",
+        SFL => "SFL",
+        SJV => "SJV",
+        SK => "SK",
+        SNJ => "SNJ",
+        STX => "STX",
+        SV => "SV",
+        TER => "TER",
+        TN => "TN",
+        UT => "UT",
+        VA => "VA",
+        VI => "VI",
+        VT => "VT",
+        WCF => "WCF",
+        WI => "WI",
+        WMA => "WMA",
+        WNY => "WNY",
+        WPA => "WPA",
+        WTX => "WTX",
+        WV => "WV",
+        WWA => "WWA",
+        WY => "WY"
+    }
+}
+
+//
+// Credit: awards that a QSO may be submitted for or granted.  CREDIT_SUBMITTED
+// and CREDIT_GRANTED hold comma-separated lists of these, each optionally
+// followed by a colon and the QSL media used (e.g., "DXCC:CARD&LOTW").
+//
+adif_enumeration! {
+    AdifCredit, "Credit" {
+        CQDX => "CQDX",
+        CQDX_BAND => "CQDX_BAND",
+        CQDX_MODE => "CQDX_MODE",
+        CQDX_MOBILE => "CQDX_MOBILE",
+        CQDX_QRP => "CQDX_QRP",
+        CQDX_SATELLITE => "CQDX_SATELLITE",
+        CQDXFIELD => "CQDXFIELD",
+        CQDXFIELD_BAND => "CQDXFIELD_BAND",
+        CQDXFIELD_MODE => "CQDXFIELD_MODE",
+        CQDXFIELD_MOBILE => "CQDXFIELD_MOBILE",
+        CQDXFIELD_QRP => "CQDXFIELD_QRP",
+        CQDXFIELD_SATELLITE => "CQDXFIELD_SATELLITE",
+        CQWAZ_MIXED => "CQWAZ_MIXED",
+        CQWAZ_BAND => "CQWAZ_BAND",
+        CQWAZ_MODE => "CQWAZ_MODE",
+        CQWAZ_SATELLITE => "CQWAZ_SATELLITE",
+        CQWAZ_EME => "CQWAZ_EME",
+        CQWAZ_MOBILE => "CQWAZ_MOBILE",
+        CQWAZ_QRP => "CQWAZ_QRP",
+        CQWPX => "CQWPX",
+        CQWPX_BAND => "CQWPX_BAND",
+        CQWPX_MODE => "CQWPX_MODE",
+        DXCC => "DXCC",
+        DXCC_BAND => "DXCC_BAND",
+        DXCC_MODE => "DXCC_MODE",
+        DXCC_SATELLITE => "DXCC_SATELLITE",
+        EAUSTRALIA => "EAUSTRALIA",
+        ECANADA => "ECANADA",
+        ECOUNTY_STATE => "ECOUNTY_STATE",
+        EDX => "EDX",
+        EDX100 => "EDX100",
+        EDX100_BAND => "EDX100_BAND",
+        EDX100_MODE => "EDX100_MODE",
+        EECHOLINK50 => "EECHOLINK50",
+        EGRID_BAND => "EGRID_BAND",
+        EGRID_MODE => "EGRID_MODE",
+        EHAM => "EHAM",
+        EHAM_BAND => "EHAM_BAND",
+        EHAM_MODE => "EHAM_MODE",
+        EHAM_SATELLITE => "EHAM_SATELLITE",
+        EJAPAN => "EJAPAN",
+        EPFX300 => "EPFX300",
+        EPFX300_MODE => "EPFX300_MODE",
+        EWAS => "EWAS",
+        EWAS_BAND => "EWAS_BAND",
+        EWAS_MODE => "EWAS_MODE",
+        EWAS_SATELLITE => "EWAS_SATELLITE",
+        EZ40 => "EZ40",
+        EZ40_MODE => "EZ40_MODE",
+        FFMA => "FFMA",
+        IOTA => "IOTA",
+        IOTA_BASIC => "IOTA_BASIC",
+        IOTA_CONT => "IOTA_CONT",
+        IOTA_GROUP => "IOTA_GROUP",
+        RDA => "RDA",
+        USACA => "USACA",
+        VUCC_BAND => "VUCC_BAND",
+        VUCC_SATELLITE => "VUCC_SATELLITE",
+        WAB => "WAB",
+        WAC => "WAC",
+        WAC_BAND => "WAC_BAND",
+        WAE => "WAE",
+        WAE_BAND => "WAE_BAND",
+        WAE_MODE => "WAE_MODE",
+        WAIP => "WAIP",
+        WAIP_BAND => "WAIP_BAND",
+        WAIP_MODE => "WAIP_MODE",
+        WAS => "WAS",
+        WAS_BAND => "WAS_BAND",
+        WAS_EME => "WAS_EME",
+        WAS_MODE => "WAS_MODE",
+        WAS_NOVICE => "WAS_NOVICE",
+        WAS_QRP => "WAS_QRP",
+        WAS_SATELLITE => "WAS_SATELLITE",
+        WITUZ => "WITUZ",
+        WITUZ_BAND => "WITUZ_BAND"
+    }
+}
+
+//
+// Primary_Administrative_Subdivision: states, provinces, and so on, which are
+// used in the STATE and MY_STATE fields.  The codes are specific to the DXCC
+// entity of the QSO.  ADIF defines subdivisions for many entities; this table
+// covers only the entities listed in ADIF_SUBDIVISION_ENTITIES.
+//
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdifSubdivision {
+    pub adsd_dxcc: u32,                 // DXCC entity code
+    pub adsd_code: &'static str,        // code used in ADIF files
+    pub adsd_name: &'static str,        // name of the subdivision
+}
+
+impl fmt::Display for AdifSubdivision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.adsd_code)
+    }
+}
+
+pub const ADIF_SUBDIVISION_ENTITIES : &[u32] = &[1, 6, 110, 150, 230, 291];
+
+const ADIF_SUBDIVISIONS : &[AdifSubdivision] = &[
+    // Canada
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "NS", adsd_name: "Nova Scotia" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "QC", adsd_name: "Québec" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "ON", adsd_name: "Ontario" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "MB", adsd_name: "Manitoba" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "SK", adsd_name: "Saskatchewan" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "AB", adsd_name: "Alberta" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "BC", adsd_name: "British Columbia" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "NT", adsd_name: "Northwest Territories" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "NB", adsd_name: "New Brunswick" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "NL", adsd_name: "Newfoundland and Labrador" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "YT", adsd_name: "Yukon" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "PE", adsd_name: "Prince Edward Island" },
+    AdifSubdivision { adsd_dxcc: 1, adsd_code: "NU", adsd_name: "Nunavut" },
+    // Alaska
+    AdifSubdivision { adsd_dxcc: 6, adsd_code: "AK", adsd_name: "Alaska" },
+    // Hawaii
+    AdifSubdivision { adsd_dxcc: 110, adsd_code: "HI", adsd_name: "Hawaii" },
+    // Australia
+    AdifSubdivision { adsd_dxcc: 150, adsd_code: "ACT", adsd_name: "Australian Capital Territory" },
+    AdifSubdivision { adsd_dxcc: 150, adsd_code: "NSW", adsd_name: "New South Wales" },
+    AdifSubdivision { adsd_dxcc: 150, adsd_code: "VIC", adsd_name: "Victoria" },
+    AdifSubdivision { adsd_dxcc: 150, adsd_code: "QLD", adsd_name: "Queensland" },
+    AdifSubdivision { adsd_dxcc: 150, adsd_code: "SA", adsd_name: "South Australia" },
+    AdifSubdivision { adsd_dxcc: 150, adsd_code: "WA", adsd_name: "Western Australia" },
+    AdifSubdivision { adsd_dxcc: 150, adsd_code: "TAS", adsd_name: "Tasmania" },
+    AdifSubdivision { adsd_dxcc: 150, adsd_code: "NT", adsd_name: "Northern Territory" },
+    // Federal Republic of Germany
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "BB", adsd_name: "Brandenburg" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "BE", adsd_name: "Berlin" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "BW", adsd_name: "Baden-Württemberg" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "BY", adsd_name: "Bayern" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "HB", adsd_name: "Bremen" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "HE", adsd_name: "Hessen" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "HH", adsd_name: "Hamburg" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "MV", adsd_name: "Mecklenburg-Vorpommern" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "NI", adsd_name: "Niedersachsen" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "NW", adsd_name: "Nordrhein-Westfalen" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "RP", adsd_name: "Rheinland-Pfalz" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "SH", adsd_name: "Schleswig-Holstein" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "SL", adsd_name: "Saarland" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "SN", adsd_name: "Sachsen" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "ST", adsd_name: "Sachsen-Anhalt" },
+    AdifSubdivision { adsd_dxcc: 230, adsd_code: "TH", adsd_name: "Thüringen" },
+    // United States of America
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "CT", adsd_name: "Connecticut" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "ME", adsd_name: "Maine" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "MA", adsd_name: "Massachusetts" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "NH", adsd_name: "New Hampshire" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "RI", adsd_name: "Rhode Island" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "VT", adsd_name: "Vermont" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "NJ", adsd_name: "New Jersey" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "NY", adsd_name: "New York" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "DE", adsd_name: "Delaware" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "DC", adsd_name: "District of Columbia" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "MD", adsd_name: "Maryland" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "PA", adsd_name: "Pennsylvania" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "AL", adsd_name: "Alabama" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "FL", adsd_name: "Florida" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "GA", adsd_name: "Georgia" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "KY", adsd_name: "Kentucky" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "NC", adsd_name: "North Carolina" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "SC", adsd_name: "South Carolina" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "TN", adsd_name: "Tennessee" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "VA", adsd_name: "Virginia" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "AR", adsd_name: "Arkansas" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "LA", adsd_name: "Louisiana" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "MS", adsd_name: "Mississippi" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "NM", adsd_name: "New Mexico" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "OK", adsd_name: "Oklahoma" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "TX", adsd_name: "Texas" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "CA", adsd_name: "California" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "AZ", adsd_name: "Arizona" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "ID", adsd_name: "Idaho" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "MT", adsd_name: "Montana" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "NV", adsd_name: "Nevada" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "OR", adsd_name: "Oregon" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "UT", adsd_name: "Utah" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "WA", adsd_name: "Washington" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "WY", adsd_name: "Wyoming" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "MI", adsd_name: "Michigan" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "OH", adsd_name: "Ohio" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "WV", adsd_name: "West Virginia" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "IL", adsd_name: "Illinois" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "IN", adsd_name: "Indiana" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "WI", adsd_name: "Wisconsin" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "CO", adsd_name: "Colorado" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "IA", adsd_name: "Iowa" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "KS", adsd_name: "Kansas" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "MN", adsd_name: "Minnesota" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "MO", adsd_name: "Missouri" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "NE", adsd_name: "Nebraska" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "ND", adsd_name: "North Dakota" },
+    AdifSubdivision { adsd_dxcc: 291, adsd_code: "SD", adsd_name: "South Dakota" },
+];
+
+//
+// Returns the subdivision of the given DXCC entity with the given code
+// (compared case-insensitively).  This returns None both for invalid codes and
+// for entities whose subdivisions aren't known; use
+// ADIF_SUBDIVISION_ENTITIES to tell the two apart.
+//
+pub fn adif_subdivision(dxcc: u32, code: &str) -> Option<&'static AdifSubdivision>
+{
+    ADIF_SUBDIVISIONS.iter()
+        .find(|s| s.adsd_dxcc == dxcc && s.adsd_code.eq_ignore_ascii_case(code))
+}
+
+//
+// Returns the known subdivisions of the given DXCC entity.
+//
+pub fn adif_subdivisions(dxcc: u32) -> Vec<&'static AdifSubdivision>
+{
+    ADIF_SUBDIVISIONS.iter().filter(|s| s.adsd_dxcc == dxcc).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!("ft8".parse::<AdifMode>(), Ok(AdifMode::FT8));
+        assert_eq!(AdifMode::FT8.to_string(), "FT8");
+        assert_eq!("1.25M".parse::<AdifBand>(), Ok(AdifBand::B1_25M));
+        assert_eq!(AdifBand::B1_25M.to_string(), "1.25m");
+        assert_eq!("olivia 8/250".parse::<AdifSubmode>(),
+            Ok(AdifSubmode::OLIVIA_8_250));
+        assert_eq!(AdifSubmode::OLIVIA_8_250.to_string(), "OLIVIA 8/250");
+        assert_eq!(AdifContestId::C070_160M_SPRINT.as_str(), "070-160M-SPRINT");
+        assert_eq!("es".parse::<AdifPropagationMode>(),
+            Ok(AdifPropagationMode::ES));
+
+        let error = "QRP".parse::<AdifMode>().unwrap_err();
+        assert_eq!(error.adee_enumeration, "Mode");
+        assert_eq!(error.adee_value, "QRP");
+        assert_eq!(error.to_string(), "\"QRP\" is not a valid Mode value");
+
+        //
+        // Every enumeration's values must round-trip through their spelling.
+        //
+        for v in AdifSubmode::ALL {
+            assert_eq!(v.as_str().parse::<AdifSubmode>(), Ok(*v));
+        }
+        for v in AdifContestId::ALL {
+            assert_eq!(v.as_str().parse::<AdifContestId>(), Ok(*v));
+        }
+        for v in AdifCredit::ALL {
+            assert_eq!(v.as_str().parse::<AdifCredit>(), Ok(*v));
+        }
+    }
+
+    #[test]
+    fn modes_and_submodes() {
+        assert_eq!(AdifSubmode::USB.mode(), AdifMode::SSB);
+        assert_eq!(AdifSubmode::FT4.mode(), AdifMode::MFSK);
+        assert_eq!(AdifSubmode::S8PSK125.mode(), AdifMode::PSK);
+        assert_eq!(AdifMode::SSB.submodes(),
+            vec![AdifSubmode::LSB, AdifSubmode::USB]);
+        assert!(AdifMode::FT8.submodes().is_empty());
+
+        assert_eq!(AdifMode::from_import("CW"), Ok((AdifMode::CW, None)));
+        assert_eq!(AdifMode::from_import("psk31"),
+            Ok((AdifMode::PSK, Some(AdifSubmode::PSK31))));
+        assert!(AdifMode::from_import("FT4").is_err());
+        assert!(AdifMode::from_import("USB").is_err());
+    }
+
+    #[test]
+    fn bands() {
+        assert_eq!(AdifBand::for_frequency(14.074), Some(AdifBand::B20M));
+        assert_eq!(AdifBand::for_frequency(1.8), Some(AdifBand::B160M));
+        assert_eq!(AdifBand::for_frequency(2.0), Some(AdifBand::B160M));
+        assert_eq!(AdifBand::for_frequency(446.0), Some(AdifBand::B70CM));
+        assert_eq!(AdifBand::for_frequency(12.0), None);
+        assert_eq!(AdifBand::B40M.edges(), (7.0, 7.3));
+        assert!(!AdifBand::B20M.contains(14.351));
+    }
+
+    #[test]
+    fn subdivisions() {
+        let il = adif_subdivision(291, "il").unwrap();
+        assert_eq!(il.adsd_code, "IL");
+        assert_eq!(il.adsd_name, "Illinois");
+        assert!(adif_subdivision(291, "ON").is_none());
+        assert_eq!(adif_subdivision(1, "ON").unwrap().adsd_name, "Ontario");
+        assert_eq!(adif_subdivisions(150).len(), 8);
+        assert!(adif_subdivisions(339).is_empty());
+    }
+
+    #[test]
+    fn serde() {
+        let json = serde_json::to_string(&AdifBand::B2M).unwrap();
+        assert_eq!(json, "\"2m\"");
+        let band : AdifBand = serde_json::from_str("\"2M\"").unwrap();
+        assert_eq!(band, AdifBand::B2M);
+        assert!(serde_json::from_str::<AdifQslRcvd>("\"X\"").is_err());
+    }
+}
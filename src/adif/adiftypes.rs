@@ -0,0 +1,579 @@
+//
+// src/adiftypes.rs: ADIF data types and typed field values
+//
+// Every ADIF field has a data type, which is either given explicitly by a type
+// indicator in the data specifier or implied by the field's name.  The
+// structures in this file describe those types and convert a field's textual
+// value into the corresponding Rust value.
+//
+
+use crate::Grid;
+use chrono::prelude::*;
+use std::error;
+use std::fmt;
+
+//
+// AdifType: the ADIF data types that consumers need to distinguish.  ADIF
+// defines a few more specialized types (e.g., AwardList, CreditList, and
+// IOTARefNo) that are represented as strings, and PositiveInteger and Digit,
+// which are represented here as Integer.
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdifType {
+    Boolean,
+    Date,
+    Time,
+    Number,
+    Integer,
+    Enumeration,
+    Location,
+    GridSquare,
+    String,
+    IntlString,
+    MultilineString,
+    IntlMultilineString,
+}
+
+impl AdifType {
+    //
+    // Returns the type for a data type indicator (e.g., "D" for Date).  As with
+    // field names, indicators are matched case-insensitively.
+    //
+    pub fn from_indicator(indicator: &str) -> Option<AdifType> {
+        match indicator.to_uppercase().as_str() {
+            "B" => Some(AdifType::Boolean),
+            "D" => Some(AdifType::Date),
+            "T" => Some(AdifType::Time),
+            "N" => Some(AdifType::Number),
+            "E" => Some(AdifType::Enumeration),
+            "L" => Some(AdifType::Location),
+            "S" => Some(AdifType::String),
+            "I" => Some(AdifType::IntlString),
+            "M" => Some(AdifType::MultilineString),
+            "G" => Some(AdifType::IntlMultilineString),
+            _ => None,
+        }
+    }
+
+    //
+    // Returns the data type indicator for this type, if it has one.  Integer
+    // and GridSquare values can't be given an explicit type.
+    //
+    pub fn indicator(&self) -> Option<&'static str> {
+        match self {
+            AdifType::Boolean => Some("B"),
+            AdifType::Date => Some("D"),
+            AdifType::Time => Some("T"),
+            AdifType::Number => Some("N"),
+            AdifType::Enumeration => Some("E"),
+            AdifType::Location => Some("L"),
+            AdifType::String => Some("S"),
+            AdifType::IntlString => Some("I"),
+            AdifType::MultilineString => Some("M"),
+            AdifType::IntlMultilineString => Some("G"),
+            AdifType::Integer | AdifType::GridSquare => None,
+        }
+    }
+
+    //
+    // Returns the data type indicator to use for this type in an ADI file.
+    // The Intl types are only allowed in ADX files, so they're written as
+    // their ASCII counterparts: I as S, and G as M.
+    //
+    pub fn adi_indicator(&self) -> Option<&'static str> {
+        match self {
+            AdifType::IntlString => AdifType::String.indicator(),
+            AdifType::IntlMultilineString => AdifType::MultilineString.indicator(),
+            _ => self.indicator(),
+        }
+    }
+
+    pub fn is_intl(&self) -> bool {
+        matches!(self, AdifType::IntlString | AdifType::IntlMultilineString)
+    }
+
+    //
+    // Returns the type ADIF specifies for the named field (compared
+    // case-insensitively), or None if the field isn't defined by ADIF, as for
+    // application-defined and user-defined fields.
+    //
+    pub fn for_field(name: &str) -> Option<AdifType> {
+        let name = name.to_lowercase();
+        ADIF_FIELD_TYPES.binary_search_by(|(field, _)| field.cmp(&name.as_str()))
+            .ok()
+            .map(|i| ADIF_FIELD_TYPES[i].1)
+    }
+}
+
+impl fmt::Display for AdifType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            AdifType::Boolean => "Boolean",
+            AdifType::Date => "Date",
+            AdifType::Time => "Time",
+            AdifType::Number => "Number",
+            AdifType::Integer => "Integer",
+            AdifType::Enumeration => "Enumeration",
+            AdifType::Location => "Location",
+            AdifType::GridSquare => "GridSquare",
+            AdifType::String => "String",
+            AdifType::IntlString => "IntlString",
+            AdifType::MultilineString => "MultilineString",
+            AdifType::IntlMultilineString => "IntlMultilineString",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+//
+// AdifValue: a field value converted according to its data type.  Locations are
+// in decimal degrees, with south latitudes and west longitudes negative.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdifValue {
+    Boolean(bool),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Number(f64),
+    Integer(i64),
+    Enumeration(String),
+    Location(f64),
+    GridSquare(Grid),
+    String(String),
+    IntlString(String),
+    MultilineString(String),
+    IntlMultilineString(String),
+}
+
+//
+// AdifValueError: a field's value could not be converted to its data type.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdifValueError {
+    pub adve_field: String,         // canonical field name
+    pub adve_type: AdifType,        // type the value was converted to
+    pub adve_value: String,         // the value that couldn't be converted
+}
+
+impl fmt::Display for AdifValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "field \"{}\": \"{}\" is not a valid {}", self.adve_field,
+            self.adve_value, self.adve_type)
+    }
+}
+
+impl error::Error for AdifValueError {}
+
+//
+// Convert the textual value of the named field to the given type.
+//
+pub fn adif_value(field: &str, adif_type: AdifType, value: &str) ->
+    Result<AdifValue, AdifValueError>
+{
+    let converted = match adif_type {
+        AdifType::Boolean => adif_value_boolean(value).map(AdifValue::Boolean),
+        AdifType::Date => adif_value_date(value).map(AdifValue::Date),
+        AdifType::Time => adif_value_time(value).map(AdifValue::Time),
+        AdifType::Number => adif_value_number(value).map(AdifValue::Number),
+        AdifType::Integer => adif_value_integer(value).map(AdifValue::Integer),
+        AdifType::Location => adif_value_location(value, AdifLocationAxis::for_field(field))
+            .map(AdifValue::Location),
+        AdifType::GridSquare => Grid::new(value).ok().map(AdifValue::GridSquare),
+        AdifType::Enumeration => {
+            if value.is_empty() || !adif_chars_valid(value, false, false) {
+                None
+            } else {
+                Some(AdifValue::Enumeration(value.to_string()))
+            }
+        },
+        AdifType::String => adif_chars_valid(value, false, false)
+            .then(|| AdifValue::String(value.to_string())),
+        AdifType::MultilineString => adif_chars_valid(value, false, true)
+            .then(|| AdifValue::MultilineString(value.to_string())),
+        AdifType::IntlString => adif_chars_valid(value, true, false)
+            .then(|| AdifValue::IntlString(value.to_string())),
+        AdifType::IntlMultilineString => adif_chars_valid(value, true, true)
+            .then(|| AdifValue::IntlMultilineString(value.to_string())),
+    };
+
+    converted.ok_or_else(|| AdifValueError {
+        adve_field: field.to_lowercase(),
+        adve_type: adif_type,
+        adve_value: value.to_string(),
+    })
+}
+
+//
+// Strings consist of printable ASCII characters; the Intl variants allow any
+// Unicode character except control characters.  Multiline strings may also
+// contain line breaks, which must be written as CR/LF.
+//
+fn adif_chars_valid(value: &str, intl: bool, multiline: bool) -> bool
+{
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if multiline && c == '\r' && chars.peek() == Some(&'\n') {
+            chars.next();
+            continue;
+        }
+        if c.is_control() || (!intl && !c.is_ascii()) {
+            return false;
+        }
+    }
+    true
+}
+
+fn adif_value_boolean(value: &str) -> Option<bool>
+{
+    match value {
+        "Y" | "y" => Some(true),
+        "N" | "n" => Some(false),
+        _ => None
+    }
+}
+
+// Dates are YYYYMMDD, no earlier than 1930.
+fn adif_value_date(value: &str) -> Option<NaiveDate>
+{
+    if value.len() != 8 || !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let year = value[0..4].parse().ok()?;
+    let month = value[4..6].parse().ok()?;
+    let day = value[6..8].parse().ok()?;
+    if year < 1930 {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+// Times are HHMM or HHMMSS.
+fn adif_value_time(value: &str) -> Option<NaiveTime>
+{
+    if (value.len() != 4 && value.len() != 6) ||
+       !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let hour = value[0..2].parse().ok()?;
+    let min = value[2..4].parse().ok()?;
+    let sec = if value.len() == 6 { value[4..6].parse().ok()? } else { 0 };
+    NaiveTime::from_hms_opt(hour, min, sec)
+}
+
+//
+// Numbers are an optional minus sign, digits, and an optional decimal point
+// followed by more digits.  ADIF doesn't allow a leading plus sign or exponent,
+// both of which str::parse() would accept, so check the format first.
+//
+fn adif_value_number(value: &str) -> Option<f64>
+{
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let mut parts = digits.splitn(2, '.');
+    let whole = parts.next().unwrap_or("");
+    let frac = parts.next().unwrap_or("");
+    if (whole.is_empty() && frac.is_empty()) ||
+       !whole.chars().all(|c| c.is_ascii_digit()) ||
+       !frac.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+fn adif_value_integer(value: &str) -> Option<i64>
+{
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+//
+// Which hemisphere letters a location field may use: N or S for the latitude
+// fields, E or W for the longitude fields, and any of them for user-defined
+// fields of type Location, which could be either.
+//
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AdifLocationAxis {
+    Latitude,
+    Longitude,
+    Either,
+}
+
+impl AdifLocationAxis {
+    fn for_field(field: &str) -> AdifLocationAxis {
+        match field.to_lowercase().as_str() {
+            "lat" | "my_lat" => AdifLocationAxis::Latitude,
+            "lon" | "my_lon" => AdifLocationAxis::Longitude,
+            _ => AdifLocationAxis::Either,
+        }
+    }
+}
+
+//
+// Locations are written "XDDD MM.MMM", where X is one of N, S, E, or W, DDD is
+// degrees (000 to 180), and MM.MMM is minutes.
+//
+fn adif_value_location(value: &str, axis: AdifLocationAxis) -> Option<f64>
+{
+    let bytes = value.as_bytes();
+    if bytes.len() != 11 || bytes[4] != b' ' || bytes[7] != b'.' {
+        return None;
+    }
+
+    let latitude = axis != AdifLocationAxis::Longitude;
+    let longitude = axis != AdifLocationAxis::Latitude;
+    let (sign, limit) = match bytes[0] {
+        b'N' | b'n' if latitude => (1.0, 90.0),
+        b'S' | b's' if latitude => (-1.0, 90.0),
+        b'E' | b'e' if longitude => (1.0, 180.0),
+        b'W' | b'w' if longitude => (-1.0, 180.0),
+        _ => return None
+    };
+
+    let degrees = &value[1..4];
+    let minutes = &value[5..11];
+    if !degrees.chars().all(|c| c.is_ascii_digit()) ||
+       !minutes.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+
+    let degrees : f64 = degrees.parse().ok()?;
+    let minutes : f64 = minutes.parse().ok()?;
+    let location = degrees + minutes / 60.0;
+    if minutes >= 60.0 || location > limit {
+        return None;
+    }
+    Some(sign * location)
+}
+
+//
+// The data type of each field defined by ADIF, sorted by field name so that it
+// can be searched with a binary search.
+//
+const ADIF_FIELD_TYPES : &[(&str, AdifType)] = &[
+    ("a_index", AdifType::Number),
+    ("address", AdifType::MultilineString),
+    ("address_intl", AdifType::IntlMultilineString),
+    ("adif_ver", AdifType::String),
+    ("age", AdifType::Number),
+    ("altitude", AdifType::Number),
+    ("ant_az", AdifType::Number),
+    ("ant_el", AdifType::Number),
+    ("ant_path", AdifType::Enumeration),
+    ("arrl_sect", AdifType::Enumeration),
+    ("award_granted", AdifType::String),
+    ("award_submitted", AdifType::String),
+    ("band", AdifType::Enumeration),
+    ("band_rx", AdifType::Enumeration),
+    ("call", AdifType::String),
+    ("check", AdifType::String),
+    ("class", AdifType::String),
+    ("clublog_qso_upload_date", AdifType::Date),
+    ("clublog_qso_upload_status", AdifType::Enumeration),
+    ("cnty", AdifType::Enumeration),
+    ("comment", AdifType::String),
+    ("comment_intl", AdifType::IntlString),
+    ("cont", AdifType::Enumeration),
+    ("contacted_op", AdifType::String),
+    ("contest_id", AdifType::String),
+    ("country", AdifType::String),
+    ("country_intl", AdifType::IntlString),
+    ("cqz", AdifType::Integer),
+    ("created_timestamp", AdifType::String),
+    ("credit_granted", AdifType::String),
+    ("credit_submitted", AdifType::String),
+    ("darc_dok", AdifType::Enumeration),
+    ("distance", AdifType::Number),
+    ("dxcc", AdifType::Enumeration),
+    ("email", AdifType::String),
+    ("eq_call", AdifType::String),
+    ("eqsl_qsl_rcvd", AdifType::Enumeration),
+    ("eqsl_qsl_sent", AdifType::Enumeration),
+    ("eqsl_qslrdate", AdifType::Date),
+    ("eqsl_qslsdate", AdifType::Date),
+    ("fists", AdifType::Integer),
+    ("fists_cc", AdifType::Integer),
+    ("force_init", AdifType::Boolean),
+    ("freq", AdifType::Number),
+    ("freq_rx", AdifType::Number),
+    ("gridsquare", AdifType::GridSquare),
+    ("gridsquare_ext", AdifType::String),
+    ("guest_op", AdifType::String),
+    ("hamlogeu_qso_upload_date", AdifType::Date),
+    ("hamlogeu_qso_upload_status", AdifType::Enumeration),
+    ("hamqth_qso_upload_date", AdifType::Date),
+    ("hamqth_qso_upload_status", AdifType::Enumeration),
+    ("hrdlog_qso_upload_date", AdifType::Date),
+    ("hrdlog_qso_upload_status", AdifType::Enumeration),
+    ("iota", AdifType::String),
+    ("iota_island_id", AdifType::Integer),
+    ("ituz", AdifType::Integer),
+    ("k_index", AdifType::Integer),
+    ("lat", AdifType::Location),
+    ("lon", AdifType::Location),
+    ("lotw_qsl_rcvd", AdifType::Enumeration),
+    ("lotw_qsl_sent", AdifType::Enumeration),
+    ("lotw_qslrdate", AdifType::Date),
+    ("lotw_qslsdate", AdifType::Date),
+    ("max_bursts", AdifType::Number),
+    ("mode", AdifType::Enumeration),
+    ("ms_shower", AdifType::String),
+    ("my_altitude", AdifType::Number),
+    ("my_antenna", AdifType::String),
+    ("my_antenna_intl", AdifType::IntlString),
+    ("my_arrl_sect", AdifType::Enumeration),
+    ("my_city", AdifType::String),
+    ("my_city_intl", AdifType::IntlString),
+    ("my_cnty", AdifType::Enumeration),
+    ("my_country", AdifType::String),
+    ("my_country_intl", AdifType::IntlString),
+    ("my_cq_zone", AdifType::Integer),
+    ("my_dxcc", AdifType::Enumeration),
+    ("my_fists", AdifType::Integer),
+    ("my_gridsquare", AdifType::GridSquare),
+    ("my_gridsquare_ext", AdifType::String),
+    ("my_iota", AdifType::String),
+    ("my_iota_island_id", AdifType::Integer),
+    ("my_itu_zone", AdifType::Integer),
+    ("my_lat", AdifType::Location),
+    ("my_lon", AdifType::Location),
+    ("my_name", AdifType::String),
+    ("my_name_intl", AdifType::IntlString),
+    ("my_postal_code", AdifType::String),
+    ("my_postal_code_intl", AdifType::IntlString),
+    ("my_pota_ref", AdifType::String),
+    ("my_rig", AdifType::String),
+    ("my_rig_intl", AdifType::IntlString),
+    ("my_sig", AdifType::String),
+    ("my_sig_info", AdifType::String),
+    ("my_sig_info_intl", AdifType::IntlString),
+    ("my_sig_intl", AdifType::IntlString),
+    ("my_sota_ref", AdifType::String),
+    ("my_state", AdifType::Enumeration),
+    ("my_street", AdifType::String),
+    ("my_street_intl", AdifType::IntlString),
+    ("my_usaca_counties", AdifType::String),
+    ("my_vucc_grids", AdifType::String),
+    ("my_wwff_ref", AdifType::String),
+    ("name", AdifType::String),
+    ("name_intl", AdifType::IntlString),
+    ("notes", AdifType::MultilineString),
+    ("notes_intl", AdifType::IntlMultilineString),
+    ("nr_bursts", AdifType::Integer),
+    ("nr_pings", AdifType::Integer),
+    ("operator", AdifType::String),
+    ("owner_callsign", AdifType::String),
+    ("pfx", AdifType::String),
+    ("pota_ref", AdifType::String),
+    ("precedence", AdifType::String),
+    ("programid", AdifType::String),
+    ("programversion", AdifType::String),
+    ("prop_mode", AdifType::Enumeration),
+    ("public_key", AdifType::String),
+    ("qrzcom_qso_upload_date", AdifType::Date),
+    ("qrzcom_qso_upload_status", AdifType::Enumeration),
+    ("qsl_rcvd", AdifType::Enumeration),
+    ("qsl_rcvd_via", AdifType::Enumeration),
+    ("qsl_sent", AdifType::Enumeration),
+    ("qsl_sent_via", AdifType::Enumeration),
+    ("qsl_via", AdifType::String),
+    ("qslmsg", AdifType::MultilineString),
+    ("qslmsg_intl", AdifType::IntlMultilineString),
+    ("qslrdate", AdifType::Date),
+    ("qslsdate", AdifType::Date),
+    ("qso_complete", AdifType::Enumeration),
+    ("qso_date", AdifType::Date),
+    ("qso_date_off", AdifType::Date),
+    ("qso_random", AdifType::Boolean),
+    ("qth", AdifType::String),
+    ("qth_intl", AdifType::IntlString),
+    ("region", AdifType::Enumeration),
+    ("rig", AdifType::MultilineString),
+    ("rig_intl", AdifType::IntlMultilineString),
+    ("rst_rcvd", AdifType::String),
+    ("rst_sent", AdifType::String),
+    ("rx_pwr", AdifType::Number),
+    ("sat_mode", AdifType::String),
+    ("sat_name", AdifType::String),
+    ("sfi", AdifType::Integer),
+    ("sig", AdifType::String),
+    ("sig_info", AdifType::String),
+    ("sig_info_intl", AdifType::IntlString),
+    ("sig_intl", AdifType::IntlString),
+    ("silent_key", AdifType::Boolean),
+    ("skcc", AdifType::String),
+    ("sota_ref", AdifType::String),
+    ("srx", AdifType::Integer),
+    ("srx_string", AdifType::String),
+    ("state", AdifType::Enumeration),
+    ("station_callsign", AdifType::String),
+    ("stx", AdifType::Integer),
+    ("stx_string", AdifType::String),
+    ("submode", AdifType::String),
+    ("swl", AdifType::Boolean),
+    ("ten_ten", AdifType::Integer),
+    ("time_off", AdifType::Time),
+    ("time_on", AdifType::Time),
+    ("tx_pwr", AdifType::Number),
+    ("uksmg", AdifType::Integer),
+    ("usaca_counties", AdifType::String),
+    ("ve_prov", AdifType::String),
+    ("vucc_grids", AdifType::String),
+    ("web", AdifType::String),
+    ("wwff_ref", AdifType::String),
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn field_table_sorted() {
+        for pair in ADIF_FIELD_TYPES.windows(2) {
+            assert!(pair[0].0 < pair[1].0, "{} >= {}", pair[0].0, pair[1].0);
+        }
+        assert_eq!(AdifType::for_field("QSO_DATE"), Some(AdifType::Date));
+        assert_eq!(AdifType::for_field("gridsquare"), Some(AdifType::GridSquare));
+        assert_eq!(AdifType::for_field("app_n1mm_exchange1"), None);
+    }
+
+    #[test]
+    fn convert_values() {
+        assert_eq!(adif_value("swl", AdifType::Boolean, "y"),
+            Ok(AdifValue::Boolean(true)));
+        assert_eq!(adif_value("qso_date", AdifType::Date, "20200229"),
+            Ok(AdifValue::Date(NaiveDate::from_ymd_opt(2020, 2, 29).unwrap())));
+        assert!(adif_value("qso_date", AdifType::Date, "20190229").is_err());
+        assert!(adif_value("qso_date", AdifType::Date, "19291231").is_err());
+        assert_eq!(adif_value("time_on", AdifType::Time, "235959"),
+            Ok(AdifValue::Time(NaiveTime::from_hms_opt(23, 59, 59).unwrap())));
+        assert!(adif_value("time_on", AdifType::Time, "2400").is_err());
+        assert_eq!(adif_value("freq", AdifType::Number, "-.5"),
+            Ok(AdifValue::Number(-0.5)));
+        assert!(adif_value("freq", AdifType::Number, "1e6").is_err());
+        assert!(adif_value("freq", AdifType::Number, "+14").is_err());
+        assert_eq!(adif_value("cqz", AdifType::Integer, "05"),
+            Ok(AdifValue::Integer(5)));
+        assert_eq!(adif_value("lat", AdifType::Location, "S033 52.500"),
+            Ok(AdifValue::Location(-33.875)));
+        assert!(adif_value("lat", AdifType::Location, "N091 00.000").is_err());
+        assert!(adif_value("lon", AdifType::Location, "W120 60.000").is_err());
+        assert!(adif_value("lat", AdifType::Location, "E010 00.000").is_err());
+        assert!(adif_value("LON", AdifType::Location, "N041 00.000").is_err());
+        assert!(adif_value("my_lat", AdifType::Location, "W073 00.000").is_err());
+        assert_eq!(adif_value("my_lon", AdifType::Location, "W073 30.000"),
+            Ok(AdifValue::Location(-73.5)));
+        assert!(adif_value("spot", AdifType::Location, "E010 00.000").is_ok());
+        assert!(adif_value("spot", AdifType::Location, "N041 00.000").is_ok());
+        assert!(adif_value("name", AdifType::String, "Jos\u{e9}").is_err());
+        assert!(adif_value("name_intl", AdifType::IntlString, "Jos\u{e9}").is_ok());
+        assert!(adif_value("notes", AdifType::MultilineString, "a\r\nb").is_ok());
+        assert!(adif_value("notes", AdifType::MultilineString, "a\nb").is_err());
+
+        let err = adif_value("QSO_DATE", AdifType::Date, "2020").unwrap_err();
+        assert_eq!(err.to_string(), "field \"qso_date\": \"2020\" is not a valid Date");
+    }
+}
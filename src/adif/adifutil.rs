@@ -48,4 +48,31 @@ pub fn byteseq_equal_ci(bytes: &Vec<u8>, s: &str) -> bool
     return true;
 }
 
+//
+// Decode bytes as Windows-1252, the character set most often used by Windows
+// logging programs that predate UTF-8 support.  This is a superset of the
+// printable characters of ISO-8859-1: bytes 0x80 through 0x9F are mostly
+// punctuation rather than control characters.  The five bytes that
+// Windows-1252 leaves undefined are decoded as the corresponding ISO-8859-1
+// control characters, so decoding never fails.
+//
+pub fn cp1252_decode(bytes: &[u8]) -> String
+{
+    const CP1252_HIGH : [char; 32] = [
+        '\u{20ac}', '\u{0081}', '\u{201a}', '\u{0192}',    // 0x80
+        '\u{201e}', '\u{2026}', '\u{2020}', '\u{2021}',
+        '\u{02c6}', '\u{2030}', '\u{0160}', '\u{2039}',    // 0x88
+        '\u{0152}', '\u{008d}', '\u{017d}', '\u{008f}',
+        '\u{0090}', '\u{2018}', '\u{2019}', '\u{201c}',    // 0x90
+        '\u{201d}', '\u{2022}', '\u{2013}', '\u{2014}',
+        '\u{02dc}', '\u{2122}', '\u{0161}', '\u{203a}',    // 0x98
+        '\u{0153}', '\u{009d}', '\u{017e}', '\u{0178}'
+    ];
+
+    bytes.iter().map(|&b| match b {
+        0x80..=0x9f => CP1252_HIGH[(b - 0x80) as usize],
+        _ => b as char
+    }).collect()
+}
+
 // TODO add tests
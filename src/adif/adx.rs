@@ -0,0 +1,465 @@
+//
+// src/adx.rs: implementation of ADX physical file format import and export
+//
+
+//
+// ADX is the XML-based physical format for ADIF.  Rather than define a separate
+// set of structures for it, we translate ADX to and from the same low-level
+// elements used for ADI (AdiFile, AdiRecord, and AdiDataSpecifier).  The
+// logical ADIF parser then doesn't need to know which format a file used.
+//
+// Most ADX elements map directly onto data specifiers: the element name is the
+// field name and the element's text is the value.  The exceptions are:
+//
+//   <USERDEF FIELDID="n" TYPE="t" ENUM="{...}"> in the header, which ADI
+//   represents as a "USERDEFn" data specifier of type "t" whose value is the
+//   field name followed by the enumeration or range, as in
+//   "SWEATERSIZE,{S,M,L}".
+//
+//   <APP PROGRAMID="p" FIELDNAME="f" TYPE="t"> in a record, which ADI
+//   represents as an "APP_p_f" data specifier of type "t".
+//
+//   <USERDEF FIELDNAME="f"> in a record, which ADI represents as a data
+//   specifier called "f".
+//
+
+use std::collections::BTreeSet;
+use std::io;
+
+use super::adi::AdiDataSpecifier;
+use super::adi::AdiFile;
+use super::adi::AdiHeader;
+use super::adi::AdiRecord;
+use super::AdifInputError;
+use super::AdifInputErrorKind;
+use super::AdifParseError;
+use super::AdifPosition;
+use crate::xml;
+use crate::xml::XmlElement;
+
+const ADX_ELEM_ROOT : &str = "ADX";
+const ADX_ELEM_HEADER : &str = "HEADER";
+const ADX_ELEM_RECORDS : &str = "RECORDS";
+const ADX_ELEM_RECORD : &str = "RECORD";
+const ADX_ELEM_APP : &str = "APP";
+const ADX_ELEM_USERDEF : &str = "USERDEF";
+
+//
+// ADX Import
+//
+
+//
+// General entry point for parsing an ADX file from an input source.
+//
+pub fn adx_parse(source: &mut dyn io::Read) -> Result<AdiFile, AdifParseError>
+{
+    let mut bytes : Vec<u8> = Vec::new();
+    source.read_to_end(&mut bytes)?;
+    let lines = adx_line_starts(&bytes);
+    let text = match String::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(e) => {
+            return Err(adx_error(&lines, e.utf8_error().valid_up_to(),
+                AdifInputErrorKind::ADIE_ENCODING,
+                "ADX input is not valid UTF-8".to_string()));
+        }
+    };
+
+    let root = match xml::xml_parse(&text) {
+        Ok(root) => root,
+        Err(e) => return Err(adx_error(&lines, e.offset,
+            AdifInputErrorKind::ADIE_SYNTAX, e.message))
+    };
+
+    if !root.name.eq_ignore_ascii_case(ADX_ELEM_ROOT) {
+        return Err(adx_error(&lines, root.offset,
+            AdifInputErrorKind::ADIE_STRUCTURE, format!(
+            "expected root element \"{}\", but found \"{}\"",
+            ADX_ELEM_ROOT, root.name)));
+    }
+
+    let header = match root.element(ADX_ELEM_HEADER) {
+        None => None,
+        Some(elem) => Some(adx_parse_header(elem, &lines)?)
+    };
+
+    let mut records : Vec<AdiRecord> = Vec::new();
+    if let Some(elem) = root.element(ADX_ELEM_RECORDS) {
+        for rec in elem.elements() {
+            if !rec.name.eq_ignore_ascii_case(ADX_ELEM_RECORD) {
+                return Err(adx_error(&lines, rec.offset,
+                    AdifInputErrorKind::ADIE_STRUCTURE, format!(
+                    "expected element \"{}\", but found \"{}\"",
+                    ADX_ELEM_RECORD, rec.name)));
+            }
+            records.push(adx_parse_record(rec, &lines)?);
+        }
+    }
+
+    Ok(AdiFile {
+        adi_header: header,
+        adi_records: records,
+        adi_warnings: Vec::new()
+    })
+}
+
+//
+// Returns the byte offset at which each line of the input starts, for use with
+// adx_position().
+//
+fn adx_line_starts(bytes: &[u8]) -> Vec<usize>
+{
+    let mut starts = vec![0];
+    starts.extend(bytes.iter().enumerate()
+        .filter(|&(_, &c)| c == b'\n')
+        .map(|(i, _)| i + 1));
+    starts
+}
+
+fn adx_position(lines: &[usize], offset: usize) -> AdifPosition
+{
+    let line = lines.partition_point(|&start| start <= offset);
+    AdifPosition {
+        adpp_offset: offset,
+        adpp_line: line,
+        adpp_column: offset - lines[line - 1] + 1
+    }
+}
+
+fn adx_error(lines: &[usize], offset: usize, kind: AdifInputErrorKind,
+    message: String) -> AdifParseError
+{
+    AdifParseError::ADIF_EBADINPUT(AdifInputError::new(kind, message)
+        .with_position(adx_position(lines, offset)))
+}
+
+fn adx_parse_header(elem: &XmlElement, lines: &[usize]) ->
+    Result<AdiHeader, AdifParseError>
+{
+    let mut fields : Vec<AdiDataSpecifier> = Vec::new();
+
+    for field in elem.elements() {
+        if field.name.eq_ignore_ascii_case(ADX_ELEM_USERDEF) {
+            let fieldid = adx_required_attribute(field, "FIELDID", lines)?;
+            let mut value = field.text();
+            if let Some(values) = field.attribute("ENUM") {
+                value.push(',');
+                value.push_str(values);
+            } else if let Some(range) = field.attribute("RANGE") {
+                value.push(',');
+                value.push_str(range);
+            }
+            fields.push(adx_data_specifier(
+                &format!("{}{}", ADX_ELEM_USERDEF, fieldid), value,
+                field.attribute("TYPE"), adx_position(lines, field.offset)));
+        } else {
+            fields.push(adx_data_specifier(&field.name, field.text(), None,
+                adx_position(lines, field.offset)));
+        }
+    }
+
+    Ok(AdiHeader {
+        adih_content: Vec::new(),
+        adih_fields: fields
+    })
+}
+
+fn adx_parse_record(elem: &XmlElement, lines: &[usize]) ->
+    Result<AdiRecord, AdifParseError>
+{
+    let mut fields : Vec<AdiDataSpecifier> = Vec::new();
+
+    for field in elem.elements() {
+        if field.name.eq_ignore_ascii_case(ADX_ELEM_APP) {
+            let programid = adx_required_attribute(field, "PROGRAMID", lines)?;
+            let fieldname = adx_required_attribute(field, "FIELDNAME", lines)?;
+            fields.push(adx_data_specifier(
+                &format!("{}_{}_{}", ADX_ELEM_APP, programid, fieldname),
+                field.text(), field.attribute("TYPE"),
+                adx_position(lines, field.offset)));
+        } else if field.name.eq_ignore_ascii_case(ADX_ELEM_USERDEF) {
+            let fieldname = adx_required_attribute(field, "FIELDNAME", lines)?;
+            fields.push(adx_data_specifier(fieldname, field.text(), None,
+                adx_position(lines, field.offset)));
+        } else {
+            fields.push(adx_data_specifier(&field.name, field.text(), None,
+                adx_position(lines, field.offset)));
+        }
+    }
+
+    Ok(AdiRecord {
+        adir_fields: fields
+    })
+}
+
+fn adx_required_attribute<'a>(elem: &'a XmlElement, name: &str,
+    lines: &[usize]) -> Result<&'a str, AdifParseError>
+{
+    match elem.attribute(name) {
+        Some(value) => Ok(value),
+        None => Err(adx_error(lines, elem.offset,
+            AdifInputErrorKind::ADIE_STRUCTURE, format!(
+            "element \"{}\" is missing attribute \"{}\"", elem.name, name)))
+    }
+}
+
+fn adx_data_specifier(name: &str, value: String, adif_type: Option<&str>,
+    position: AdifPosition) -> AdiDataSpecifier
+{
+    AdiDataSpecifier {
+        adif_name: name.to_string(),
+        adif_name_canon: name.to_lowercase(),
+        adif_length: value.len(),
+        adif_bytes: value.into_bytes(),
+        adif_type: adif_type.map(|t| t.to_string()),
+        adif_position: position
+    }
+}
+
+//
+// ADX Export
+//
+
+//
+// Write the given file to the output stream as ADX.  Free-form header content
+// has no equivalent in ADX, so it's dropped.  Values must be valid UTF-8.
+//
+pub fn adx_write(adf: &AdiFile, sink: &mut dyn io::Write) -> io::Result<()>
+{
+    //
+    // Record fields that were declared with USERDEF in the header have to be
+    // written as USERDEF elements, so collect their names first.
+    //
+    let mut userdefs : BTreeSet<String> = BTreeSet::new();
+
+    writeln!(sink, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(sink, "<{}>", ADX_ELEM_ROOT)?;
+
+    if let Some(ref adh) = adf.adi_header {
+        writeln!(sink, "  <{}>", ADX_ELEM_HEADER)?;
+        for field in &adh.adih_fields {
+            let value = adx_value(field)?;
+            match adx_userdef_id(field) {
+                Some(fieldid) => {
+                    let (name, rest) = match value.find(',') {
+                        Some(i) => (&value[..i], Some(&value[i + 1..])),
+                        None => (value, None)
+                    };
+                    userdefs.insert(name.to_lowercase());
+                    write!(sink, "    <{} FIELDID=\"{}\"", ADX_ELEM_USERDEF,
+                        fieldid)?;
+                    if let Some(ref t) = field.adif_type {
+                        write!(sink, " TYPE=\"{}\"", xml::xml_escape(t))?;
+                    }
+                    if let Some(rest) = rest {
+                        let attr = if rest.contains(':') { "RANGE" } else { "ENUM" };
+                        write!(sink, " {}=\"{}\"", attr, xml::xml_escape(rest))?;
+                    }
+                    writeln!(sink, ">{}</{}>", xml::xml_escape(name),
+                        ADX_ELEM_USERDEF)?;
+                }
+                None => {
+                    let name = field.adif_name.to_uppercase();
+                    writeln!(sink, "    <{}>{}</{}>", name,
+                        xml::xml_escape(value), name)?;
+                }
+            }
+        }
+        writeln!(sink, "  </{}>", ADX_ELEM_HEADER)?;
+    }
+
+    writeln!(sink, "  <{}>", ADX_ELEM_RECORDS)?;
+    for rec in &adf.adi_records {
+        writeln!(sink, "    <{}>", ADX_ELEM_RECORD)?;
+        for field in &rec.adir_fields {
+            let value = xml::xml_escape(adx_value(field)?);
+            let canon = &field.adif_name_canon;
+            if let Some((programid, fieldname)) = adx_app_field(&field.adif_name) {
+                write!(sink, "      <{} PROGRAMID=\"{}\" FIELDNAME=\"{}\"",
+                    ADX_ELEM_APP, xml::xml_escape(programid),
+                    xml::xml_escape(fieldname))?;
+                if let Some(ref t) = field.adif_type {
+                    write!(sink, " TYPE=\"{}\"", xml::xml_escape(t))?;
+                }
+                writeln!(sink, ">{}</{}>", value, ADX_ELEM_APP)?;
+            } else if userdefs.contains(canon) {
+                writeln!(sink, "      <{} FIELDNAME=\"{}\">{}</{}>",
+                    ADX_ELEM_USERDEF, xml::xml_escape(&field.adif_name), value,
+                    ADX_ELEM_USERDEF)?;
+            } else {
+                let name = field.adif_name.to_uppercase();
+                writeln!(sink, "      <{}>{}</{}>", name, value, name)?;
+            }
+        }
+        writeln!(sink, "    </{}>", ADX_ELEM_RECORD)?;
+    }
+    writeln!(sink, "  </{}>", ADX_ELEM_RECORDS)?;
+    writeln!(sink, "</{}>", ADX_ELEM_ROOT)
+}
+
+fn adx_value(field: &AdiDataSpecifier) -> io::Result<&str>
+{
+    std::str::from_utf8(&field.adif_bytes).map_err(|_| io::Error::new(
+        io::ErrorKind::InvalidData, format!(
+        "field \"{}\": value is not valid UTF-8", field.adif_name)))
+}
+
+//
+// If this header field is a USERDEF declaration, return its field id.
+//
+fn adx_userdef_id(field: &AdiDataSpecifier) -> Option<&str>
+{
+    let prefix = ADX_ELEM_USERDEF.len();
+    if field.adif_name_canon.len() > prefix &&
+       field.adif_name_canon[..prefix].eq_ignore_ascii_case(ADX_ELEM_USERDEF) &&
+       field.adif_name_canon[prefix..].chars().all(|c| c.is_ascii_digit()) {
+        Some(&field.adif_name[prefix..])
+    } else {
+        None
+    }
+}
+
+//
+// If this is an application-defined field (i.e., "APP_PROGRAMID_FIELDNAME"),
+// return the program id and field name.  Program ids can't contain
+// underscores, so the first underscore after the "APP_" prefix separates them.
+//
+fn adx_app_field(name: &str) -> Option<(&str, &str)>
+{
+    let prefix = ADX_ELEM_APP.len() + 1;
+    if name.len() <= prefix || !name[..prefix].eq_ignore_ascii_case("app_") {
+        return None;
+    }
+
+    let rest = &name[prefix..];
+    match rest.find('_') {
+        Some(i) if i > 0 && i + 1 < rest.len() => Some((&rest[..i], &rest[i + 1..])),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io;
+    use super::AdifInputErrorKind;
+    use super::AdifParseError;
+
+    const ADX_SAMPLE : &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ADX>
+  <HEADER>
+    <ADIF_VER>3.1.0</ADIF_VER>
+    <PROGRAMID>monolog</PROGRAMID>
+    <USERDEF FIELDID="1" TYPE="N">EPC</USERDEF>
+    <USERDEF FIELDID="2" TYPE="E" ENUM="{S,M,L}">SWEATERSIZE</USERDEF>
+    <USERDEF FIELDID="3" TYPE="N" RANGE="{5:20}">SHOESIZE</USERDEF>
+  </HEADER>
+  <RECORDS>
+    <RECORD>
+      <QSO_DATE>19900620</QSO_DATE>
+      <TIME_ON>1523</TIME_ON>
+      <CALL>VK9NS</CALL>
+      <BAND>20M</BAND>
+      <MODE>RTTY</MODE>
+      <USERDEF FIELDNAME="SWEATERSIZE">M</USERDEF>
+      <USERDEF FIELDNAME="SHOESIZE">11</USERDEF>
+      <APP PROGRAMID="MONOLOG" FIELDNAME="Compression" TYPE="S">off</APP>
+    </RECORD>
+    <RECORD>
+      <QSO_DATE>20101022</QSO_DATE>
+      <CALL>ON4UN</CALL>
+      <COMMENT>a &lt;b&gt; &amp; c</COMMENT>
+    </RECORD>
+  </RECORDS>
+</ADX>
+"#;
+
+    fn field<'a>(rec: &'a super::AdiRecord, name: &str) -> &'a super::AdiDataSpecifier {
+        rec.adir_fields.iter().find(|f| f.adif_name_canon == name).unwrap()
+    }
+
+    #[test]
+    fn parse_sample() {
+        let mut source = io::Cursor::new(ADX_SAMPLE);
+        let adf = super::adx_parse(&mut source).unwrap();
+
+        let header = adf.adi_header.as_ref().unwrap();
+        assert_eq!(header.adih_fields.len(), 5);
+        assert_eq!(header.adih_fields[3].adif_name_canon, "userdef2");
+        assert_eq!(header.adih_fields[3].adif_bytes, b"SWEATERSIZE,{S,M,L}".to_vec());
+        assert_eq!(header.adih_fields[3].adif_type.as_deref(), Some("E"));
+
+        assert_eq!(adf.adi_records.len(), 2);
+        let rec = &adf.adi_records[0];
+        assert_eq!(field(rec, "call").adif_bytes, b"VK9NS".to_vec());
+        assert_eq!(field(rec, "sweatersize").adif_bytes, b"M".to_vec());
+        assert_eq!(field(rec, "app_monolog_compression").adif_bytes, b"off".to_vec());
+        assert_eq!(field(&adf.adi_records[1], "comment").adif_bytes,
+            b"a <b> & c".to_vec());
+    }
+
+    #[test]
+    fn parse_with_bom() {
+        let input = format!("\u{feff}{}", ADX_SAMPLE);
+        let mut source = io::Cursor::new(input);
+        let adif = crate::adif::adif_parse("bom", &mut source).unwrap();
+        assert_eq!(adif.adif_records.len(), 2);
+        assert_eq!(adif.adif_records[0].adir_field_values["call"], "VK9NS");
+    }
+
+    #[test]
+    fn write_roundtrip() {
+        let mut source = io::Cursor::new(ADX_SAMPLE);
+        let adf = super::adx_parse(&mut source).unwrap();
+
+        let mut output : Vec<u8> = Vec::new();
+        super::adx_write(&adf, &mut output).unwrap();
+        let text = String::from_utf8(output.clone()).unwrap();
+        assert!(text.contains(
+            "<USERDEF FIELDID=\"3\" TYPE=\"N\" RANGE=\"{5:20}\">SHOESIZE</USERDEF>"));
+        assert!(text.contains("<USERDEF FIELDNAME=\"SWEATERSIZE\">M</USERDEF>"));
+        assert!(text.contains(
+            "<APP PROGRAMID=\"MONOLOG\" FIELDNAME=\"Compression\" TYPE=\"S\">off</APP>"));
+
+        let mut reader = io::Cursor::new(output);
+        let reparsed = super::adx_parse(&mut reader).unwrap();
+        assert_eq!(reparsed.adi_records.len(), adf.adi_records.len());
+        for (orig, new) in adf.adi_records.iter().zip(&reparsed.adi_records) {
+            assert_eq!(orig.adir_fields.len(), new.adir_fields.len());
+            for (a, b) in orig.adir_fields.iter().zip(&new.adir_fields) {
+                assert_eq!(a.adif_name_canon, b.adif_name_canon);
+                assert_eq!(a.adif_bytes, b.adif_bytes);
+                assert_eq!(a.adif_type, b.adif_type);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let mut source = io::Cursor::new("<NOTADX></NOTADX>");
+        assert!(super::adx_parse(&mut source).is_err());
+        let mut source = io::Cursor::new(
+            "<ADX><RECORDS><RECORD><APP>x</APP></RECORD></RECORDS></ADX>");
+        assert!(super::adx_parse(&mut source).is_err());
+
+        // Errors report where in the document the problem is.
+        let mut source = io::Cursor::new(
+            "<ADX>\n<RECORDS>\n  <RECORD><APP>x</APP></RECORD>\n</RECORDS></ADX>");
+        match super::adx_parse(&mut source) {
+            Err(AdifParseError::ADIF_EBADINPUT(e)) => {
+                assert_eq!(e.adie_kind, AdifInputErrorKind::ADIE_STRUCTURE);
+                let position = e.adie_position.unwrap();
+                assert_eq!((position.adpp_line, position.adpp_column), (3, 11));
+                assert_eq!(position.adpp_offset, 26);
+            },
+            _ => panic!("expected input error")
+        }
+        let mut source = io::Cursor::new("<ADX>\n<HEADER>\n</ADX>");
+        match super::adx_parse(&mut source) {
+            Err(AdifParseError::ADIF_EBADINPUT(e)) => {
+                assert_eq!(e.adie_kind, AdifInputErrorKind::ADIE_SYNTAX);
+                assert_eq!(e.adie_position.unwrap().adpp_line, 3);
+            },
+            _ => panic!("expected input error")
+        }
+    }
+}
@@ -5,34 +5,69 @@ use crate::lotw::LoTWStatus;
 // exchanging data about amateur radio contacts ("QSOs").  This crate seeks to
 // implement an ADIF importer and a small reporting program.
 //
-// As of this writing, the latest ADIF standard is v3.0.8, available here:
+// As of this writing, the latest ADIF standard is v3.1.4, available here:
 //
-//   http://www.adif.org/308/ADIF_308.htm
+//   https://www.adif.org/314/ADIF_314.htm
 //
 // Note that much of ADIF describes a logical form for the data.  There are
 // currently two physical file formats: ADI (a somewhat baroque format described
 // originally in version 1, which dates back to 1996) and ADX (a more modern
 // XML-based format).  ADI appears to be more widely used, while ADX is marked
-// optional in the standard.  For that reason, this crate currently only seeks
-// to implement ADI.
+// optional in the standard.  Both are supported here: each physical format is
+// translated to the same low-level elements, which are then interpreted by a
+// single logical parser.
 //
 // Section II.A ("Upward Compatibility") guarantees that "an ADIF file compliant
 // with ADIF version N will comply with any future ADIF version M where M>N."
 // By implementing v3, we support all v1 and v2 files.
 //
 
+use std::error;
 use std::io;
+use std::io::BufRead;
 use std::fmt;
 
 mod adi;
 mod adif;
+mod adifenums;
+mod adiftypes;
 mod adifutil;
+mod adx;
 
 //
 // TODO decide whether there's a cleaner way to structure this.
 //
 pub use crate::adif::adif::AdifDumpWhichRecords;
+pub use crate::adif::adif::AdifFile;
+pub use crate::adif::adif::AdifReader;
 pub use crate::adif::adif::AdifRecord;
+pub use crate::adif::adif::AdifUserDef;
+pub use crate::adif::adif::adif_reader;
+pub use crate::adif::adif::adif_reader_with_options;
+pub use crate::adif::adif::adif_validate_enumerations;
+pub use crate::adif::adif::adif_validate_userdefs;
+pub use crate::adif::adifenums::AdifAntPath;
+pub use crate::adif::adifenums::AdifArrlSection;
+pub use crate::adif::adifenums::AdifBand;
+pub use crate::adif::adifenums::AdifContestId;
+pub use crate::adif::adifenums::AdifContinent;
+pub use crate::adif::adifenums::AdifCredit;
+pub use crate::adif::adifenums::AdifEnumError;
+pub use crate::adif::adifenums::AdifMode;
+pub use crate::adif::adifenums::AdifPropagationMode;
+pub use crate::adif::adifenums::AdifQslRcvd;
+pub use crate::adif::adifenums::AdifQslSent;
+pub use crate::adif::adifenums::AdifQslVia;
+pub use crate::adif::adifenums::AdifRegion;
+pub use crate::adif::adifenums::AdifSubdivision;
+pub use crate::adif::adifenums::AdifSubmode;
+pub use crate::adif::adifenums::ADIF_SUBDIVISION_ENTITIES;
+pub use crate::adif::adifenums::adif_subdivision;
+pub use crate::adif::adifenums::adif_subdivisions;
+pub use crate::adif::adiftypes::AdifType;
+pub use crate::adif::adiftypes::AdifValue;
+pub use crate::adif::adiftypes::AdifValueError;
+pub use crate::adif::adiftypes::adif_value;
 pub use crate::adif::adif::adif_dump;
 
 //
@@ -44,7 +79,7 @@ pub use crate::adif::adif::adif_dump;
 #[derive(Debug)]
 pub enum AdifParseError {
     ADIF_EIO(io::Error),                  // error from underlying I/O
-    ADIF_EBADINPUT(String),               // invalid input
+    ADIF_EBADINPUT(AdifInputError),       // invalid input
     ADIF_ENOT_YET_IMPLEMENTED(String),    // feature that's not yet implemented
 }
 
@@ -70,11 +105,273 @@ impl fmt::Display for AdifParseError {
     }
 }
 
-pub fn adif_parse(label: &str, source: &mut io::Read) ->
+impl error::Error for AdifParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            AdifParseError::ADIF_EIO(ioerror) => Some(ioerror),
+            _ => None
+        }
+    }
+}
+
+//
+// AdifPosition identifies a point in the input.  Lines and columns are 1-based,
+// and columns count bytes rather than characters.
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdifPosition {
+    pub adpp_offset : usize,    // bytes from the start of the input
+    pub adpp_line : usize,      // line number
+    pub adpp_column : usize     // column number
+}
+
+impl Default for AdifPosition {
+    fn default() -> Self {
+        AdifPosition { adpp_offset: 0, adpp_line: 1, adpp_column: 1 }
+    }
+}
+
+impl fmt::Display for AdifPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.adpp_line, self.adpp_column)
+    }
+}
+
+//
+// AdifInputErrorKind classifies the ways in which input can be invalid.
+//
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdifInputErrorKind {
+    ADIE_SYNTAX,        // malformed data specifier or XML
+    ADIE_ENCODING,      // non-ASCII name or invalid UTF-8 value
+    ADIE_TOO_LONG,      // value longer than "adpo_max_fieldlen"
+    ADIE_TRUNCATED,     // input ended in the middle of the header or a record
+    ADIE_STRUCTURE,     // unexpected ADX element or missing attribute
+    ADIE_BAD_TYPE,      // unknown or missing data type indicator
+    ADIE_BAD_USERDEF,   // malformed USERDEF declaration
+    ADIE_DUPLICATE,     // field appears more than once in a record
+    ADIE_BAD_VALUE      // value not valid for its field
+}
+
+//
+// AdifInputError describes invalid input.  Each of the location fields is
+// filled in when it's known: errors found after the low-level elements have
+// been parsed (e.g., duplicate fields) still know which field and record they
+// came from, but errors from validating an AdifFile after parsing have no
+// position.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdifInputError {
+    pub adie_kind : AdifInputErrorKind,         // what's wrong with the input
+    pub adie_position : Option<AdifPosition>,   // where in the input
+    pub adie_record : Option<usize>,            // 1-based record number
+    pub adie_field : Option<String>,            // field name, as it appears
+    pub adie_message : String                   // description of the problem
+}
+
+impl AdifInputError {
+    pub fn new(kind: AdifInputErrorKind, message: String) -> Self {
+        AdifInputError {
+            adie_kind: kind,
+            adie_position: None,
+            adie_record: None,
+            adie_field: None,
+            adie_message: message
+        }
+    }
+
+    pub fn with_position(mut self, position: AdifPosition) -> Self {
+        self.adie_position = Some(position);
+        self
+    }
+
+    pub fn with_record(mut self, which: usize) -> Self {
+        self.adie_record = Some(which);
+        self
+    }
+
+    pub fn with_field(mut self, name: &str) -> Self {
+        self.adie_field = Some(name.to_string());
+        self
+    }
+}
+
+impl fmt::Display for AdifInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut location : Vec<String> = Vec::new();
+        if let Some(position) = self.adie_position {
+            location.push(position.to_string());
+        }
+        if let Some(which) = self.adie_record {
+            location.push(format!("record {}", which));
+        }
+        if let Some(ref name) = self.adie_field {
+            location.push(format!("field \"{}\"", name));
+        }
+
+        if location.is_empty() {
+            write!(f, "{}", self.adie_message)
+        } else {
+            write!(f, "{}: {}", location.join(", "), self.adie_message)
+        }
+    }
+}
+
+impl error::Error for AdifInputError {}
+
+//
+// AdifParseOptions controls how input is parsed.  The defaults are:
+//
+//     adpo_max_fieldlen   64 KiB.  This limit exists to fail gracefully on bad
+//                         input that would otherwise use lots of memory, not
+//                         because of anything intrinsic to the format.
+//
+//     adpo_duplicates     ADP_ERROR: a record with more than one value for the
+//                         same field is invalid.
+//
+//     adpo_charset        ADC_UTF8: values are decoded as UTF-8 (see
+//                         AdifCharset).
+//
+//     adpo_intl_to_base   false: "_INTL" fields are kept separate from their
+//                         base fields.  If true, the value of each "_INTL"
+//                         field replaces the value of the base field (e.g.,
+//                         "NAME_INTL" replaces "NAME").  See
+//                         AdifRecord::map_intl_fields().
+//
+//     adpo_lenient        false: the first malformed data specifier or invalid
+//                         value fails the whole parse.  In lenient mode, the
+//                         bad field (or, if the input ends in the middle of a
+//                         record, the bad record) is skipped instead, and an
+//                         AdifParseWarning describing it is recorded.  I/O
+//                         errors are always fatal.
+//
+#[derive(Debug, Clone)]
+pub struct AdifParseOptions {
+    pub adpo_max_fieldlen : usize,                  // max bytes in one value
+    pub adpo_duplicates : AdifDuplicatePolicy,      // duplicate field handling
+    pub adpo_charset : AdifCharset,                 // how to decode values
+    pub adpo_intl_to_base : bool,                   // replace base with _INTL
+    pub adpo_lenient : bool                         // skip bad input
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdifDuplicatePolicy {
+    ADP_ERROR,          // fail (or, in lenient mode, warn and keep the first)
+    ADP_KEEP_FIRST,     // silently keep the first value
+    ADP_KEEP_LAST       // silently keep the last value
+}
+
+//
+// AdifCharset describes how values are converted from bytes to text.  The ADIF
+// specification says that ADI files contain only ASCII, with international
+// text carried in the "_INTL" fields of ADX files.  In practice, ADI files
+// often contain UTF-8, and files from older Windows programs often contain
+// Windows-1252 (or ISO-8859-1, which is nearly a subset).
+//
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdifCharset {
+    ADC_ASCII,      // values must be ASCII, except for Intl data types
+    ADC_UTF8,       // values must be UTF-8
+    ADC_LATIN1,     // values that aren't UTF-8 are decoded as Windows-1252
+    ADC_RAW         // anything goes; see AdifRecord::value_bytes()
+}
+
+impl Default for AdifParseOptions {
+    fn default() -> Self {
+        AdifParseOptions {
+            adpo_max_fieldlen: 64 * 1024,
+            adpo_duplicates: AdifDuplicatePolicy::ADP_ERROR,
+            adpo_charset: AdifCharset::ADC_UTF8,
+            adpo_intl_to_base: false,
+            adpo_lenient: false
+        }
+    }
+}
+
+//
+// AdifParseWarning describes input that was skipped in lenient mode.  It's the
+// same error that would have been returned in strict mode.
+//
+pub type AdifParseWarning = AdifInputError;
+
+//
+// Parse an ADIF file in either physical format, using the default options.
+//
+pub fn adif_parse(label: &str, source: &mut dyn io::Read) ->
     Result<adif::AdifFile, AdifParseError>
 {
-    let adi = adi::adi_parse(source)?;
-    adif::adif_parse_adi(label, &adi)
+    adif_parse_with_options(label, source, &AdifParseOptions::default())
+}
+
+//
+// Parse an ADIF file in either physical format.  ADX files are recognized by
+// their leading XML declaration or "<ADX>" root element; anything else is
+// parsed as ADI.
+//
+pub fn adif_parse_with_options(label: &str, source: &mut dyn io::Read,
+    options: &AdifParseOptions) -> Result<adif::AdifFile, AdifParseError>
+{
+    let mut reader = io::BufReader::new(source);
+    let is_adx = {
+        // Look past any UTF-8 byte order mark, which the XML reader skips.
+        let buf = reader.fill_buf()?;
+        let buf = buf.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(buf);
+        let start = buf.iter()
+            .position(|&c| !(c as char).is_ascii_whitespace())
+            .unwrap_or(buf.len());
+        let start = &buf[start..];
+        start.starts_with(b"<?xml") ||
+            (start.len() >= 4 && start[..4].eq_ignore_ascii_case(b"<adx"))
+    };
+
+    if is_adx {
+        let adx = adx::adx_parse(&mut reader)?;
+        adif::adif_parse_adi(label, &adx, options)
+    } else {
+        let adi = adi::adi_parse(&mut reader, options)?;
+        adif::adif_parse_adi(label, &adi, options)
+    }
+}
+
+pub fn adif_parse_adi(label: &str, source: &mut dyn io::Read) ->
+    Result<adif::AdifFile, AdifParseError>
+{
+    let options = AdifParseOptions::default();
+    let adi = adi::adi_parse(source, &options)?;
+    adif::adif_parse_adi(label, &adi, &options)
+}
+
+//
+// Parse an ADX file.  ADX is read into memory in its entirety, so
+// "adpo_max_fieldlen" doesn't apply, and malformed XML is always fatal.
+//
+pub fn adif_parse_adx(label: &str, source: &mut dyn io::Read) ->
+    Result<adif::AdifFile, AdifParseError>
+{
+    let adx = adx::adx_parse(source)?;
+    adif::adif_parse_adi(label, &adx, &AdifParseOptions::default())
+}
+
+//
+// Write the given ADIF file to "sink" in ADI format.  The result can be read
+// back with adif_parse().
+//
+pub fn adif_write_adi(adif: &adif::AdifFile, sink: &mut dyn io::Write) ->
+    io::Result<()>
+{
+    adi::adi_write(&adif::adif_to_adi(adif, false), sink)
+}
+
+//
+// Write the given ADIF file to "sink" in ADX format.
+//
+pub fn adif_write_adx(adif: &adif::AdifFile, sink: &mut dyn io::Write) ->
+    io::Result<()>
+{
+    adx::adx_write(&adif::adif_to_adi(adif, true), sink)
 }
 
 pub trait CallsignInfo {
@@ -99,7 +396,7 @@ impl CallsignInfo for Call {
                     };
                 let grid =
                     match record.adir_field_values.get("my_gridsquare") {
-                        Some(grid) => Some(Grid::new(grid.to_string()).unwrap()),
+                        Some(grid) => Grid::new(grid.to_string()).ok(),
                         None => None,
                     };
                 let qth =
@@ -139,7 +436,7 @@ impl CallsignInfo for Call {
                     };
                 let grid =
                     match record.adir_field_values.get("gridsquare") {
-                        Some(grid) => Some(Grid::new(grid.to_string()).unwrap()),
+                        Some(grid) => Grid::new(grid.to_string()).ok(),
                         None => None,
                     };
                 let qth =
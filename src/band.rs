@@ -1,115 +1,180 @@
-#[derive(Debug, Serialize, Deserialize,PartialEq,Clone)]
-pub enum Band {
-    OneHundredSixtyMeters(i32),
-    EightyMeters(i32),
-    FortyMeters(i32),
-    ThirtyMeters(i32),
-    TwentyMeters(i32),
-    SeventeenMeters(i32),
-    FifteenMeters(i32),
-    TwelveMeters(i32),
-    TenMeters(i32),
-    SixMeters(i32),
-    TwoMeters(i32),
-    OnePointTwoFiveMeters(i32),
-    SeventyCM(i32),
-    ThirtyThreeCM(i32),
-    Unknown(i32)
+use crate::Frequency;
+
+//
+// Each band's variant, ADIF band name, lower and upper edges in Hz (both
+// included in the band, as in the ADIF Band enumeration) and display color.
+// The Band enum, AMATEUR_BANDS and the lookups below are all generated from
+// this one table, so a band's edges are only written down once.
+//
+macro_rules! bands {
+    ($($variant:ident, $name:expr, $lower:expr, $upper:expr, $color:expr;)*) => {
+        #[derive(Debug, Serialize, Deserialize,PartialEq,Clone)]
+        pub enum Band {
+            $($variant(Frequency),)*
+            Unknown(Frequency)
+        }
+
+        const BAND_COUNT: usize = [$($name),*].len();
+
+        pub const AMATEUR_BANDS: [Band; BAND_COUNT] =
+            [$(Band::$variant(Frequency::from_hz($lower))),*];
+
+        impl Band {
+            pub fn new<F: Into<Frequency>>(freq: F) -> Band {
+                let freq = freq.into();
+                match freq.hz() {
+                    $($lower..=$upper => Band::$variant(freq),)*
+                    _ => Band::Unknown(freq)
+                }
+            }
+
+            // Returns the band for the value of an ADIF "band" field (e.g.,
+            // "20m"), compared case-insensitively.  Since the field doesn't
+            // say where in the band the QSO was, the frequency is taken to be
+            // the lower band edge.
+            pub fn from_adif_str(band: &str) -> Option<Band> {
+                $(if band.eq_ignore_ascii_case($name) {
+                    return Some(Band::$variant(Frequency::from_hz($lower)));
+                })*
+                None
+            }
+
+            pub fn freq(&self) -> Frequency {
+                match self {
+                    $(Band::$variant(f) |)*
+                    Band::Unknown(f) => *f
+                }
+            }
+
+            pub fn band(&self) -> Option<&'static str> {
+                match self {
+                    $(Band::$variant(_) => Some($name),)*
+                    Band::Unknown(_) => None
+                }
+            }
+
+            // Lower and upper edges of the band, as given by ADIF.
+            pub fn edges(&self) -> Option<(Frequency, Frequency)> {
+                match self {
+                    $(Band::$variant(_) => Some((Frequency::from_hz($lower), Frequency::from_hz($upper))),)*
+                    Band::Unknown(_) => None
+                }
+            }
+
+            pub fn color(&self) -> Option<&'static str> {
+                match self {
+                    $(Band::$variant(_) => Some($color),)*
+                    Band::Unknown(_) => None
+                }
+            }
+        }
+    }
+}
+
+bands! {
+    TwentyTwoHundredMeters, "2190m",  135_700,         137_800,           "#ff4500";
+    SixHundredThirtyMeters, "630m",   472_000,         479_000,           "#1e90ff";
+    FiveHundredSixtyMeters, "560m",   501_000,         504_000,           "#00bfff";
+    OneHundredSixtyMeters,  "160m",   1_800_000,       2_000_000,         "#7cfc00";
+    EightyMeters,           "80m",    3_500_000,       4_000_000,         "#e550e5";
+    SixtyMeters,            "60m",    5_060_000,       5_450_000,         "#00008b";
+    FortyMeters,            "40m",    7_000_000,       7_300_000,         "#5959ff";
+    ThirtyMeters,           "30m",    10_100_000,      10_150_000,        "#62d962";
+    TwentyMeters,           "20m",    14_000_000,      14_350_000,        "#f2c40c";
+    SeventeenMeters,        "17m",    18_068_000,      18_168_000,        "#f2f261";
+    FifteenMeters,          "15m",    21_000_000,      21_450_000,        "#cca166";
+    TwelveMeters,           "12m",    24_890_000,      24_990_000,        "#b22222";
+    TenMeters,              "10m",    28_000_000,      29_700_000,        "#ff69b4";
+    EightMeters,            "8m",     40_000_000,      45_000_000,        "#7f00f1";
+    SixMeters,              "6m",     50_000_000,      54_000_000,        "#FF0000";
+    FiveMeters,             "5m",     54_000_001,      69_900_000,        "#e0e0e0";
+    FourMeters,             "4m",     70_000_000,      71_000_000,        "#cc0044";
+    TwoMeters,              "2m",     144_000_000,     148_000_000,       "#FF1493";
+    OnePointTwoFiveMeters,  "1.25m",  222_000_000,     225_000_000,       "#CCFF00";
+    SeventyCM,              "70cm",   420_000_000,     450_000_000,       "#999900";
+    ThirtyThreeCM,          "33cm",   902_000_000,     928_000_000,       "#5AB8C7";
+    TwentyThreeCM,          "23cm",   1_240_000_000,   1_300_000_000,     "#5AB87A";
+    ThirteenCM,             "13cm",   2_300_000_000,   2_450_000_000,     "#2E8B57";
+    NineCM,                 "9cm",    3_300_000_000,   3_500_000_000,     "#8B4513";
+    SixCM,                  "6cm",    5_650_000_000,   5_925_000_000,     "#4682B4";
+    ThreeCM,                "3cm",    10_000_000_000,  10_500_000_000,    "#708090";
+    OnePointTwoFiveCM,      "1.25cm", 24_000_000_000,  24_250_000_000,    "#9ACD32";
+    SixMM,                  "6mm",    47_000_000_000,  47_200_000_000,    "#D2691E";
+    FourMM,                 "4mm",    75_500_000_000,  81_000_000_000,    "#BC8F8F";
+    TwoPointFiveMM,         "2.5mm",  119_980_000_000, 123_000_000_000,   "#DAA520";
+    TwoMM,                  "2mm",    134_000_000_000, 149_000_000_000,   "#20B2AA";
+    OneMM,                  "1mm",    241_000_000_000, 250_000_000_000,   "#778899";
+    SubMM,                  "submm",  300_000_000_000, 7_500_000_000_000, "#696969";
 }
-pub const AMATEUR_BANDS: [Band; 14] =
-    [Band::OneHundredSixtyMeters(1800),
-     Band::EightyMeters(3500000),
-     Band::FortyMeters(7000000),
-     Band::ThirtyMeters(10100000),
-     Band::TwentyMeters(18068000),
-     Band::SeventeenMeters(18068000),
-     Band::FifteenMeters(21000000),
-     Band::TwelveMeters(24890000),
-     Band::TenMeters(28000000),
-     Band::SixMeters(50000000),
-     Band::TwoMeters(144000000),
-     Band::OnePointTwoFiveMeters(222000000),
-     Band::SeventyCM(420000000),
-     Band::ThirtyThreeCM(902000000)];
 
 impl Band {
-    pub fn new(freq:i32) -> Band {
-        match freq {
-            freq if freq >= 1800 && freq <= 2000 => Band::OneHundredSixtyMeters(freq),
-            freq if freq >= 3500000 && freq <= 4000000 => Band::EightyMeters(freq),
-            freq if freq >= 7000000 && freq <= 7300000 => Band::FortyMeters(freq),
-            freq if freq >= 10100000 && freq <= 10150000 => Band::ThirtyMeters(freq),
-            freq if freq >= 14000000 && freq <= 14350000 => Band::TwentyMeters(freq),
-            freq if freq >= 18068000 && freq <= 18168000 => Band::SeventeenMeters(freq),
-            freq if freq >= 21000000 && freq <= 21450000 => Band::FifteenMeters(freq),
-            freq if freq >= 24890000 && freq <= 24990000 => Band::TwelveMeters(freq),
-            freq if freq >= 28000000 && freq <= 29700000 => Band::TenMeters(freq),
-            freq if freq >= 50000000 && freq <= 54000000 => Band::SixMeters(freq),
-            freq if freq >= 144000000 && freq <= 148000000 => Band::TwoMeters(freq),
-            freq if freq >= 222000000 && freq <= 225000000 => Band::OnePointTwoFiveMeters(freq),
-            freq if freq >= 420000000 && freq <= 450000000 => Band::SeventyCM(freq),
-            freq if freq >= 902000000 && freq <= 928000000 => Band::ThirtyThreeCM(freq),
-            _ => Band::Unknown(freq)
-        }
+    // The frequency in Hz, saturating at i32::MAX (about 2.1 GHz).
+    #[deprecated(note = "use freq(), which doesn't overflow above 2.1 GHz")]
+    pub fn frequency(&self) -> i32 {
+        self.freq().hz().min(i32::MAX as u64) as i32
     }
+    #[deprecated(note = "use freq().mhz(), which doesn't lose precision above HF")]
     pub fn mhz(&self) -> f32 {
-        self.frequency() as f32 / 1000000.0
+        self.freq().mhz() as f32
     }
-    pub fn frequency(&self) -> i32 {
-        match self {
-            Band::OneHundredSixtyMeters(f) |
-            Band::EightyMeters(f) |
-            Band::FortyMeters(f) | 
-            Band::ThirtyMeters(f) |
-            Band::TwentyMeters(f) |
-            Band::SeventeenMeters(f) |
-            Band::FifteenMeters(f) |
-            Band::TwelveMeters(f) |
-            Band::TenMeters(f) |
-            Band::SixMeters(f) |
-            Band::TwoMeters(f) |
-            Band::OnePointTwoFiveMeters(f) |
-            Band::SeventyCM(f) |
-            Band::ThirtyThreeCM(f) |
-            Band::Unknown(f) => *f
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adif::AdifBand;
+
+    #[test]
+    fn test_new() {
+        let hz = Frequency::from_hz;
+        assert_eq!(Band::new(hz(136_000)), Band::TwentyTwoHundredMeters(hz(136_000)));
+        assert_eq!(Band::new(hz(1_840_000)), Band::OneHundredSixtyMeters(hz(1_840_000)));
+        assert_eq!(Band::new(hz(1_840)), Band::Unknown(hz(1_840)));
+        assert_eq!(Band::new(hz(5_357_000)), Band::SixtyMeters(hz(5_357_000)));
+        assert_eq!(Band::new(hz(70_200_000)), Band::FourMeters(hz(70_200_000)));
+        assert_eq!(Band::new(hz(1_296_200_000)), Band::TwentyThreeCM(hz(1_296_200_000)));
+        assert_eq!(Band::new(10_368_100_000u64), Band::ThreeCM(hz(10_368_100_000)));
+        assert_eq!(Band::new(hz(12_000_000)), Band::Unknown(hz(12_000_000)));
+        assert_eq!(Band::ThreeCM(hz(10_368_100_000)).band(), Some("3cm"));
+        assert_eq!(Band::new(hz(144_174_000)).freq().mhz(), 144.174);
+
+        for band in AMATEUR_BANDS.iter() {
+            assert_eq!(&Band::new(band.freq()), band);
         }
     }
-    pub fn band(&self) -> Option<&'static str> {
-        match self {
-            Band::OneHundredSixtyMeters(_) => Some("160m"),
-            Band::EightyMeters(_) => Some("80m"),
-            Band::FortyMeters(_) => Some("40m"),
-            Band::ThirtyMeters(_) => Some("30m"),
-            Band::TwentyMeters(_) => Some("20m"),
-            Band::SeventeenMeters(_) => Some("17m"),
-            Band::FifteenMeters(_) => Some("15m"),
-            Band::TwelveMeters(_) => Some("12m"),
-            Band::TenMeters(_) => Some("10m"),
-            Band::SixMeters(_) => Some("6m"),
-            Band::TwoMeters(_) => Some("2m"),
-            Band::OnePointTwoFiveMeters(_) => Some("1.25m"),
-            Band::SeventyCM(_) => Some("70cm"),
-            Band::ThirtyThreeCM(_) => Some("33cm"),
-            Band::Unknown(_) => None
-        }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_integer_frequencies() {
+        let freq: i32 = 14_074_000;
+        let band = Band::new(freq);
+        assert_eq!(band, Band::TwentyMeters(Frequency::from_hz(14_074_000)));
+        assert_eq!(band.frequency(), freq);
+        assert_eq!(band.mhz(), 14.074);
+        assert_eq!(Band::new(7_074_000u32).band(), Some("40m"));
+        assert_eq!(Band::new(-1), Band::Unknown(Frequency::from_hz(0)));
+        assert_eq!(Band::new(10_368_100_000u64).frequency(), i32::MAX);
+    }
+
+    #[test]
+    fn test_from_adif_str() {
+        let hz = Frequency::from_hz;
+        assert_eq!(Band::from_adif_str("23CM"), Some(Band::TwentyThreeCM(hz(1_240_000_000))));
+        assert_eq!(Band::from_adif_str("2190m"), Some(Band::TwentyTwoHundredMeters(hz(135_700))));
+        assert_eq!(Band::from_adif_str("11m"), None);
     }
-    pub fn color(&self) -> Option<&'static str> {
-        match self {
-            Band::OneHundredSixtyMeters(_) => Some("#7cfc00"),
-            Band::EightyMeters(_) => Some("#e550e5"),
-            Band::FortyMeters(_) => Some("#5959ff"),
-            Band::ThirtyMeters(_) => Some("#62d962"),
-            Band::TwentyMeters(_) => Some("#f2c40c"),
-            Band::SeventeenMeters(_) => Some("#f2f261"),
-            Band::FifteenMeters(_) => Some("#cca166"),
-            Band::TwelveMeters(_) => Some("#b22222"),
-            Band::TenMeters(_) => Some("#ff69b4"),
-            Band::SixMeters(_) => Some("#FF0000"),
-            Band::TwoMeters(_) => Some("#FF1493"),
-            Band::OnePointTwoFiveMeters(_) => Some("#CCFF00"),
-            Band::SeventyCM(_) => Some("#999900"),
-            Band::ThirtyThreeCM(_) => Some("#5AB8C7"),
-            Band::Unknown(_) => None
+
+    #[test]
+    fn test_adif_bands() {
+        // Every ADIF band has a Band with the same name and edges.
+        assert_eq!(AMATEUR_BANDS.len(), AdifBand::ALL.len());
+        for adif in AdifBand::ALL {
+            let band = Band::from_adif_str(adif.as_str()).unwrap();
+            let (lower, upper) = adif.edges();
+            assert_eq!(band.band(), Some(adif.as_str()));
+            assert_eq!(band.edges(), Some((Frequency::from_mhz(lower),
+                Frequency::from_mhz(upper))));
+            assert!(band.color().is_some());
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,349 @@
+use crate::adif::{AdifMode, AdifSubmode};
+use crate::{Frequency, Mode, ModeCategory};
+use std::fmt;
+use self::SegmentUsage::*;
+
+// IARU band plans divide each band into segments by the kind of emission they
+// are for.  The tables here follow the Region 1 (2020) and Region 2 (2016) HF,
+// 6m, and 2m plans, with adjacent segments of the same kind merged: they
+// don't distinguish, for example, contest-preferred or DX-window segments.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum IaruRegion {
+    Region1,
+    Region2,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum SegmentUsage {
+    CW,
+    NarrowDigital,
+    AllModes,
+    Beacons,
+}
+
+// A segment covers lower <= frequency < upper; the segment at the top of a
+// band also includes its upper edge.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Segment {
+    pub lower: Frequency,
+    pub upper: Frequency,
+    pub usage: SegmentUsage,
+}
+
+// A calling frequency for one of the WSJT-X modes.  Signals are transmitted
+// within the audio passband above the dial frequency.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DialFrequency {
+    pub frequency: Frequency,
+    pub mode: Mode,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BandPlanMismatch {
+    pub frequency: Frequency,
+    pub mode: Mode,
+    pub segment: Segment,
+}
+
+impl fmt::Display for BandPlanMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {} is outside the band plan's {} segment ({} - {} kHz)",
+            self.mode, self.frequency, self.segment.usage,
+            self.segment.lower.format_khz(), self.segment.upper.format_khz())
+    }
+}
+
+impl fmt::Display for SegmentUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let usage = match self {
+            SegmentUsage::CW => "CW",
+            SegmentUsage::NarrowDigital => "narrow band digital",
+            SegmentUsage::AllModes => "all modes",
+            SegmentUsage::Beacons => "beacon",
+        };
+        write!(f, "{}", usage)
+    }
+}
+
+impl SegmentUsage {
+    // The kinds of mode the segment is recommended for.  Beacon segments are
+    // to be kept clear of QSOs.
+    pub fn recommended_modes(&self) -> &'static [ModeCategory] {
+        match self {
+            SegmentUsage::CW => &[ModeCategory::CW],
+            SegmentUsage::NarrowDigital => &[ModeCategory::CW, ModeCategory::Digital],
+            SegmentUsage::AllModes => &[ModeCategory::Phone, ModeCategory::CW,
+                ModeCategory::Digital, ModeCategory::Image],
+            SegmentUsage::Beacons => &[],
+        }
+    }
+}
+
+impl Segment {
+    pub fn recommended_modes(&self) -> &'static [ModeCategory] {
+        self.usage.recommended_modes()
+    }
+
+    pub fn permits(&self, mode: &Mode) -> bool {
+        self.recommended_modes().contains(&mode.category())
+    }
+}
+
+// Width of the audio passband used by the WSJT-X modes.
+const DIAL_PASSBAND_HZ: u64 = 3_000;
+
+const fn seg(lower: u64, upper: u64, usage: SegmentUsage) -> Segment {
+    Segment {
+        lower: Frequency::from_hz(lower),
+        upper: Frequency::from_hz(upper),
+        usage,
+    }
+}
+
+const fn dial(frequency: u64, mode: Mode) -> DialFrequency {
+    DialFrequency { frequency: Frequency::from_hz(frequency), mode }
+}
+
+const REGION1_SEGMENTS: &[Segment] = &[
+    // 160m
+    seg(1_810_000, 1_838_000, CW),
+    seg(1_838_000, 1_840_000, NarrowDigital),
+    seg(1_840_000, 2_000_000, AllModes),
+    // 80m
+    seg(3_500_000, 3_570_000, CW),
+    seg(3_570_000, 3_600_000, NarrowDigital),
+    seg(3_600_000, 3_800_000, AllModes),
+    // 60m
+    seg(5_351_500, 5_354_000, NarrowDigital),
+    seg(5_354_000, 5_366_000, AllModes),
+    seg(5_366_000, 5_366_500, NarrowDigital),
+    // 40m
+    seg(7_000_000, 7_040_000, CW),
+    seg(7_040_000, 7_050_000, NarrowDigital),
+    seg(7_050_000, 7_200_000, AllModes),
+    // 30m
+    seg(10_100_000, 10_130_000, CW),
+    seg(10_130_000, 10_150_000, NarrowDigital),
+    // 20m
+    seg(14_000_000, 14_070_000, CW),
+    seg(14_070_000, 14_099_000, NarrowDigital),
+    seg(14_099_000, 14_101_000, Beacons),
+    seg(14_101_000, 14_112_000, NarrowDigital),
+    seg(14_112_000, 14_350_000, AllModes),
+    // 17m
+    seg(18_068_000, 18_095_000, CW),
+    seg(18_095_000, 18_109_000, NarrowDigital),
+    seg(18_109_000, 18_111_000, Beacons),
+    seg(18_111_000, 18_168_000, AllModes),
+    // 15m
+    seg(21_000_000, 21_070_000, CW),
+    seg(21_070_000, 21_149_000, NarrowDigital),
+    seg(21_149_000, 21_151_000, Beacons),
+    seg(21_151_000, 21_450_000, AllModes),
+    // 12m
+    seg(24_890_000, 24_915_000, CW),
+    seg(24_915_000, 24_929_000, NarrowDigital),
+    seg(24_929_000, 24_931_000, Beacons),
+    seg(24_931_000, 24_990_000, AllModes),
+    // 10m
+    seg(28_000_000, 28_070_000, CW),
+    seg(28_070_000, 28_190_000, NarrowDigital),
+    seg(28_190_000, 28_225_000, Beacons),
+    seg(28_225_000, 29_700_000, AllModes),
+    // 6m
+    seg(50_000_000, 50_100_000, CW),
+    seg(50_100_000, 50_400_000, AllModes),
+    seg(50_400_000, 50_500_000, Beacons),
+    seg(50_500_000, 52_000_000, AllModes),
+    // 2m
+    seg(144_000_000, 144_150_000, CW),
+    seg(144_150_000, 144_400_000, AllModes),
+    seg(144_400_000, 144_490_000, Beacons),
+    seg(144_490_000, 146_000_000, AllModes),
+];
+
+const REGION2_SEGMENTS: &[Segment] = &[
+    // 160m
+    seg(1_800_000, 1_810_000, NarrowDigital),
+    seg(1_810_000, 1_830_000, CW),
+    seg(1_830_000, 1_840_000, NarrowDigital),
+    seg(1_840_000, 2_000_000, AllModes),
+    // 80m
+    seg(3_500_000, 3_570_000, CW),
+    seg(3_570_000, 3_600_000, NarrowDigital),
+    seg(3_600_000, 4_000_000, AllModes),
+    // 60m
+    seg(5_351_500, 5_354_000, NarrowDigital),
+    seg(5_354_000, 5_366_000, AllModes),
+    seg(5_366_000, 5_366_500, NarrowDigital),
+    // 40m
+    seg(7_000_000, 7_040_000, CW),
+    seg(7_040_000, 7_050_000, NarrowDigital),
+    seg(7_050_000, 7_300_000, AllModes),
+    // 30m
+    seg(10_100_000, 10_130_000, CW),
+    seg(10_130_000, 10_150_000, NarrowDigital),
+    // 20m
+    seg(14_000_000, 14_070_000, CW),
+    seg(14_070_000, 14_099_500, NarrowDigital),
+    seg(14_099_500, 14_100_500, Beacons),
+    seg(14_100_500, 14_112_000, NarrowDigital),
+    seg(14_112_000, 14_350_000, AllModes),
+    // 17m
+    seg(18_068_000, 18_095_000, CW),
+    seg(18_095_000, 18_109_500, NarrowDigital),
+    seg(18_109_500, 18_110_500, Beacons),
+    seg(18_110_500, 18_168_000, AllModes),
+    // 15m
+    seg(21_000_000, 21_070_000, CW),
+    seg(21_070_000, 21_149_500, NarrowDigital),
+    seg(21_149_500, 21_150_500, Beacons),
+    seg(21_150_500, 21_450_000, AllModes),
+    // 12m
+    seg(24_890_000, 24_915_000, CW),
+    seg(24_915_000, 24_929_500, NarrowDigital),
+    seg(24_929_500, 24_930_500, Beacons),
+    seg(24_930_500, 24_990_000, AllModes),
+    // 10m
+    seg(28_000_000, 28_070_000, CW),
+    seg(28_070_000, 28_190_000, NarrowDigital),
+    seg(28_190_000, 28_225_000, Beacons),
+    seg(28_225_000, 29_700_000, AllModes),
+    // 6m
+    seg(50_000_000, 50_060_000, CW),
+    seg(50_060_000, 50_080_000, Beacons),
+    seg(50_080_000, 50_100_000, CW),
+    seg(50_100_000, 54_000_000, AllModes),
+    // 2m
+    seg(144_000_000, 144_100_000, CW),
+    seg(144_100_000, 144_275_000, AllModes),
+    seg(144_275_000, 144_300_000, Beacons),
+    seg(144_300_000, 148_000_000, AllModes),
+];
+
+const FT8: Mode = Mode::new(AdifMode::FT8);
+const FT4: Mode = Mode::Adif { mode: AdifMode::MFSK, submode: Some(AdifSubmode::FT4) };
+
+// The dial frequencies are the same in both regions.
+const DIAL_FREQUENCIES: &[DialFrequency] = &[
+    dial(1_840_000, FT8),
+    dial(3_573_000, FT8),
+    dial(3_575_000, FT4),
+    dial(5_357_000, FT8),
+    dial(7_047_500, FT4),
+    dial(7_074_000, FT8),
+    dial(10_136_000, FT8),
+    dial(10_140_000, FT4),
+    dial(14_074_000, FT8),
+    dial(14_080_000, FT4),
+    dial(18_100_000, FT8),
+    dial(18_104_000, FT4),
+    dial(21_074_000, FT8),
+    dial(21_140_000, FT4),
+    dial(24_915_000, FT8),
+    dial(24_919_000, FT4),
+    dial(28_074_000, FT8),
+    dial(28_180_000, FT4),
+    dial(50_313_000, FT8),
+    dial(50_318_000, FT4),
+    dial(144_170_000, FT4),
+    dial(144_174_000, FT8),
+];
+
+pub fn segments(region: IaruRegion) -> &'static [Segment] {
+    match region {
+        IaruRegion::Region1 => REGION1_SEGMENTS,
+        IaruRegion::Region2 => REGION2_SEGMENTS,
+    }
+}
+
+// Returns the band plan segment containing the frequency, or None if the
+// region's plan doesn't cover it.
+pub fn segment(region: IaruRegion, freq: Frequency) -> Option<&'static Segment> {
+    let segments = segments(region);
+    segments.iter()
+        .find(|s| s.lower <= freq && freq < s.upper)
+        .or_else(|| segments.iter().find(|s| s.upper == freq))
+}
+
+// Returns the FT8 or FT4 dial frequency whose passband contains the frequency.
+pub fn dial_frequency(freq: Frequency) -> Option<&'static DialFrequency> {
+    DIAL_FREQUENCIES.iter().find(|d| d.frequency <= freq &&
+        freq.hz() <= d.frequency.hz() + DIAL_PASSBAND_HZ)
+}
+
+pub fn dial_frequencies() -> &'static [DialFrequency] {
+    DIAL_FREQUENCIES
+}
+
+// Reports a mode that the band plan doesn't recommend for the segment
+// containing the frequency.  Frequencies outside the plan aren't checked.
+pub fn check_mode(region: IaruRegion, freq: Frequency, mode: &Mode) -> Option<BandPlanMismatch> {
+    match segment(region, freq) {
+        Some(segment) if !segment.permits(mode) => Some(BandPlanMismatch {
+            frequency: freq,
+            mode: mode.clone(),
+            segment: *segment,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn khz(khz: u64) -> Frequency {
+        Frequency::from_hz(khz * 1_000)
+    }
+
+    #[test]
+    fn test_segment() {
+        let region1 = IaruRegion::Region1;
+        assert_eq!(segment(region1, khz(14_025)).unwrap().usage, SegmentUsage::CW);
+        assert_eq!(segment(region1, khz(14_074)).unwrap().usage, SegmentUsage::NarrowDigital);
+        assert_eq!(segment(region1, khz(14_100)).unwrap().usage, SegmentUsage::Beacons);
+        assert_eq!(segment(region1, khz(14_070)).unwrap().usage, SegmentUsage::NarrowDigital);
+        assert_eq!(segment(region1, khz(14_350)).unwrap().usage, SegmentUsage::AllModes);
+        assert_eq!(segment(region1, khz(7_250)), None);
+        assert_eq!(segment(IaruRegion::Region2, khz(7_250)).unwrap().usage,
+            SegmentUsage::AllModes);
+        assert_eq!(segment(region1, khz(1_805)), None);
+        assert_eq!(segment(IaruRegion::Region2, khz(1_805)).unwrap().usage,
+            SegmentUsage::NarrowDigital);
+        assert!(SegmentUsage::Beacons.recommended_modes().is_empty());
+
+        for region in &[IaruRegion::Region1, IaruRegion::Region2] {
+            for pair in segments(*region).windows(2) {
+                assert!(pair[0].lower < pair[0].upper);
+                assert!(pair[0].upper <= pair[1].lower);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dial_frequency() {
+        let ft8 = dial_frequency(Frequency::from_hz(14_075_500)).unwrap();
+        assert_eq!(ft8.mode.to_string(), "FT8");
+        assert_eq!(ft8.frequency, khz(14_074));
+        assert_eq!(dial_frequency(Frequency::from_hz(7_049_000)).unwrap().mode.to_string(), "FT4");
+        assert_eq!(dial_frequency(khz(14_078)), None);
+    }
+
+    #[test]
+    fn test_check_mode() {
+        let mode = |name: &str| name.parse::<Mode>().unwrap();
+        let region = IaruRegion::Region1;
+        assert_eq!(check_mode(region, khz(14_074), &mode("FT8")), None);
+        assert_eq!(check_mode(region, khz(14_030), &mode("CW")), None);
+        assert_eq!(check_mode(region, khz(14_200), &mode("FT8")), None);
+        assert_eq!(check_mode(region, khz(432_100), &mode("USB")), None);
+
+        let mismatch = check_mode(region, khz(14_030), &mode("USB")).unwrap();
+        assert_eq!(mismatch.segment.usage, SegmentUsage::CW);
+        assert_eq!(mismatch.to_string(),
+            "USB at 14.03 MHz is outside the band plan's CW segment (14000 - 14070 kHz)");
+        assert!(check_mode(region, khz(14_074), &mode("LSB")).is_some());
+        assert!(check_mode(region, khz(14_100), &mode("CW")).is_some());
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize};
 use std::fmt;
+use std::str::FromStr;
 use chrono::prelude::*;
 use crate::Grid;
 use crate::lotw::LoTWStatus;
@@ -57,14 +58,24 @@ impl Call {
         }
     }
 
+    // The prefix that determines where the station is operating from (e.g.,
+    // "VE3" for VE3/W1AW, "3DA0" for 3DA0XYZ).
     pub fn prefix(&self) -> Option<String> {
-        let index = self.call.to_string().chars().position(|c| c.is_digit(10) );
-        match index {
-            Some(index) => {
-                Some(self.call.to_string()[..index+1].to_string())
-            },
-            None => None,
-        }
+        self.parts().ok().map(|parts| parts.prefix).filter(|prefix| !prefix.is_empty())
+    }
+
+    // The prefix as counted for the CQ WPX contest (e.g., "F0" for F/W1AW).
+    pub fn wpx_prefix(&self) -> Option<String> {
+        self.parts().ok().map(|parts| parts.wpx)
+    }
+
+    // The call without any prefix override or suffixes ("W1AW" for VE3/W1AW/P).
+    pub fn base_call(&self) -> Option<String> {
+        self.parts().ok().map(|parts| parts.base)
+    }
+
+    pub fn parts(&self) -> Result<CallParts, CallError> {
+        self.call.parse()
     }
 
     pub fn call(&self) -> String {
@@ -139,6 +150,168 @@ impl fmt::Display for Call {
     }
 }
 
+#[derive(Debug,Clone,PartialEq)]
+pub enum CallError {
+    Empty,
+    InvalidCharacter(char),
+    // A call made up only of suffixes, such as "P" or "QRP".
+    MissingBase,
+    // More than a prefix override and a base call (e.g., "VE3/KH6/W1AW").
+    TooManyParts(String),
+}
+
+impl fmt::Display for CallError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CallError::Empty => write!(f, "empty callsign"),
+            CallError::InvalidCharacter(c) => write!(f, "'{}' is not valid in a callsign", c),
+            CallError::MissingBase => write!(f, "callsign has no base call"),
+            CallError::TooManyParts(call) => write!(f, "\"{}\" has more than one prefix", call),
+        }
+    }
+}
+
+impl std::error::Error for CallError {}
+
+// The portable and operating designators that can follow a call.
+#[derive(Debug,Clone,PartialEq)]
+pub enum CallSuffix {
+    Portable,
+    Mobile,
+    MaritimeMobile,
+    AeronauticalMobile,
+    Qrp,
+    // Operating from another call area of the same country (e.g., W1AW/4).
+    CallArea(char),
+    Other(String),
+}
+
+impl CallSuffix {
+    fn new(suffix: &str) -> CallSuffix {
+        match suffix {
+            "P" => CallSuffix::Portable,
+            "M" => CallSuffix::Mobile,
+            "MM" => CallSuffix::MaritimeMobile,
+            "AM" => CallSuffix::AeronauticalMobile,
+            "QRP" => CallSuffix::Qrp,
+            s if s.len() == 1 && s.as_bytes()[0].is_ascii_digit() => CallSuffix::CallArea(s.as_bytes()[0] as char),
+            s => CallSuffix::Other(s.to_string())
+        }
+    }
+}
+
+impl fmt::Display for CallSuffix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CallSuffix::Portable => write!(f, "P"),
+            CallSuffix::Mobile => write!(f, "M"),
+            CallSuffix::MaritimeMobile => write!(f, "MM"),
+            CallSuffix::AeronauticalMobile => write!(f, "AM"),
+            CallSuffix::Qrp => write!(f, "QRP"),
+            CallSuffix::CallArea(digit) => write!(f, "{}", digit),
+            CallSuffix::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+// A callsign split into its parts.  For "KH6/K1ABC/QRP" the base call is
+// K1ABC, the prefix override KH6, and the suffixes [Qrp]; the effective prefix
+// (KH6) is the one that decides the DXCC entity.
+#[derive(Debug,Clone,PartialEq)]
+pub struct CallParts {
+    pub base: String,
+    pub prefix_override: Option<String>,
+    pub suffixes: Vec<CallSuffix>,
+    pub prefix: String,
+    pub wpx: String,
+}
+
+impl CallParts {
+    // Maritime and aeronautical mobile stations don't count for any entity.
+    pub fn is_maritime_or_aeronautical(&self) -> bool {
+        self.suffixes.iter().any(|suffix|
+            *suffix == CallSuffix::MaritimeMobile || *suffix == CallSuffix::AeronauticalMobile)
+    }
+}
+
+impl FromStr for CallParts {
+    type Err = CallError;
+
+    // Parts are separated by '/'.  Any part that isn't a known suffix is a
+    // base call or prefix override; of two such parts the shorter is taken as
+    // the prefix, and the first when they are the same length (VP2E/W1AW).  A
+    // part without a digit that follows the base call is an unknown suffix
+    // (e.g., /A or /LH).  Suffixes only follow a call, so the known ones are
+    // prefixes when they come first (M/W1AW is in England, MM/W1AW is in
+    // Scotland).
+    fn from_str(call: &str) -> Result<CallParts, CallError> {
+        let call = call.trim().to_uppercase();
+        if call.is_empty() {
+            return Err(CallError::Empty);
+        }
+        if let Some(c) = call.chars().find(|c| !c.is_ascii_alphanumeric() && *c != '/') {
+            return Err(CallError::InvalidCharacter(c));
+        }
+
+        let mut calls: Vec<&str> = Vec::new();
+        let mut suffixes = Vec::new();
+        for part in call.split('/').filter(|part| !part.is_empty()) {
+            let suffix = CallSuffix::new(part);
+            let is_suffix = match suffix {
+                CallSuffix::Other(_) => !calls.is_empty() && !part.chars().any(|c| c.is_ascii_digit()),
+                _ => !calls.is_empty()
+            };
+            if is_suffix {
+                suffixes.push(suffix);
+            } else {
+                calls.push(part);
+            }
+        }
+
+        let (base, prefix_override) = match calls.as_slice() {
+            [] => return Err(CallError::MissingBase),
+            [base] => (base.to_string(), None),
+            [first, second] if second.len() < first.len() => (first.to_string(), Some(second.to_string())),
+            [first, second] => (second.to_string(), Some(first.to_string())),
+            _ => return Err(CallError::TooManyParts(call)),
+        };
+
+        let area = suffixes.iter().rev().find_map(|suffix| match suffix {
+            CallSuffix::CallArea(digit) => Some(*digit),
+            _ => None
+        });
+        let (prefix, wpx) = match (&prefix_override, area) {
+            (Some(prefix), _) if prefix.chars().any(|c| c.is_ascii_digit()) =>
+                (prefix.to_string(), prefix.to_string()),
+            (Some(prefix), _) => (prefix.to_string(), format!("{}0", prefix)),
+            (None, Some(digit)) => {
+                let prefix = format!("{}{}", base_prefix(&base).trim_end_matches(|c: char| c.is_ascii_digit()), digit);
+                (prefix.to_string(), prefix)
+            },
+            (None, None) => {
+                let prefix = base_prefix(&base);
+                if prefix.is_empty() {
+                    // Calls without a digit count as their first two letters
+                    // followed by a zero (RAEM is RA0).
+                    (base.to_string(), format!("{}0", base.chars().take(2).collect::<String>()))
+                } else {
+                    (prefix.to_string(), prefix.to_string())
+                }
+            }
+        };
+
+        Ok(CallParts { base, prefix_override, suffixes, prefix, wpx })
+    }
+}
+
+// The prefix of a base call: everything up to and including the last digit
+// that is followed only by letters (KK4 for KK4WJS, 3DA0 for 3DA0XYZ, GB100
+// for GB100RSGB), or nothing if the call has no digits.
+fn base_prefix(base: &str) -> &str {
+    let end = base.trim_end_matches(|c: char| c.is_ascii_alphabetic()).len();
+    if base[..end].chars().any(|c| c.is_ascii_digit()) { &base[..end] } else { "" }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,4 +321,47 @@ mod tests {
         let call = Call::new("KK4WJS");
         assert_eq!(call.prefix(), Some("KK4".to_string()));
     }
+
+    #[test]
+    fn test_parts() {
+        let parts = |call: &str| call.parse::<CallParts>().unwrap();
+        let prefixes = |call: &str| { let p = parts(call); (p.base, p.prefix, p.wpx) };
+        let owned = |a: &str, b: &str, c: &str| (a.to_string(), b.to_string(), c.to_string());
+
+        assert_eq!(prefixes("VE3/W1AW"), owned("W1AW", "VE3", "VE3"));
+        assert_eq!(prefixes("W1AW/VE3"), owned("W1AW", "VE3", "VE3"));
+        assert_eq!(prefixes("W1AW/P"), owned("W1AW", "W1", "W1"));
+        assert_eq!(prefixes("W1AW/4"), owned("W1AW", "W4", "W4"));
+        assert_eq!(prefixes("3DA0XYZ"), owned("3DA0XYZ", "3DA0", "3DA0"));
+        assert_eq!(prefixes("GB100RSGB"), owned("GB100RSGB", "GB100", "GB100"));
+        assert_eq!(prefixes("F/W1AW"), owned("W1AW", "F", "F0"));
+        assert_eq!(prefixes("VP2E/W1AW"), owned("W1AW", "VP2E", "VP2E"));
+        assert_eq!(prefixes("raem"), owned("RAEM", "RAEM", "RA0"));
+        assert_eq!(prefixes("M/W1AW"), owned("W1AW", "M", "M0"));
+        assert_eq!(prefixes("MM/W1AW"), owned("W1AW", "MM", "MM0"));
+        assert_eq!(prefixes("AM/W1AW/P"), owned("W1AW", "AM", "AM0"));
+
+        let kh6 = parts("KH6/K1ABC/QRP");
+        assert_eq!(kh6.prefix_override, Some("KH6".to_string()));
+        assert_eq!(kh6.suffixes, vec![CallSuffix::Qrp]);
+        assert_eq!(kh6.prefix, "KH6");
+
+        let mm = parts("W1AW/MM");
+        assert_eq!(mm.suffixes, vec![CallSuffix::MaritimeMobile]);
+        assert!(mm.is_maritime_or_aeronautical());
+        assert!(!parts("W1AW/M").is_maritime_or_aeronautical());
+        assert!(!parts("MM/W1AW").is_maritime_or_aeronautical());
+        assert_eq!(parts("W1AW/A").suffixes, vec![CallSuffix::Other("A".to_string())]);
+        assert_eq!(CallSuffix::CallArea('4').to_string(), "4");
+
+        assert_eq!("".parse::<CallParts>(), Err(CallError::Empty));
+        assert_eq!("W1-AW".parse::<CallParts>(), Err(CallError::InvalidCharacter('-')));
+        assert_eq!("/".parse::<CallParts>(), Err(CallError::MissingBase));
+        assert!("VE3/KH6/W1AW".parse::<CallParts>().is_err());
+
+        let call = Call::new("ve3/w1aw/p");
+        assert_eq!(call.prefix(), Some("VE3".to_string()));
+        assert_eq!(call.base_call(), Some("W1AW".to_string()));
+        assert_eq!(Call::new("F/W1AW").wpx_prefix(), Some("F0".to_string()));
+    }
 }
\ No newline at end of file
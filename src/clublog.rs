@@ -0,0 +1,314 @@
+//
+// src/clublog.rs: Club Log country data (cty.xml)
+//
+// Club Log's cty.xml records when each prefix and exception applied, so it
+// can resolve QSOs from before a prefix was reallocated or an entity was
+// deleted.  It also lists operations that don't count for DXCC (invalid
+// operations) and calls that were in an unusual CQ zone (zone exceptions).
+// Lookups therefore take the QSO's date as well as the call.
+//
+// Club Log doesn't give ITU zones, so they come from the built-in table.
+//
+
+use crate::{Call,Country};
+use crate::adif::AdifContinent;
+use crate::cty::{self, CtyEntity, CtyError};
+use crate::xml::{self, XmlElement};
+use chrono::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+// When a record applies.  Either end may be open.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct DateSpan {
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+}
+
+impl DateSpan {
+    fn contains(&self, date: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| start <= date) && self.end.is_none_or(|end| date <= end)
+    }
+}
+
+// A prefix or exception, with the values it overrides for its entity.
+#[derive(Debug, Clone, PartialEq)]
+struct ClubLogRecord {
+    dxcc: u32,
+    cq_zone: Option<u8>,
+    continent: Option<AdifContinent>,
+    coord: Option<(f64, f64)>,
+    span: DateSpan,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClubLogCty {
+    date: Option<DateTime<Utc>>,
+    entities: HashMap<u32, CtyEntity>,
+    exceptions: HashMap<String, Vec<ClubLogRecord>>,
+    prefixes: HashMap<String, Vec<ClubLogRecord>>,
+    invalid: HashMap<String, Vec<DateSpan>>,
+    zones: HashMap<String, Vec<(u8, DateSpan)>>,
+}
+
+impl ClubLogCty {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<ClubLogCty, CtyError> {
+        ClubLogCty::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<ClubLogCty, CtyError> {
+        let root = xml::xml_parse(text).map_err(|e| CtyError::Parse {
+            line: line_at(text, e.offset),
+            message: e.message,
+        })?;
+        let mut cty = ClubLogCty {
+            date: root.attribute("date").and_then(parse_date),
+            ..ClubLogCty::default()
+        };
+
+        for e in list(&root, "entities", "entity") {
+            let dxcc = parse_required(text, e, "adif")?;
+            let country = Country::from_dxcc(dxcc);
+            let continent = match e.child_text("cont") {
+                Some(cont) => parse_value(text, e, "cont", &cont)?,
+                None => country.map(|c| c.continent()).ok_or_else(|| error(text, e, "missing <cont>"))?,
+            };
+            let cq_zone = match e.child_text("cqz") {
+                Some(cqz) => parse_value(text, e, "cqz", &cqz)?,
+                None => country.map(|c| c.cq_zone()).unwrap_or(0),
+            };
+            cty.entities.insert(dxcc, CtyEntity {
+                name: e.child_text("name").unwrap_or_default(),
+                primary_prefix: e.child_text("prefix").unwrap_or_default(),
+                dxcc: Some(dxcc),
+                continent,
+                cq_zone,
+                itu_zone: country.map(|c| c.itu_zone()).unwrap_or(0),
+                coord: parse_coord(text, e)?,
+                utc_offset: None,
+                wae_only: false,
+            });
+        }
+
+        for (section, name) in &[("exceptions", "exception"), ("prefixes", "prefix")] {
+            for e in list(&root, section, name) {
+                let record = ClubLogRecord {
+                    dxcc: parse_required(text, e, "adif")?,
+                    cq_zone: parse_optional(text, e, "cqz")?,
+                    continent: parse_optional(text, e, "cont")?,
+                    coord: parse_coord(text, e)?,
+                    span: parse_span(text, e)?,
+                };
+                let map = if *section == "exceptions" { &mut cty.exceptions } else { &mut cty.prefixes };
+                map.entry(call_text(text, e)?).or_default().push(record);
+            }
+        }
+
+        for e in list(&root, "invalid_operations", "invalid") {
+            let span = parse_span(text, e)?;
+            cty.invalid.entry(call_text(text, e)?).or_default().push(span);
+        }
+
+        for e in list(&root, "zone_exceptions", "zone_exception") {
+            let zone = parse_required(text, e, "zone")?;
+            let span = parse_span(text, e)?;
+            cty.zones.entry(call_text(text, e)?).or_default().push((zone, span));
+        }
+
+        Ok(cty)
+    }
+
+    // When the file was generated, from the root element's "date" attribute.
+    pub fn date(&self) -> Option<DateTime<Utc>> {
+        self.date
+    }
+
+    pub fn entity(&self, dxcc: u32) -> Option<&CtyEntity> {
+        self.entities.get(&dxcc)
+    }
+
+    pub fn is_invalid_operation(&self, call: &Call, date: DateTime<Utc>) -> bool {
+        self.invalid.get(&call.call())
+            .is_some_and(|spans| spans.iter().any(|span| span.contains(date)))
+    }
+
+    // The entity for a QSO with the call at the given date.  Exceptions for
+    // the exact call are checked first and then the longest prefix that was
+    // in use at the time.  Invalid operations, maritime and aeronautical
+    // mobile stations and calls with no matching prefix have no entity.
+    pub fn lookup(&self, call: &Call, date: DateTime<Utc>) -> Option<CtyEntity> {
+        if self.is_invalid_operation(call, date) {
+            return None;
+        }
+        let (exact, key) = cty::lookup_keys(call)?;
+
+        let valid = |records: &Vec<ClubLogRecord>| records.iter().find(|r| r.span.contains(date)).cloned();
+        let record = exact.iter()
+            .filter_map(|call| self.exceptions.get(call))
+            .find_map(valid)
+            .or_else(|| (1..=key.len()).rev()
+                .filter_map(|len| self.prefixes.get(&key[..len]))
+                .find_map(valid))?;
+
+        let mut entity = self.entities.get(&record.dxcc).cloned()?;
+        entity.cq_zone = record.cq_zone.unwrap_or(entity.cq_zone);
+        entity.continent = record.continent.unwrap_or(entity.continent);
+        entity.coord = record.coord.or(entity.coord);
+
+        let zone = self.zones.get(&call.call())
+            .and_then(|zones| zones.iter().find(|(_, span)| span.contains(date)));
+        if let Some((zone, _)) = zone {
+            entity.cq_zone = *zone;
+        }
+        Some(entity)
+    }
+}
+
+// The elements named "name" in the root's "section" element.
+fn list<'a>(root: &'a XmlElement, section: &str, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+    root.element(section)
+        .into_iter()
+        .flat_map(move |s| s.elements().filter(move |e| e.name.eq_ignore_ascii_case(name)))
+}
+
+fn line_at(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+fn error(text: &str, e: &XmlElement, message: &str) -> CtyError {
+    CtyError::Parse { line: line_at(text, e.offset), message: message.to_string() }
+}
+
+fn parse_value<T: std::str::FromStr>(text: &str, e: &XmlElement, name: &str, value: &str) -> Result<T, CtyError> {
+    value.parse::<T>().map_err(|_| error(text, e, &format!("invalid <{}> \"{}\"", name, value)))
+}
+
+fn parse_optional<T: std::str::FromStr>(text: &str, e: &XmlElement, name: &str) -> Result<Option<T>, CtyError> {
+    match e.child_text(name) {
+        Some(value) if !value.is_empty() => parse_value(text, e, name, &value).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn parse_required<T: std::str::FromStr>(text: &str, e: &XmlElement, name: &str) -> Result<T, CtyError> {
+    parse_optional(text, e, name)?.ok_or_else(|| error(text, e, &format!("missing <{}>", name)))
+}
+
+fn call_text(text: &str, e: &XmlElement) -> Result<String, CtyError> {
+    parse_required::<String>(text, e, "call").map(|call| call.to_uppercase())
+}
+
+fn parse_coord(text: &str, e: &XmlElement) -> Result<Option<(f64, f64)>, CtyError> {
+    match (parse_optional(text, e, "lat")?, parse_optional(text, e, "long")?) {
+        (Some(lat), Some(lon)) => Ok(Some((lat, lon))),
+        _ => Ok(None),
+    }
+}
+
+fn parse_date(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s.trim()).ok().map(|date| date.with_timezone(&Utc))
+}
+
+fn parse_span(text: &str, e: &XmlElement) -> Result<DateSpan, CtyError> {
+    let date = |name: &str| match e.child_text(name) {
+        Some(value) if !value.is_empty() => parse_date(&value)
+            .map(Some)
+            .ok_or_else(|| error(text, e, &format!("invalid <{}> \"{}\"", name, value))),
+        _ => Ok(None),
+    };
+    Ok(DateSpan { start: date("start")?, end: date("end")? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CTY_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<clublog date="2024-01-01T00:00:00+00:00" xmlns="https://clublog.org/cty/v1.2">
+<entities>
+<entity><adif>28</adif><name>CANAL ZONE</name><prefix>KZ5</prefix><deleted>TRUE</deleted><cqz>7</cqz><cont>NA</cont><long>-79.50</long><lat>9.00</lat><end>1979-09-30T23:59:59+00:00</end></entity>
+<entity><adif>110</adif><name>HAWAII</name><prefix>KH6</prefix><deleted>FALSE</deleted><cqz>31</cqz><cont>OC</cont><long>-157.80</long><lat>21.30</lat></entity>
+<entity><adif>291</adif><name>UNITED STATES OF AMERICA</name><prefix>K</prefix><deleted>FALSE</deleted><cqz>5</cqz><cont>NA</cont><long>-91.67</long><lat>37.53</lat></entity>
+</entities>
+<exceptions>
+<exception record="1"><call>KZ5XX</call><entity>UNITED STATES OF AMERICA</entity><adif>291</adif><cqz>4</cqz><cont>NA</cont><long>-91.67</long><lat>37.53</lat><start>1975-01-01T00:00:00+00:00</start><end>1975-12-31T23:59:59+00:00</end></exception>
+</exceptions>
+<prefixes>
+<prefix record="1"><call>K</call><entity>UNITED STATES OF AMERICA</entity><adif>291</adif><cqz>5</cqz><cont>NA</cont><long>-91.67</long><lat>37.53</lat></prefix>
+<prefix record="2"><call>KH6</call><entity>HAWAII</entity><adif>110</adif><cqz>31</cqz><cont>OC</cont><long>-157.80</long><lat>21.30</lat></prefix>
+<prefix record="3"><call>KZ5</call><entity>CANAL ZONE</entity><adif>28</adif><cqz>7</cqz><cont>NA</cont><long>-79.50</long><lat>9.00</lat><end>1979-09-30T23:59:59+00:00</end></prefix>
+<prefix record="4"><call>K6</call><entity>UNITED STATES OF AMERICA</entity><adif>291</adif><cqz>3</cqz><cont>NA</cont><long>-120.00</long><lat>36.00</lat></prefix>
+</prefixes>
+<invalid_operations>
+<invalid record="1"><call>K1BAD</call><start>2000-01-01T00:00:00+00:00</start><end>2000-12-31T23:59:59+00:00</end></invalid>
+</invalid_operations>
+<zone_exceptions>
+<zone_exception record="1"><call>KH6/K1ABC</call><zone>32</zone><start>2010-06-01T00:00:00+00:00</start><end>2010-06-30T23:59:59+00:00</end></zone_exception>
+</zone_exceptions>
+</clublog>
+"#;
+
+    fn date(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let cty = ClubLogCty::parse(CTY_XML).unwrap();
+        assert_eq!(cty.date(), Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()));
+        let hawaii = cty.entity(110).unwrap();
+        assert_eq!(hawaii.name, "HAWAII");
+        assert_eq!(hawaii.continent, AdifContinent::OC);
+        assert_eq!((hawaii.cq_zone, hawaii.itu_zone), (31, 61));
+        assert_eq!(hawaii.coord, Some((21.3, -157.8)));
+    }
+
+    #[test]
+    fn test_lookup_by_date() {
+        let cty = ClubLogCty::parse(CTY_XML).unwrap();
+        let lookup = |call: &str, date| cty.lookup(&Call::new(call), date).and_then(|e| e.dxcc);
+
+        // KZ5 was the Canal Zone until the end of September 1979.
+        assert_eq!(lookup("KZ5AB", date(1978, 5, 1)), Some(28));
+        assert_eq!(lookup("KZ5AB", date(1985, 5, 1)), Some(291));
+
+        // An exception applies only while it was in effect.
+        let exception = cty.lookup(&Call::new("KZ5XX"), date(1975, 6, 1)).unwrap();
+        assert_eq!((exception.dxcc, exception.cq_zone), (Some(291), 4));
+        assert_eq!(lookup("KZ5XX", date(1977, 6, 1)), Some(28));
+
+        assert_eq!(lookup("K6XYZ", date(2020, 1, 1)), Some(291));
+        assert_eq!(cty.lookup(&Call::new("K6XYZ"), date(2020, 1, 1)).unwrap().cq_zone, 3);
+        assert_eq!(lookup("KH6/K1ABC", date(2020, 1, 1)), Some(110));
+        assert_eq!(lookup("K1ABC/MM", date(2020, 1, 1)), None);
+        assert_eq!(lookup("JA1ABC", date(2020, 1, 1)), None);
+    }
+
+    #[test]
+    fn test_invalid_and_zone_exceptions() {
+        let cty = ClubLogCty::parse(CTY_XML).unwrap();
+        assert!(cty.is_invalid_operation(&Call::new("K1BAD"), date(2000, 6, 1)));
+        assert!(cty.lookup(&Call::new("K1BAD"), date(2000, 6, 1)).is_none());
+        assert_eq!(cty.lookup(&Call::new("K1BAD"), date(2001, 6, 1)).unwrap().dxcc, Some(291));
+
+        let zone = |date| cty.lookup(&Call::new("KH6/K1ABC"), date).unwrap().cq_zone;
+        assert_eq!(zone(date(2010, 6, 15)), 32);
+        assert_eq!(zone(date(2010, 7, 15)), 31);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        match ClubLogCty::parse("<clublog>\n<entities>\n<entity><adif>x</adif></entity>\n</entities>\n</clublog>") {
+            Err(CtyError::Parse { line, message }) => {
+                assert_eq!(line, 3);
+                assert_eq!(message, "invalid <adif> \"x\"");
+            },
+            other => panic!("{:?}", other),
+        }
+        assert!(ClubLogCty::parse("<clublog><prefixes><prefix><call>K</call></prefix></prefixes></clublog>").is_err());
+        assert!(ClubLogCty::parse("<clublog><invalid_operations><invalid><call>K1A</call>\
+            <start>yesterday</start></invalid></invalid_operations></clublog>").is_err());
+        assert!(ClubLogCty::parse("<clublog>").is_err());
+        assert!(ClubLogCty::load("/nonexistent/cty.xml").is_err());
+    }
+}
@@ -1,403 +1,1157 @@
 use crate::Call;
+use crate::call::{CallParts, CallSuffix};
+use crate::adif::AdifContinent;
 
-#[derive(Debug, Serialize, Deserialize,Clone,PartialEq)]
-pub enum Country {
-    Alaska,
-    Andorra,
-    Angola,
-    Argentina,
-    Armenia,
-    Australia,
-    Austria,
-    Azores,
-    Bahamas,
-    Bahrain,
-    Belarus,
-    Bhutan,
-    Bolivia,
-    BosniaAndHerzegovina,
-    Botswana,
-    Brazil,
-    Canada,
-    CapeVerde,
-    Chile,
-    China,
-    Comoros,
-    Colombia,
-    CookIslands,
-    Cuba,
-    Cyprus,
-    Czechia,
-    DominicanRepublic,
-    Ecuador,
-    ElSalvador,
-    Eritrea,
-    Estonia,
-    Ethiopia,
-    France,
-    Gambia,
-    Germany,
-    Haiti,
-    Honduras,
-    HongKong,
-    Hungary,
-    India,
-    Iran,
-    Iraq,
-    Ireland,
-    Israel,
-    Italy,
-    Kyrgyzstan,
-    Liberia,
-    Lithuania,
-    Moldova,
-    Morocco,
-    Mozambique,
-    Nauru,
-    Netherlands,
-    Nicaragua,
-    Niue,
-    NorthKorea,
-    Oman,
-    Pakistan,
-    PalestinianAuthority,
-    Panama,
-    Philippines,
-    Poland,
-    Portugal,
-    Qatar,
-    Russia,
-    SaudiArabia,
-    Slovakia,
-    Slovenia,
-    SolomonIslands,
-    SouthKorea,
-    Switzerland,
-    Spain,
-    Taiwan,
-    Tajikistan,
-    Thailand,
-    Turkmenistan,
-    Tonga,
-    Ukraine,
-    UnitedArabEmirates,
-    UnitedKingdom,
-    UnitedStates,
-    Uruguay,
-    VaticanCity,
-    WorldMeteorologicalOrganization,
+//
+// Each DXCC entity, current and deleted, with its ADIF DXCC entity code and
+// name, a display name, the ISO 3166 code of the country it belongs to (for
+// flags), its continent and its usual CQ and ITU zones.  Entities that span
+// several zones (e.g., the United States and Asiatic Russia) are given the
+// zone of most of their stations.
+//
+macro_rules! dxcc_entities {
+    ($($variant:ident => ($dxcc:expr, $adif:expr, $name:expr, $code:expr, $continent:ident,
+        $cq:expr, $itu:expr, $deleted:expr)),* $(,)?) => {
+        #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Country {
+            $($variant),*
+        }
+
+        impl Country {
+            pub const ALL: &'static [Country] = &[$(Country::$variant),*];
+
+            // The ADIF DXCC entity code.
+            pub fn dxcc(&self) -> u32 {
+                match self {
+                    $(Country::$variant => $dxcc),*
+                }
+            }
+            // The entity name as given in the ADIF DXCC enumeration (e.g.,
+            // "UNITED STATES OF AMERICA").
+            pub fn adif_name(&self) -> &'static str {
+                match self {
+                    $(Country::$variant => $adif),*
+                }
+            }
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Country::$variant => $name),*
+                }
+            }
+            pub fn code(&self) -> &'static str {
+                match self {
+                    $(Country::$variant => $code),*
+                }
+            }
+            pub fn continent(&self) -> AdifContinent {
+                match self {
+                    $(Country::$variant => AdifContinent::$continent),*
+                }
+            }
+            pub fn cq_zone(&self) -> u8 {
+                match self {
+                    $(Country::$variant => $cq),*
+                }
+            }
+            pub fn itu_zone(&self) -> u8 {
+                match self {
+                    $(Country::$variant => $itu),*
+                }
+            }
+            // Deleted entities still count for QSOs made while they were
+            // current.
+            pub fn is_deleted(&self) -> bool {
+                match self {
+                    $(Country::$variant => $deleted),*
+                }
+            }
+        }
+    }
+}
+
+dxcc_entities! {
+    Canada => (1, "CANADA", "Canada", "ca", NA, 5, 9, false),
+    AbuAilIslands => (2, "ABU AIL IS.", "Abu Ail Is.", "ye", AS, 21, 39, true),
+    Afghanistan => (3, "AFGHANISTAN", "Afghanistan", "af", AS, 21, 40, false),
+    AgalegaAndStBrandon => (4, "AGALEGA & ST. BRANDON IS.", "Agalega & St. Brandon Is.", "mu", AF, 39, 53, false),
+    AlandIslands => (5, "ALAND IS.", "Aland Is.", "ax", EU, 15, 18, false),
+    Alaska => (6, "ALASKA", "Alaska", "us", NA, 1, 1, false),
+    Albania => (7, "ALBANIA", "Albania", "al", EU, 15, 28, false),
+    Aldabra => (8, "ALDABRA", "Aldabra", "sc", AF, 39, 53, true),
+    AmericanSamoa => (9, "AMERICAN SAMOA", "American Samoa", "as", OC, 32, 62, false),
+    AmsterdamAndStPaul => (10, "AMSTERDAM & ST. PAUL IS.", "Amsterdam & St. Paul Is.", "tf", AF, 39, 68, false),
+    AndamanAndNicobar => (11, "ANDAMAN & NICOBAR IS.", "Andaman & Nicobar Is.", "in", AS, 26, 49, false),
+    Anguilla => (12, "ANGUILLA", "Anguilla", "ai", NA, 8, 11, false),
+    Antarctica => (13, "ANTARCTICA", "Antarctica", "aq", AN, 13, 74, false),
+    Armenia => (14, "ARMENIA", "Armenia", "am", AS, 21, 29, false),
+    AsiaticRussia => (15, "ASIATIC RUSSIA", "Asiatic Russia", "ru", AS, 17, 30, false),
+    NewZealandSubantarcticIslands => (16, "NEW ZEALAND SUBANTARCTIC ISLANDS", "New Zealand Subantarctic Islands", "nz", OC, 32, 60, false),
+    AvesIsland => (17, "AVES I.", "Aves I.", "ve", NA, 8, 11, false),
+    Azerbaijan => (18, "AZERBAIJAN", "Azerbaijan", "az", AS, 21, 29, false),
+    BajoNuevo => (19, "BAJO NUEVO", "Bajo Nuevo", "co", NA, 7, 11, true),
+    BakerAndHowland => (20, "BAKER & HOWLAND IS.", "Baker & Howland Is.", "um", OC, 31, 61, false),
+    BalearicIslands => (21, "BALEARIC IS.", "Balearic Is.", "es", EU, 14, 37, false),
+    Palau => (22, "PALAU", "Palau", "pw", OC, 27, 64, false),
+    BlenheimReef => (23, "BLENHEIM REEF", "Blenheim Reef", "mu", AF, 39, 41, true),
+    Bouvet => (24, "BOUVET", "Bouvet", "bv", AF, 38, 67, false),
+    BritishNorthBorneo => (25, "BRITISH NORTH BORNEO", "British North Borneo", "my", OC, 28, 54, true),
+    BritishSomaliland => (26, "BRITISH SOMALILAND", "British Somaliland", "so", AF, 37, 48, true),
+    Belarus => (27, "BELARUS", "Belarus", "by", EU, 16, 29, false),
+    CanalZone => (28, "CANAL ZONE", "Canal Zone", "pa", NA, 7, 11, true),
+    CanaryIslands => (29, "CANARY IS.", "Canary Is.", "es", AF, 33, 36, false),
+    CelebeAndMolucca => (30, "CELEBE & MOLUCCA IS.", "Celebe & Molucca Is.", "id", OC, 28, 54, true),
+    CentralKiribati => (31, "C. KIRIBATI (BRITISH PHOENIX IS.)", "Central Kiribati", "ki", OC, 31, 62, false),
+    CeutaAndMelilla => (32, "CEUTA & MELILLA", "Ceuta & Melilla", "es", AF, 33, 37, false),
+    ChagosIslands => (33, "CHAGOS IS.", "Chagos Is.", "io", AF, 39, 41, false),
+    ChathamIslands => (34, "CHATHAM IS.", "Chatham Is.", "nz", OC, 32, 60, false),
+    ChristmasIsland => (35, "CHRISTMAS I.", "Christmas I.", "cx", OC, 29, 54, false),
+    ClippertonIsland => (36, "CLIPPERTON I.", "Clipperton I.", "fr", NA, 7, 10, false),
+    CocosIsland => (37, "COCOS I.", "Cocos I.", "cr", NA, 7, 11, false),
+    CocosKeelingIslands => (38, "COCOS (KEELING) IS.", "Cocos (Keeling) Is.", "cc", OC, 29, 54, false),
+    FormerComoros => (39, "COMOROS", "Comoros (deleted)", "km", AF, 39, 53, true),
+    Crete => (40, "CRETE", "Crete", "gr", EU, 20, 28, false),
+    CrozetIsland => (41, "CROZET I.", "Crozet I.", "tf", AF, 39, 68, false),
+    DamaoAndDiu => (42, "DAMAO, DIU", "Damao, Diu", "in", AS, 22, 41, true),
+    DesecheoIsland => (43, "DESECHEO I.", "Desecheo I.", "pr", NA, 8, 11, false),
+    Desroches => (44, "DESROCHES", "Desroches", "sc", AF, 39, 53, true),
+    Dodecanese => (45, "DODECANESE", "Dodecanese", "gr", EU, 20, 28, false),
+    EastMalaysia => (46, "EAST MALAYSIA", "East Malaysia", "my", OC, 28, 54, false),
+    EasterIsland => (47, "EASTER I.", "Easter I.", "cl", SA, 12, 63, false),
+    EastKiribati => (48, "E. KIRIBATI (LINE IS.)", "East Kiribati", "ki", OC, 31, 61, false),
+    EquatorialGuinea => (49, "EQUATORIAL GUINEA", "Equatorial Guinea", "gq", AF, 36, 47, false),
+    Mexico => (50, "MEXICO", "Mexico", "mx", NA, 6, 10, false),
+    Eritrea => (51, "ERITREA", "Eritrea", "er", AF, 37, 48, false),
+    Estonia => (52, "ESTONIA", "Estonia", "ee", EU, 15, 29, false),
+    Ethiopia => (53, "ETHIOPIA", "Ethiopia", "et", AF, 37, 48, false),
+    EuropeanRussia => (54, "EUROPEAN RUSSIA", "European Russia", "ru", EU, 16, 29, false),
+    Farquhar => (55, "FARQUHAR", "Farquhar", "sc", AF, 39, 53, true),
+    FernandoDeNoronha => (56, "FERNANDO DE NORONHA", "Fernando de Noronha", "br", SA, 11, 13, false),
+    FrenchEquatorialAfrica => (57, "FRENCH EQUATORIAL AFRICA", "French Equatorial Africa", "cf", AF, 36, 47, true),
+    FrenchIndochina => (58, "FRENCH INDO-CHINA", "French Indo-China", "vn", AS, 26, 49, true),
+    FrenchWestAfrica => (59, "FRENCH WEST AFRICA", "French West Africa", "sn", AF, 35, 46, true),
+    Bahamas => (60, "BAHAMAS", "Bahamas", "bs", NA, 8, 11, false),
+    FranzJosefLand => (61, "FRANZ JOSEF LAND", "Franz Josef Land", "ru", EU, 40, 75, false),
+    Barbados => (62, "BARBADOS", "Barbados", "bb", NA, 8, 11, false),
+    FrenchGuiana => (63, "FRENCH GUIANA", "French Guiana", "gf", SA, 9, 12, false),
+    Bermuda => (64, "BERMUDA", "Bermuda", "bm", NA, 5, 11, false),
+    BritishVirginIslands => (65, "BRITISH VIRGIN IS.", "British Virgin Islands", "vg", NA, 8, 11, false),
+    Belize => (66, "BELIZE", "Belize", "bz", NA, 7, 11, false),
+    FrenchIndia => (67, "FRENCH INDIA", "French India", "in", AS, 22, 41, true),
+    KuwaitSaudiArabiaNeutralZone => (68, "KUWAIT/SAUDI ARABIA NEUTRAL ZONE", "Kuwait/Saudi Arabia Neutral Zone", "kw", AS, 21, 39, true),
+    CaymanIslands => (69, "CAYMAN IS.", "Cayman Is.", "ky", NA, 8, 11, false),
+    Cuba => (70, "CUBA", "Cuba", "cu", NA, 8, 11, false),
+    GalapagosIslands => (71, "GALAPAGOS IS.", "Galapagos Is.", "ec", SA, 10, 12, false),
+    DominicanRepublic => (72, "DOMINICAN REPUBLIC", "Dominican Republic", "do", NA, 8, 11, false),
+    ElSalvador => (74, "EL SALVADOR", "El Salvador", "sv", NA, 7, 11, false),
+    Georgia => (75, "GEORGIA", "Georgia", "ge", AS, 21, 29, false),
+    Guatemala => (76, "GUATEMALA", "Guatemala", "gt", NA, 7, 11, false),
+    Grenada => (77, "GRENADA", "Grenada", "gd", NA, 8, 11, false),
+    Haiti => (78, "HAITI", "Haiti", "ht", NA, 8, 11, false),
+    Guadeloupe => (79, "GUADELOUPE", "Guadeloupe", "gp", NA, 8, 11, false),
+    Honduras => (80, "HONDURAS", "Honduras", "hn", NA, 7, 11, false),
+    FormerGermany => (81, "GERMANY", "Germany (deleted)", "de", EU, 14, 28, true),
+    Jamaica => (82, "JAMAICA", "Jamaica", "jm", NA, 8, 11, false),
+    Martinique => (84, "MARTINIQUE", "Martinique", "mq", NA, 8, 11, false),
+    BonaireCuracao => (85, "BONAIRE, CURACAO", "Bonaire, Curacao", "cw", SA, 9, 11, true),
+    Nicaragua => (86, "NICARAGUA", "Nicaragua", "ni", NA, 7, 11, false),
+    Panama => (88, "PANAMA", "Panama", "pa", NA, 7, 11, false),
+    TurksAndCaicos => (89, "TURKS & CAICOS IS.", "Turks & Caicos Is.", "tc", NA, 8, 11, false),
+    TrinidadAndTobago => (90, "TRINIDAD & TOBAGO", "Trinidad & Tobago", "tt", SA, 9, 11, false),
+    Aruba => (91, "ARUBA", "Aruba", "aw", SA, 9, 11, false),
+    GeyserReef => (93, "GEYSER REEF", "Geyser Reef", "fr", AF, 39, 53, true),
+    AntiguaAndBarbuda => (94, "ANTIGUA & BARBUDA", "Antigua & Barbuda", "ag", NA, 8, 11, false),
+    Dominica => (95, "DOMINICA", "Dominica", "dm", NA, 8, 11, false),
+    Montserrat => (96, "MONTSERRAT", "Montserrat", "ms", NA, 8, 11, false),
+    StLucia => (97, "ST. LUCIA", "St. Lucia", "lc", NA, 8, 11, false),
+    StVincent => (98, "ST. VINCENT", "St. Vincent", "vc", NA, 8, 11, false),
+    GloriosoIslands => (99, "GLORIOSO IS.", "Glorioso Is.", "tf", AF, 39, 53, false),
+    Argentina => (100, "ARGENTINA", "Argentina", "ar", SA, 13, 14, false),
+    Goa => (101, "GOA", "Goa", "in", AS, 22, 41, true),
+    GoldCoastTogoland => (102, "GOLD COAST, TOGOLAND", "Gold Coast, Togoland", "gh", AF, 35, 46, true),
+    Guam => (103, "GUAM", "Guam", "gu", OC, 27, 64, false),
+    Bolivia => (104, "BOLIVIA", "Bolivia", "bo", SA, 10, 12, false),
+    GuantanamoBay => (105, "GUANTANAMO BAY", "Guantanamo Bay", "us", NA, 8, 11, false),
+    Guernsey => (106, "GUERNSEY", "Guernsey", "gg", EU, 14, 27, false),
+    Guinea => (107, "GUINEA", "Guinea", "gn", AF, 35, 46, false),
+    Brazil => (108, "BRAZIL", "Brazil", "br", SA, 11, 15, false),
+    GuineaBissau => (109, "GUINEA-BISSAU", "Guinea-Bissau", "gw", AF, 35, 46, false),
+    Hawaii => (110, "HAWAII", "Hawaii", "us", OC, 31, 61, false),
+    HeardIsland => (111, "HEARD I.", "Heard I.", "hm", AF, 39, 68, false),
+    Chile => (112, "CHILE", "Chile", "cl", SA, 12, 14, false),
+    Ifni => (113, "IFNI", "Ifni", "ma", AF, 33, 37, true),
+    IsleOfMan => (114, "ISLE OF MAN", "Isle of Man", "im", EU, 14, 27, false),
+    ItalianSomaliland => (115, "ITALIAN SOMALILAND", "Italian Somaliland", "so", AF, 37, 48, true),
+    Colombia => (116, "COLOMBIA", "Colombia", "co", SA, 9, 12, false),
+    ItuHq => (117, "ITU HQ", "ITU HQ", "ch", EU, 14, 28, false),
+    JanMayen => (118, "JAN MAYEN", "Jan Mayen", "sj", EU, 40, 18, false),
+    Java => (119, "JAVA", "Java", "id", OC, 28, 54, true),
+    Ecuador => (120, "ECUADOR", "Ecuador", "ec", SA, 10, 12, false),
+    Jersey => (122, "JERSEY", "Jersey", "je", EU, 14, 27, false),
+    JohnstonIsland => (123, "JOHNSTON I.", "Johnston I.", "um", OC, 31, 61, false),
+    JuanDeNovaEuropa => (124, "JUAN DE NOVA, EUROPA", "Juan de Nova, Europa", "tf", AF, 39, 53, false),
+    JuanFernandezIslands => (125, "JUAN FERNANDEZ IS.", "Juan Fernandez Is.", "cl", SA, 12, 14, false),
+    Kaliningrad => (126, "KALININGRAD", "Kaliningrad", "ru", EU, 15, 29, false),
+    KamaranIslands => (127, "KAMARAN IS.", "Kamaran Is.", "ye", AS, 21, 39, true),
+    KareloFinnishRepublic => (128, "KARELO-FINNISH REPUBLIC", "Karelo-Finnish Republic", "ru", EU, 16, 19, true),
+    Guyana => (129, "GUYANA", "Guyana", "gy", SA, 9, 12, false),
+    Kazakhstan => (130, "KAZAKHSTAN", "Kazakhstan", "kz", AS, 17, 30, false),
+    KerguelenIslands => (131, "KERGUELEN IS.", "Kerguelen Is.", "tf", AF, 39, 68, false),
+    Paraguay => (132, "PARAGUAY", "Paraguay", "py", SA, 11, 14, false),
+    KermadecIslands => (133, "KERMADEC IS.", "Kermadec Is.", "nz", OC, 32, 60, false),
+    KingmanReef => (134, "KINGMAN REEF", "Kingman Reef", "um", OC, 31, 61, true),
+    Kyrgyzstan => (135, "KYRGYZSTAN", "Kyrgyzstan", "kg", AS, 17, 30, false),
+    Peru => (136, "PERU", "Peru", "pe", SA, 10, 12, false),
+    SouthKorea => (137, "REPUBLIC OF KOREA", "South Korea", "kr", AS, 25, 44, false),
+    KureIsland => (138, "KURE I.", "Kure I.", "um", OC, 31, 61, false),
+    KuriaMuriaIsland => (139, "KURIA MURIA I.", "Kuria Muria I.", "om", AS, 21, 39, true),
+    Suriname => (140, "SURINAME", "Suriname", "sr", SA, 9, 12, false),
+    FalklandIslands => (141, "FALKLAND IS.", "Falkland Is.", "fk", SA, 13, 16, false),
+    LakshadweepIslands => (142, "LAKSHADWEEP IS.", "Lakshadweep Is.", "in", AS, 22, 41, false),
+    Laos => (143, "LAOS", "Laos", "la", AS, 26, 49, false),
+    Uruguay => (144, "URUGUAY", "Uruguay", "uy", SA, 13, 14, false),
+    Latvia => (145, "LATVIA", "Latvia", "lv", EU, 15, 29, false),
+    Lithuania => (146, "LITHUANIA", "Lithuania", "lt", EU, 15, 29, false),
+    LordHoweIsland => (147, "LORD HOWE I.", "Lord Howe I.", "au", OC, 30, 60, false),
+    Venezuela => (148, "VENEZUELA", "Venezuela", "ve", SA, 9, 12, false),
+    Azores => (149, "AZORES", "Azores", "pt", EU, 14, 36, false),
+    Australia => (150, "AUSTRALIA", "Australia", "au", OC, 30, 59, false),
+    MalyjVysotskijIsland => (151, "MALYJ VYSOTSKIJ I.", "Malyj Vysotskij I.", "ru", EU, 16, 29, true),
+    Macao => (152, "MACAO", "Macao", "mo", AS, 24, 44, false),
+    MacquarieIsland => (153, "MACQUARIE I.", "Macquarie I.", "au", OC, 30, 60, false),
+    YemenArabRepublic => (154, "YEMEN ARAB REPUBLIC", "Yemen Arab Republic", "ye", AS, 21, 39, true),
+    Malaya => (155, "MALAYA", "Malaya", "my", AS, 28, 54, true),
+    Nauru => (157, "NAURU", "Nauru", "nr", OC, 31, 65, false),
+    Vanuatu => (158, "VANUATU", "Vanuatu", "vu", OC, 32, 56, false),
+    Maldives => (159, "MALDIVES", "Maldives", "mv", AS, 22, 41, false),
+    Tonga => (160, "TONGA", "Tonga", "to", OC, 32, 62, false),
+    MalpeloIsland => (161, "MALPELO I.", "Malpelo I.", "co", SA, 9, 12, false),
+    NewCaledonia => (162, "NEW CALEDONIA", "New Caledonia", "nc", OC, 32, 56, false),
+    PapuaNewGuinea => (163, "PAPUA NEW GUINEA", "Papua New Guinea", "pg", OC, 28, 51, false),
+    Manchuria => (164, "MANCHURIA", "Manchuria", "cn", AS, 24, 33, true),
+    Mauritius => (165, "MAURITIUS", "Mauritius", "mu", AF, 39, 53, false),
+    MarianaIslands => (166, "MARIANA IS.", "Mariana Is.", "mp", OC, 27, 64, false),
+    MarketReef => (167, "MARKET REEF", "Market Reef", "ax", EU, 15, 18, false),
+    MarshallIslands => (168, "MARSHALL IS.", "Marshall Is.", "mh", OC, 31, 65, false),
+    Mayotte => (169, "MAYOTTE", "Mayotte", "yt", AF, 39, 53, false),
+    NewZealand => (170, "NEW ZEALAND", "New Zealand", "nz", OC, 32, 60, false),
+    MellishReef => (171, "MELLISH REEF", "Mellish Reef", "au", OC, 30, 56, false),
+    PitcairnIsland => (172, "PITCAIRN I.", "Pitcairn I.", "pn", OC, 32, 63, false),
+    Micronesia => (173, "MICRONESIA", "Micronesia", "fm", OC, 27, 65, false),
+    MidwayIsland => (174, "MIDWAY I.", "Midway I.", "um", OC, 31, 61, false),
+    FrenchPolynesia => (175, "FRENCH POLYNESIA", "French Polynesia", "pf", OC, 32, 63, false),
+    Fiji => (176, "FIJI", "Fiji", "fj", OC, 32, 56, false),
+    MinamiTorishima => (177, "MINAMI TORISHIMA", "Minami Torishima", "jp", OC, 27, 90, false),
+    MinervaReef => (178, "MINERVA REEF", "Minerva Reef", "to", OC, 32, 62, true),
+    Moldova => (179, "MOLDOVA", "Moldova", "md", EU, 16, 29, false),
+    MountAthos => (180, "MOUNT ATHOS", "Mount Athos", "gr", EU, 20, 28, false),
+    Mozambique => (181, "MOZAMBIQUE", "Mozambique", "mz", AF, 37, 53, false),
+    NavassaIsland => (182, "NAVASSA I.", "Navassa I.", "um", NA, 8, 11, false),
+    NetherlandsBorneo => (183, "NETHERLANDS BORNEO", "Netherlands Borneo", "id", OC, 28, 54, true),
+    NetherlandsNewGuinea => (184, "NETHERLANDS NEW GUINEA", "Netherlands New Guinea", "id", OC, 28, 51, true),
+    SolomonIslands => (185, "SOLOMON IS.", "Solomon Islands", "sb", OC, 28, 51, false),
+    NewfoundlandLabrador => (186, "NEWFOUNDLAND, LABRADOR", "Newfoundland, Labrador", "ca", NA, 5, 9, true),
+    Niger => (187, "NIGER", "Niger", "ne", AF, 35, 46, false),
+    Niue => (188, "NIUE", "Niue", "nu", OC, 32, 62, false),
+    NorfolkIsland => (189, "NORFOLK I.", "Norfolk I.", "nf", OC, 32, 60, false),
+    Samoa => (190, "SAMOA", "Samoa", "ws", OC, 32, 62, false),
+    NorthCookIslands => (191, "NORTH COOK IS.", "North Cook Is.", "ck", OC, 32, 62, false),
+    Ogasawara => (192, "OGASAWARA", "Ogasawara", "jp", AS, 27, 45, false),
+    Okinawa => (193, "OKINAWA (RYUKYU IS.)", "Okinawa (Ryukyu Is.)", "jp", AS, 25, 45, true),
+    OkinoToriShima => (194, "OKINO TORI-SHIMA", "Okino Tori-shima", "jp", AS, 27, 45, true),
+    AnnobonIsland => (195, "ANNOBON I.", "Annobon I.", "gq", AF, 36, 52, false),
+    FormerPalestine => (196, "PALESTINE", "Palestine (deleted)", "ps", AS, 20, 39, true),
+    PalmyraAndJarvis => (197, "PALMYRA & JARVIS IS.", "Palmyra & Jarvis Is.", "um", OC, 31, 61, false),
+    PapuaTerritory => (198, "PAPUA TERRITORY", "Papua Territory", "pg", OC, 28, 51, true),
+    PeterIIsland => (199, "PETER 1 I.", "Peter 1 I.", "aq", AN, 12, 72, false),
+    PortugueseTimor => (200, "PORTUGUESE TIMOR", "Portuguese Timor", "tl", OC, 28, 54, true),
+    PrinceEdwardAndMarion => (201, "PRINCE EDWARD & MARION IS.", "Prince Edward & Marion Is.", "za", AF, 38, 57, false),
+    PuertoRico => (202, "PUERTO RICO", "Puerto Rico", "pr", NA, 8, 11, false),
+    Andorra => (203, "ANDORRA", "Andorra", "ad", EU, 14, 27, false),
+    Revillagigedo => (204, "REVILLAGIGEDO", "Revillagigedo", "mx", NA, 6, 10, false),
+    AscensionIsland => (205, "ASCENSION I.", "Ascension I.", "sh", AF, 36, 66, false),
+    Austria => (206, "AUSTRIA", "Austria", "at", EU, 15, 28, false),
+    RodriguesIsland => (207, "RODRIGUES I.", "Rodrigues I.", "mu", AF, 39, 53, false),
+    RuandaUrundi => (208, "RUANDA-URUNDI", "Ruanda-Urundi", "rw", AF, 36, 52, true),
+    Belgium => (209, "BELGIUM", "Belgium", "be", EU, 14, 27, false),
+    Saar => (210, "SAAR", "Saar", "de", EU, 14, 28, true),
+    SableIsland => (211, "SABLE I.", "Sable I.", "ca", NA, 5, 9, false),
+    Bulgaria => (212, "BULGARIA", "Bulgaria", "bg", EU, 20, 28, false),
+    SaintMartin => (213, "SAINT MARTIN", "Saint Martin", "mf", NA, 8, 11, false),
+    Corsica => (214, "CORSICA", "Corsica", "fr", EU, 15, 28, false),
+    Cyprus => (215, "CYPRUS", "Cyprus", "cy", AS, 20, 39, false),
+    SanAndresAndProvidencia => (216, "SAN ANDRES & PROVIDENCIA", "San Andres & Providencia", "co", NA, 7, 11, false),
+    SanFelixAndSanAmbrosio => (217, "SAN FELIX & SAN AMBROSIO", "San Felix & San Ambrosio", "cl", SA, 12, 14, false),
+    Czechoslovakia => (218, "CZECHOSLOVAKIA", "Czechoslovakia", "cz", EU, 15, 28, true),
+    SaoTomeAndPrincipe => (219, "SAO TOME & PRINCIPE", "Sao Tome & Principe", "st", AF, 36, 47, false),
+    Sarawak => (220, "SARAWAK", "Sarawak", "my", OC, 28, 54, true),
+    Denmark => (221, "DENMARK", "Denmark", "dk", EU, 14, 18, false),
+    FaroeIslands => (222, "FAROE IS.", "Faroe Is.", "fo", EU, 14, 18, false),
+    England => (223, "ENGLAND", "England", "gb", EU, 14, 27, false),
+    Finland => (224, "FINLAND", "Finland", "fi", EU, 15, 18, false),
+    Sardinia => (225, "SARDINIA", "Sardinia", "it", EU, 15, 28, false),
+    SaudiArabiaIraqNeutralZone => (226, "SAUDI ARABIA/IRAQ NEUTRAL ZONE", "Saudi Arabia/Iraq Neutral Zone", "sa", AS, 21, 39, true),
+    France => (227, "FRANCE", "France", "fr", EU, 14, 27, false),
+    SerranaBankAndRoncadorCay => (228, "SERRANA BANK & RONCADOR CAY", "Serrana Bank & Roncador Cay", "co", NA, 7, 11, true),
+    GermanDemocraticRepublic => (229, "GERMAN DEMOCRATIC REPUBLIC", "German Democratic Republic", "de", EU, 14, 28, true),
+    Germany => (230, "FEDERAL REPUBLIC OF GERMANY", "Germany", "de", EU, 14, 28, false),
+    Sikkim => (231, "SIKKIM", "Sikkim", "in", AS, 22, 41, true),
+    Somalia => (232, "SOMALIA", "Somalia", "so", AF, 37, 48, false),
+    Gibraltar => (233, "GIBRALTAR", "Gibraltar", "gi", EU, 14, 37, false),
+    SouthCookIslands => (234, "SOUTH COOK IS.", "South Cook Is.", "ck", OC, 32, 62, false),
+    SouthGeorgiaIsland => (235, "SOUTH GEORGIA I.", "South Georgia I.", "gs", SA, 13, 73, false),
+    Greece => (236, "GREECE", "Greece", "gr", EU, 20, 28, false),
+    Greenland => (237, "GREENLAND", "Greenland", "gl", NA, 40, 5, false),
+    SouthOrkneyIslands => (238, "SOUTH ORKNEY IS.", "South Orkney Is.", "aq", SA, 13, 73, false),
+    Hungary => (239, "HUNGARY", "Hungary", "hu", EU, 15, 28, false),
+    SouthSandwichIslands => (240, "SOUTH SANDWICH IS.", "South Sandwich Is.", "gs", SA, 13, 73, false),
+    SouthShetlandIslands => (241, "SOUTH SHETLAND IS.", "South Shetland Is.", "aq", SA, 13, 73, false),
+    Iceland => (242, "ICELAND", "Iceland", "is", EU, 40, 17, false),
+    SouthYemen => (243, "PEOPLE'S DEMOCRATIC REP. OF YEMEN", "People's Democratic Rep. of Yemen", "ye", AS, 21, 39, true),
+    SouthernSudan => (244, "SOUTHERN SUDAN", "Southern Sudan", "sd", AF, 34, 48, true),
+    Ireland => (245, "IRELAND", "Ireland", "ie", EU, 14, 27, false),
+    SovereignMilitaryOrderOfMalta => (246, "SOVEREIGN MILITARY ORDER OF MALTA", "Sovereign Military Order of Malta", "it", EU, 15, 28, false),
+    SpratlyIslands => (247, "SPRATLY IS.", "Spratly Is.", "cn", AS, 26, 50, false),
+    Italy => (248, "ITALY", "Italy", "it", EU, 15, 28, false),
+    StKittsAndNevis => (249, "ST. KITTS & NEVIS", "St. Kitts & Nevis", "kn", NA, 8, 11, false),
+    StHelena => (250, "ST. HELENA", "St. Helena", "sh", AF, 36, 66, false),
+    Liechtenstein => (251, "LIECHTENSTEIN", "Liechtenstein", "li", EU, 14, 28, false),
+    StPaulIsland => (252, "ST. PAUL I.", "St. Paul I.", "ca", NA, 5, 9, false),
+    StPeterAndStPaulRocks => (253, "ST. PETER & ST. PAUL ROCKS", "St. Peter & St. Paul Rocks", "br", SA, 11, 13, false),
+    Luxembourg => (254, "LUXEMBOURG", "Luxembourg", "lu", EU, 14, 27, false),
+    StMaartenSabaStEustatius => (255, "ST. MAARTEN, SABA, ST. EUSTATIUS", "St. Maarten, Saba, St. Eustatius", "sx", NA, 8, 11, true),
+    MadeiraIslands => (256, "MADEIRA IS.", "Madeira Is.", "pt", AF, 33, 36, false),
+    Malta => (257, "MALTA", "Malta", "mt", EU, 15, 28, false),
+    Sumatra => (258, "SUMATRA", "Sumatra", "id", OC, 28, 54, true),
+    Svalbard => (259, "SVALBARD", "Svalbard", "sj", EU, 40, 18, false),
+    Monaco => (260, "MONACO", "Monaco", "mc", EU, 14, 27, false),
+    SwanIslands => (261, "SWAN IS.", "Swan Is.", "hn", NA, 7, 11, true),
+    Tajikistan => (262, "TAJIKISTAN", "Tajikistan", "tj", AS, 17, 30, false),
+    Netherlands => (263, "NETHERLANDS", "Netherlands", "nl", EU, 14, 27, false),
+    Tangier => (264, "TANGIER", "Tangier", "ma", AF, 33, 37, true),
+    NorthernIreland => (265, "NORTHERN IRELAND", "Northern Ireland", "gb", EU, 14, 27, false),
+    Norway => (266, "NORWAY", "Norway", "no", EU, 14, 18, false),
+    TerritoryOfNewGuinea => (267, "TERRITORY OF NEW GUINEA", "Territory of New Guinea", "pg", OC, 28, 51, true),
+    Tibet => (268, "TIBET", "Tibet", "cn", AS, 23, 41, true),
+    Poland => (269, "POLAND", "Poland", "pl", EU, 15, 28, false),
+    TokelauIslands => (270, "TOKELAU IS.", "Tokelau Is.", "tk", OC, 31, 62, false),
+    Trieste => (271, "TRIESTE", "Trieste", "it", EU, 15, 28, true),
+    Portugal => (272, "PORTUGAL", "Portugal", "pt", EU, 14, 37, false),
+    TrindadeAndMartimVaz => (273, "TRINDADE & MARTIM VAZ IS.", "Trindade & Martim Vaz Is.", "br", SA, 11, 15, false),
+    TristanDaCunhaAndGough => (274, "TRISTAN DA CUNHA & GOUGH I.", "Tristan da Cunha & Gough I.", "sh", AF, 38, 66, false),
+    Romania => (275, "ROMANIA", "Romania", "ro", EU, 20, 28, false),
+    TromelinIsland => (276, "TROMELIN I.", "Tromelin I.", "tf", AF, 39, 53, false),
+    StPierreAndMiquelon => (277, "ST. PIERRE & MIQUELON", "St. Pierre & Miquelon", "pm", NA, 5, 9, false),
+    SanMarino => (278, "SAN MARINO", "San Marino", "sm", EU, 15, 28, false),
+    Scotland => (279, "SCOTLAND", "Scotland", "gb", EU, 14, 27, false),
+    Turkmenistan => (280, "TURKMENISTAN", "Turkmenistan", "tm", AS, 17, 30, false),
+    Spain => (281, "SPAIN", "Spain", "es", EU, 14, 37, false),
+    Tuvalu => (282, "TUVALU", "Tuvalu", "tv", OC, 31, 65, false),
+    UkSovereignBaseAreasOnCyprus => (283, "UK SOVEREIGN BASE AREAS ON CYPRUS", "UK Sovereign Base Areas on Cyprus", "cy", AS, 20, 39, false),
+    Sweden => (284, "SWEDEN", "Sweden", "se", EU, 14, 18, false),
+    VirginIslands => (285, "VIRGIN IS.", "Virgin Is.", "vi", NA, 8, 11, false),
+    Uganda => (286, "UGANDA", "Uganda", "ug", AF, 37, 48, false),
+    Switzerland => (287, "SWITZERLAND", "Switzerland", "ch", EU, 14, 28, false),
+    Ukraine => (288, "UKRAINE", "Ukraine", "ua", EU, 16, 29, false),
+    UnitedNationsHq => (289, "UNITED NATIONS HQ", "United Nations HQ", "un", NA, 5, 8, false),
+    UnitedStates => (291, "UNITED STATES OF AMERICA", "United States", "us", NA, 5, 8, false),
+    Uzbekistan => (292, "UZBEKISTAN", "Uzbekistan", "uz", AS, 17, 30, false),
+    Vietnam => (293, "VIET NAM", "Viet Nam", "vn", AS, 26, 49, false),
+    Wales => (294, "WALES", "Wales", "gb", EU, 14, 27, false),
+    VaticanCity => (295, "VATICAN", "Vatican City", "va", EU, 15, 28, false),
+    Serbia => (296, "SERBIA", "Serbia", "rs", EU, 15, 28, false),
+    WakeIsland => (297, "WAKE I.", "Wake I.", "um", OC, 31, 65, false),
+    WallisAndFutuna => (298, "WALLIS & FUTUNA IS.", "Wallis & Futuna Is.", "wf", OC, 32, 62, false),
+    WestMalaysia => (299, "WEST MALAYSIA", "West Malaysia", "my", AS, 28, 54, false),
+    WestKiribati => (301, "W. KIRIBATI (GILBERT IS. )", "West Kiribati", "ki", OC, 31, 65, false),
+    WesternSahara => (302, "WESTERN SAHARA", "Western Sahara", "eh", AF, 33, 46, false),
+    WillisIsland => (303, "WILLIS I.", "Willis I.", "au", OC, 30, 55, false),
+    Bahrain => (304, "BAHRAIN", "Bahrain", "bh", AS, 21, 39, false),
+    Bangladesh => (305, "BANGLADESH", "Bangladesh", "bd", AS, 22, 41, false),
+    Bhutan => (306, "BHUTAN", "Bhutan", "bt", AS, 22, 41, false),
+    Zanzibar => (307, "ZANZIBAR", "Zanzibar", "tz", AF, 37, 53, true),
+    CostaRica => (308, "COSTA RICA", "Costa Rica", "cr", NA, 7, 11, false),
+    Myanmar => (309, "MYANMAR", "Myanmar", "mm", AS, 26, 49, false),
+    Cambodia => (312, "CAMBODIA", "Cambodia", "kh", AS, 26, 49, false),
+    SriLanka => (315, "SRI LANKA", "Sri Lanka", "lk", AS, 22, 41, false),
+    China => (318, "CHINA", "China", "cn", AS, 24, 44, false),
+    HongKong => (321, "HONG KONG", "Hong Kong", "hk", AS, 24, 44, false),
+    India => (324, "INDIA", "India", "in", AS, 22, 41, false),
+    Indonesia => (327, "INDONESIA", "Indonesia", "id", OC, 28, 51, false),
+    Iran => (330, "IRAN", "Iran", "ir", AS, 21, 40, false),
+    Iraq => (333, "IRAQ", "Iraq", "iq", AS, 21, 39, false),
+    Israel => (336, "ISRAEL", "Israel", "il", AS, 20, 39, false),
+    Japan => (339, "JAPAN", "Japan", "jp", AS, 25, 45, false),
+    Jordan => (342, "JORDAN", "Jordan", "jo", AS, 20, 39, false),
+    NorthKorea => (344, "DEMOCRATIC PEOPLE'S REP. OF KOREA", "North Korea", "kp", AS, 25, 44, false),
+    Brunei => (345, "BRUNEI DARUSSALAM", "Brunei", "bn", OC, 28, 54, false),
+    Kuwait => (348, "KUWAIT", "Kuwait", "kw", AS, 21, 39, false),
+    Lebanon => (354, "LEBANON", "Lebanon", "lb", AS, 20, 39, false),
+    Mongolia => (363, "MONGOLIA", "Mongolia", "mn", AS, 23, 32, false),
+    Nepal => (369, "NEPAL", "Nepal", "np", AS, 22, 42, false),
+    Oman => (370, "OMAN", "Oman", "om", AS, 21, 39, false),
+    Pakistan => (372, "PAKISTAN", "Pakistan", "pk", AS, 21, 41, false),
+    Philippines => (375, "PHILIPPINES", "Philippines", "ph", OC, 27, 50, false),
+    Qatar => (376, "QATAR", "Qatar", "qa", AS, 21, 39, false),
+    SaudiArabia => (378, "SAUDI ARABIA", "Saudi Arabia", "sa", AS, 21, 39, false),
+    Seychelles => (379, "SEYCHELLES", "Seychelles", "sc", AF, 39, 53, false),
+    Singapore => (381, "SINGAPORE", "Singapore", "sg", AS, 28, 54, false),
+    Djibouti => (382, "DJIBOUTI", "Djibouti", "dj", AF, 37, 48, false),
+    Syria => (384, "SYRIA", "Syria", "sy", AS, 20, 39, false),
+    Taiwan => (386, "TAIWAN", "Taiwan", "tw", AS, 24, 44, false),
+    Thailand => (387, "THAILAND", "Thailand", "th", AS, 26, 49, false),
+    Turkey => (390, "TURKEY", "Turkey", "tr", AS, 20, 39, false),
+    UnitedArabEmirates => (391, "UNITED ARAB EMIRATES", "United Arab Emirates", "ae", AS, 21, 39, false),
+    Algeria => (400, "ALGERIA", "Algeria", "dz", AF, 33, 37, false),
+    Angola => (401, "ANGOLA", "Angola", "ao", AF, 36, 52, false),
+    Botswana => (402, "BOTSWANA", "Botswana", "bw", AF, 38, 57, false),
+    Burundi => (404, "BURUNDI", "Burundi", "bi", AF, 36, 52, false),
+    Cameroon => (406, "CAMEROON", "Cameroon", "cm", AF, 36, 47, false),
+    CentralAfrica => (408, "CENTRAL AFRICA", "Central African Republic", "cf", AF, 36, 47, false),
+    CapeVerde => (409, "CAPE VERDE", "Cape Verde", "cv", AF, 35, 46, false),
+    Chad => (410, "CHAD", "Chad", "td", AF, 36, 47, false),
+    Comoros => (411, "COMOROS", "Comoros", "km", AF, 39, 53, false),
+    RepublicOfTheCongo => (412, "REPUBLIC OF THE CONGO", "Republic of the Congo", "cg", AF, 36, 52, false),
+    DemocraticRepublicOfTheCongo => (414, "DEMOCRATIC REPUBLIC OF THE CONGO", "Democratic Republic of the Congo", "cd", AF, 36, 52, false),
+    Benin => (416, "BENIN", "Benin", "bj", AF, 35, 46, false),
+    Gabon => (420, "GABON", "Gabon", "ga", AF, 36, 52, false),
+    Gambia => (422, "THE GAMBIA", "Gambia", "gm", AF, 35, 46, false),
+    Ghana => (424, "GHANA", "Ghana", "gh", AF, 35, 46, false),
+    CoteDIvoire => (428, "COTE D'IVOIRE", "Cote d'Ivoire", "ci", AF, 35, 46, false),
+    Kenya => (430, "KENYA", "Kenya", "ke", AF, 37, 48, false),
+    Lesotho => (432, "LESOTHO", "Lesotho", "ls", AF, 38, 57, false),
+    Liberia => (434, "LIBERIA", "Liberia", "lr", AF, 35, 46, false),
+    Libya => (436, "LIBYA", "Libya", "ly", AF, 34, 38, false),
+    Madagascar => (438, "MADAGASCAR", "Madagascar", "mg", AF, 39, 53, false),
+    Malawi => (440, "MALAWI", "Malawi", "mw", AF, 37, 53, false),
+    Mali => (442, "MALI", "Mali", "ml", AF, 35, 46, false),
+    Mauritania => (444, "MAURITANIA", "Mauritania", "mr", AF, 35, 46, false),
+    Morocco => (446, "MOROCCO", "Morocco", "ma", AF, 33, 37, false),
+    Nigeria => (450, "NIGERIA", "Nigeria", "ng", AF, 35, 46, false),
+    Zimbabwe => (452, "ZIMBABWE", "Zimbabwe", "zw", AF, 38, 53, false),
+    ReunionIsland => (453, "REUNION I.", "Reunion I.", "re", AF, 39, 53, false),
+    Rwanda => (454, "RWANDA", "Rwanda", "rw", AF, 36, 52, false),
+    Senegal => (456, "SENEGAL", "Senegal", "sn", AF, 35, 46, false),
+    SierraLeone => (458, "SIERRA LEONE", "Sierra Leone", "sl", AF, 35, 46, false),
+    RotumaIsland => (460, "ROTUMA I.", "Rotuma I.", "fj", OC, 32, 56, false),
+    SouthAfrica => (462, "REPUBLIC OF SOUTH AFRICA", "South Africa", "za", AF, 38, 57, false),
+    Namibia => (464, "NAMIBIA", "Namibia", "na", AF, 38, 57, false),
+    Sudan => (466, "SUDAN", "Sudan", "sd", AF, 34, 48, false),
+    Eswatini => (468, "KINGDOM OF ESWATINI", "Eswatini", "sz", AF, 38, 57, false),
+    Tanzania => (470, "TANZANIA", "Tanzania", "tz", AF, 37, 53, false),
+    Tunisia => (474, "TUNISIA", "Tunisia", "tn", AF, 33, 37, false),
+    Egypt => (478, "EGYPT", "Egypt", "eg", AF, 34, 38, false),
+    BurkinaFaso => (480, "BURKINA FASO", "Burkina Faso", "bf", AF, 35, 46, false),
+    Zambia => (482, "ZAMBIA", "Zambia", "zm", AF, 36, 53, false),
+    Togo => (483, "TOGO", "Togo", "tg", AF, 35, 46, false),
+    WalvisBay => (488, "WALVIS BAY", "Walvis Bay", "na", AF, 38, 57, true),
+    ConwayReef => (489, "CONWAY REEF", "Conway Reef", "fj", OC, 32, 56, false),
+    BanabaIsland => (490, "BANABA I. (OCEAN I.)", "Banaba I.", "ki", OC, 31, 65, false),
+    Yemen => (492, "YEMEN", "Yemen", "ye", AS, 21, 39, false),
+    PenguinIslands => (493, "PENGUIN IS.", "Penguin Is.", "na", AF, 38, 57, true),
+    Croatia => (497, "CROATIA", "Croatia", "hr", EU, 15, 28, false),
+    Slovenia => (499, "SLOVENIA", "Slovenia", "si", EU, 15, 28, false),
+    BosniaAndHerzegovina => (501, "BOSNIA-HERZEGOVINA", "Bosnia and Herzegovina", "ba", EU, 15, 28, false),
+    NorthMacedonia => (502, "NORTH MACEDONIA (REPUBLIC OF)", "North Macedonia", "mk", EU, 15, 28, false),
+    Czechia => (503, "CZECH REPUBLIC", "Czechia", "cz", EU, 15, 28, false),
+    Slovakia => (504, "SLOVAK REPUBLIC", "Slovakia", "sk", EU, 15, 28, false),
+    PratasIsland => (505, "PRATAS I.", "Pratas I.", "tw", AS, 24, 44, false),
+    ScarboroughReef => (506, "SCARBOROUGH REEF", "Scarborough Reef", "ph", AS, 27, 50, false),
+    TemotuProvince => (507, "TEMOTU PROVINCE", "Temotu Province", "sb", OC, 32, 51, false),
+    AustralIslands => (508, "AUSTRAL I.", "Austral I.", "pf", OC, 32, 63, false),
+    MarquesasIslands => (509, "MARQUESAS IS.", "Marquesas Is.", "pf", OC, 31, 63, false),
+    PalestinianAuthority => (510, "PALESTINE", "Palestinian Authority", "ps", AS, 20, 39, false),
+    TimorLeste => (511, "TIMOR - LESTE", "Timor-Leste", "tl", OC, 28, 54, false),
+    ChesterfieldIslands => (512, "CHESTERFIELD IS.", "Chesterfield Is.", "nc", OC, 30, 56, false),
+    DucieIsland => (513, "DUCIE I.", "Ducie I.", "pn", OC, 32, 63, false),
+    Montenegro => (514, "MONTENEGRO", "Montenegro", "me", EU, 15, 28, false),
+    SwainsIsland => (515, "SWAINS I.", "Swains I.", "as", OC, 32, 62, false),
+    SaintBarthelemy => (516, "SAINT BARTHELEMY", "Saint Barthelemy", "bl", NA, 8, 11, false),
+    Curacao => (517, "CURACAO", "Curacao", "cw", SA, 9, 11, false),
+    SintMaarten => (518, "SINT MAARTEN", "Sint Maarten", "sx", NA, 8, 11, false),
+    SabaAndStEustatius => (519, "SABA & ST. EUSTATIUS", "Saba & St. Eustatius", "bq", NA, 8, 11, false),
+    Bonaire => (520, "BONAIRE", "Bonaire", "bq", SA, 9, 11, false),
+    SouthSudan => (521, "SOUTH SUDAN (REPUBLIC OF)", "South Sudan", "ss", AF, 34, 48, false),
+    Kosovo => (522, "REPUBLIC OF KOSOVO", "Kosovo", "xk", EU, 15, 28, false)
 }
 
 impl Country {
-    pub fn code(&self) -> &'static str {
-        match self {
-            Country::Alaska => "us",
-            Country::Andorra => "ad",
-            Country::Angola => "ao",
-            Country::Argentina => "ar",
-            Country::Armenia => "am",
-            Country::Australia => "au",
-            Country::Austria => "at",
-            Country::Azores => "pz",
-            Country::Bahamas => "bs",
-            Country::Bahrain => "bh",
-            Country::Belarus => "by",
-            Country::Bhutan => "bt",
-            Country::Bolivia => "bo",
-            Country::BosniaAndHerzegovina => "ba",
-            Country::Botswana => "bw",
-            Country::Brazil => "br",
-            Country::Canada => "ca",
-            Country::CapeVerde => "cv",
-            Country::Chile => "cl",
-            Country::China => "cn",
-            Country::Comoros => "km",
-            Country::Colombia => "co",
-            Country::CookIslands => "ck",
-            Country::Cuba => "cu",
-            Country::Cyprus => "cy",
-            Country::Czechia => "cz",
-            Country::DominicanRepublic => "dm",
-            Country::Ecuador => "ec",
-            Country::ElSalvador => "sv",
-            Country::Eritrea => "er",
-            Country::Estonia => "ee",
-            Country::Ethiopia => "et",
-            Country::France => "fr",
-            Country::Gambia => "gm",
-            Country::Germany => "de",
-            Country::Haiti => "ht",
-            Country::Honduras => "hn",
-            Country::HongKong => "hk",
-            Country::Hungary => "hu",
-            Country::India => "in",
-            Country::Iran => "ir",
-            Country::Iraq => "iq",
-            Country::Ireland => "ie",
-            Country::Israel => "il",
-            Country::Italy => "it",
-            Country::Kyrgyzstan => "kg",
-            Country::Liberia => "lr",
-            Country::Lithuania => "lt",
-            Country::Moldova => "md",
-            Country::Morocco => "ma",
-            Country::Mozambique => "mz",
-            Country::Nauru => "nr",
-            Country::Netherlands => "nl",
-            Country::Nicaragua => "ni",
-            Country::Niue => "nu",
-            Country::NorthKorea => "kp",
-            Country::Oman => "om",
-            Country::Pakistan => "pk",
-            Country::PalestinianAuthority => "ps",
-            Country::Panama => "pa",
-            Country::Philippines => "ph",
-            Country::Poland => "pl",
-            Country::Portugal => "pt",
-            Country::Qatar => "qa",
-            Country::Russia => "ru",
-            Country::SaudiArabia => "sa",
-            Country::Slovakia => "sk",
-            Country::Slovenia => "si",
-            Country::SolomonIslands => "sb",
-            Country::SouthKorea => "kr",
-            Country::Switzerland => "ch",
-            Country::Spain => "es",
-            Country::Taiwan => "tw",
-            Country::Tajikistan => "tj",
-            Country::Thailand => "th",
-            Country::Turkmenistan => "tm",
-            Country::Tonga => "to",
-            Country::Ukraine => "ua",
-            Country::UnitedArabEmirates => "ae",
-            Country::UnitedKingdom => "gb",
-            Country::UnitedStates => "us",
-            Country::Uruguay => "uy",
-            Country::VaticanCity => "va",
-            Country::WorldMeteorologicalOrganization => "un",
-        }
+    // The entity with an ADIF DXCC entity code.  Code 0 ("none", used for
+    // maritime mobile stations) has no entity.
+    pub fn from_dxcc(dxcc: u32) -> Option<Country> {
+        Country::ALL.iter().find(|country| country.dxcc() == dxcc).copied()
+    }
+
+    // The entity for the value of an ADIF "country" field, which should be
+    // the entity's ADIF name but is matched case-insensitively against the
+    // display name as well.
+    pub fn from_adif_name(name: &str) -> Option<Country> {
+        let name = name.trim();
+        Country::ALL.iter()
+            .find(|country| country.adif_name().eq_ignore_ascii_case(name) ||
+                country.name().eq_ignore_ascii_case(name))
+            .copied()
     }
-    pub fn name(&self) -> &'static str {
-        match self {
-            Country::Alaska => "Alaska",
-            Country::Andorra => "Andorra",
-            Country::Angola => "Angola",
-            Country::Argentina => "Argentina",
-            Country::Armenia => "Armenia",
-            Country::Australia => "Australia",
-            Country::Austria => "Austria",
-            Country::Azores => "Azores",
-            Country::Bahamas => "Bahamas",
-            Country::Bahrain => "Bahrain",
-            Country::Belarus => "Belarus",
-            Country::Bhutan => "Bhutan",
-            Country::Bolivia => "Bolivia",
-            Country::BosniaAndHerzegovina => "Bosnia and Herzegovina",
-            Country::Botswana => "Botswana",
-            Country::Brazil => "Brazil",
-            Country::Canada => "Canada",
-            Country::CapeVerde => "CapeVerde",
-            Country::Chile => "Chile",
-            Country::China => "China",
-            Country::Comoros => "Comoros",
-            Country::Colombia => "Colombia",
-            Country::CookIslands => "Cook Islands",
-            Country::Cuba => "Cuba",
-            Country::Cyprus => "Cyprus",
-            Country::Czechia => "Czechia",
-            Country::DominicanRepublic => "Dominican Republic",
-            Country::Ecuador => "Ecuador",
-            Country::ElSalvador => "El Salvador",
-            Country::Eritrea => "Eritrea",
-            Country::Estonia => "Estonia",
-            Country::Ethiopia => "Ethiopia",
-            Country::France => "France",
-            Country::Gambia => "Gambia",
-            Country::Germany => "Germany",
-            Country::Haiti => "Haiti",
-            Country::Honduras => "Honduras",
-            Country::HongKong => "Hong Kong",
-            Country::Hungary => "Hungary",
-            Country::India => "India",
-            Country::Iran => "Iran",
-            Country::Iraq => "Iraq",
-            Country::Ireland => "Ireland",
-            Country::Israel => "Israel",
-            Country::Italy => "Italy",
-            Country::Kyrgyzstan => "Kyrgyzstan",
-            Country::Liberia => "Liberia",
-            Country::Lithuania => "Lithuania",
-            Country::Moldova => "Moldova",
-            Country::Morocco => "Morocco",
-            Country::Mozambique => "Mozambique",
-            Country::Nauru => "Nauru",
-            Country::Netherlands => "Netherlands",
-            Country::Nicaragua => "Nicaragua",
-            Country::Niue => "Niue",
-            Country::NorthKorea => "North Korea",
-            Country::Oman => "Oman",
-            Country::Pakistan => "Pakistan",
-            Country::PalestinianAuthority => "Palestinian Authority",
-            Country::Panama => "Panama",
-            Country::Philippines => "Philippines",
-            Country::Poland => "Poland",
-            Country::Portugal => "Portugal",
-            Country::Qatar => "Qatar",
-            Country::Russia => "Russia",
-            Country::SaudiArabia => "Saudi Arabia",
-            Country::Slovakia => "Slovakia",
-            Country::Slovenia => "Slovenia",
-            Country::SolomonIslands => "Solomon Islands",
-            Country::SouthKorea => "South Korea",
-            Country::Switzerland => "Switzerland",
-            Country::Spain => "Spain",
-            Country::Taiwan => "Taiwan",
-            Country::Tajikistan => "Tajikistan",
-            Country::Thailand => "Thailand",
-            Country::Turkmenistan => "Turkmenistan",
-            Country::Tonga => "Tonga",
-            Country::Ukraine => "Ukraine",
-            Country::UnitedArabEmirates => "United Arab Emirates",
-            Country::UnitedKingdom => "United Kingdom",
-            Country::UnitedStates => "United States",
-            Country::Uruguay => "Uruguay",
-            Country::VaticanCity => "Vatican City",
-            Country::WorldMeteorologicalOrganization => "World Meteorological Organization",
+
+    // The entity given by a record's DXCC field, or if that is missing, by
+    // its COUNTRY field.  The MY_DXCC and MY_COUNTRY fields are used when
+    // mine is true.
+    pub fn from_adif_record(record: &crate::adif::AdifRecord, mine: bool) -> Option<Country> {
+        let (dxcc, country) = if mine { ("my_dxcc", "my_country") } else { ("dxcc", "country") };
+        match record.integer(dxcc) {
+            Ok(Some(dxcc)) if dxcc >= 0 => Country::from_dxcc(dxcc as u32),
+            _ => record.adir_field_values.get(country).and_then(|name| Country::from_adif_name(name)),
         }
     }
 }
 
+//
+// Prefixes that pick out an entity within another entity's ITU allocation,
+// where '#' stands for any digit.  The longest matching prefix wins.
+//
+const DXCC_PREFIXES: &[(&str, Country)] = &[
+    ("1A", Country::SovereignMilitaryOrderOfMalta),
+    ("1S", Country::SpratlyIslands),
+    ("3B6", Country::AgalegaAndStBrandon),
+    ("3B7", Country::AgalegaAndStBrandon),
+    ("3B9", Country::RodriguesIsland),
+    ("3C0", Country::AnnobonIsland),
+    ("3DA", Country::Eswatini),
+    ("4U1I", Country::ItuHq),
+    ("4U1U", Country::UnitedNationsHq),
+    ("9M6", Country::EastMalaysia),
+    ("9M8", Country::EastMalaysia),
+    ("9W6", Country::EastMalaysia),
+    ("9W8", Country::EastMalaysia),
+    ("AH0", Country::MarianaIslands),
+    ("AH1", Country::BakerAndHowland),
+    ("AH2", Country::Guam),
+    ("AH3", Country::JohnstonIsland),
+    ("AH4", Country::MidwayIsland),
+    ("AH5", Country::PalmyraAndJarvis),
+    ("AH6", Country::Hawaii),
+    ("AH7", Country::Hawaii),
+    ("AH7K", Country::KureIsland),
+    ("AH8", Country::AmericanSamoa),
+    ("AH8S", Country::SwainsIsland),
+    ("AH9", Country::WakeIsland),
+    ("AL#", Country::Alaska),
+    ("BS7", Country::ScarboroughReef),
+    ("BV9P", Country::PratasIsland),
+    ("CE0X", Country::SanFelixAndSanAmbrosio),
+    ("CE0Y", Country::EasterIsland),
+    ("CE0Z", Country::JuanFernandezIslands),
+    ("CQ3", Country::MadeiraIslands),
+    ("CR3", Country::MadeiraIslands),
+    ("CT3", Country::MadeiraIslands),
+    ("CY0", Country::SableIsland),
+    ("CY9", Country::StPaulIsland),
+    ("EA6", Country::BalearicIslands),
+    ("EA8", Country::CanaryIslands),
+    ("EA9", Country::CeutaAndMelilla),
+    ("EB6", Country::BalearicIslands),
+    ("EB8", Country::CanaryIslands),
+    ("EB9", Country::CeutaAndMelilla),
+    ("EC6", Country::BalearicIslands),
+    ("EC8", Country::CanaryIslands),
+    ("EC9", Country::CeutaAndMelilla),
+    ("ED6", Country::BalearicIslands),
+    ("ED8", Country::CanaryIslands),
+    ("ED9", Country::CeutaAndMelilla),
+    ("EE6", Country::BalearicIslands),
+    ("EE8", Country::CanaryIslands),
+    ("EE9", Country::CeutaAndMelilla),
+    ("EF6", Country::BalearicIslands),
+    ("EF8", Country::CanaryIslands),
+    ("EF9", Country::CeutaAndMelilla),
+    ("EG6", Country::BalearicIslands),
+    ("EG8", Country::CanaryIslands),
+    ("EG9", Country::CeutaAndMelilla),
+    ("EH6", Country::BalearicIslands),
+    ("EH8", Country::CanaryIslands),
+    ("EH9", Country::CeutaAndMelilla),
+    ("FT#E", Country::JuanDeNovaEuropa),
+    ("FT#G", Country::GloriosoIslands),
+    ("FT#J", Country::JuanDeNovaEuropa),
+    ("FT#T", Country::TromelinIsland),
+    ("FT#W", Country::CrozetIsland),
+    ("FT#X", Country::KerguelenIslands),
+    ("FT#Z", Country::AmsterdamAndStPaul),
+    ("H40", Country::TemotuProvince),
+    ("HB0", Country::Liechtenstein),
+    ("HC8", Country::GalapagosIslands),
+    ("HD8", Country::GalapagosIslands),
+    ("HK0", Country::SanAndresAndProvidencia),
+    ("HK0M", Country::MalpeloIsland),
+    ("IM0", Country::Sardinia),
+    ("IS0", Country::Sardinia),
+    ("J45", Country::Dodecanese),
+    ("J49", Country::Crete),
+    ("JD1", Country::Ogasawara),
+    ("KH0", Country::MarianaIslands),
+    ("KH1", Country::BakerAndHowland),
+    ("KH2", Country::Guam),
+    ("KH3", Country::JohnstonIsland),
+    ("KH4", Country::MidwayIsland),
+    ("KH5", Country::PalmyraAndJarvis),
+    ("KH6", Country::Hawaii),
+    ("KH7", Country::Hawaii),
+    ("KH7K", Country::KureIsland),
+    ("KH8", Country::AmericanSamoa),
+    ("KH8S", Country::SwainsIsland),
+    ("KH9", Country::WakeIsland),
+    ("KL#", Country::Alaska),
+    ("KP1", Country::NavassaIsland),
+    ("KP2", Country::VirginIslands),
+    ("KP3", Country::PuertoRico),
+    ("KP4", Country::PuertoRico),
+    ("KP5", Country::DesecheoIsland),
+    ("NH0", Country::MarianaIslands),
+    ("NH1", Country::BakerAndHowland),
+    ("NH2", Country::Guam),
+    ("NH3", Country::JohnstonIsland),
+    ("NH4", Country::MidwayIsland),
+    ("NH5", Country::PalmyraAndJarvis),
+    ("NH6", Country::Hawaii),
+    ("NH7", Country::Hawaii),
+    ("NH7K", Country::KureIsland),
+    ("NH8", Country::AmericanSamoa),
+    ("NH8S", Country::SwainsIsland),
+    ("NH9", Country::WakeIsland),
+    ("NL#", Country::Alaska),
+    ("NP1", Country::NavassaIsland),
+    ("NP2", Country::VirginIslands),
+    ("NP3", Country::PuertoRico),
+    ("NP4", Country::PuertoRico),
+    ("NP5", Country::DesecheoIsland),
+    ("OH0", Country::AlandIslands),
+    ("OJ0", Country::MarketReef),
+    ("PJ2", Country::Curacao),
+    ("PJ4", Country::Bonaire),
+    ("PJ5", Country::SabaAndStEustatius),
+    ("PJ6", Country::SabaAndStEustatius),
+    ("PJ7", Country::SintMaarten),
+    ("PY0F", Country::FernandoDeNoronha),
+    ("PY0S", Country::StPeterAndStPaulRocks),
+    ("PY0T", Country::TrindadeAndMartimVaz),
+    ("R1FJ", Country::FranzJosefLand),
+    ("SV5", Country::Dodecanese),
+    ("SV9", Country::Crete),
+    ("SW5", Country::Dodecanese),
+    ("SW9", Country::Crete),
+    ("SX5", Country::Dodecanese),
+    ("SX9", Country::Crete),
+    ("SY5", Country::Dodecanese),
+    ("SY9", Country::Crete),
+    ("SZ5", Country::Dodecanese),
+    ("SZ9", Country::Crete),
+    ("T30", Country::WestKiribati),
+    ("T31", Country::CentralKiribati),
+    ("T32", Country::EastKiribati),
+    ("T33", Country::BanabaIsland),
+    ("TE9", Country::CocosIsland),
+    ("TI9", Country::CocosIsland),
+    ("VK0H", Country::HeardIsland),
+    ("VK0M", Country::MacquarieIsland),
+    ("VK9C", Country::CocosKeelingIslands),
+    ("VK9L", Country::LordHoweIsland),
+    ("VK9M", Country::MellishReef),
+    ("VK9N", Country::NorfolkIsland),
+    ("VK9W", Country::WillisIsland),
+    ("VK9X", Country::ChristmasIsland),
+    ("VP2E", Country::Anguilla),
+    ("VP2M", Country::Montserrat),
+    ("VP2V", Country::BritishVirginIslands),
+    ("VP5", Country::TurksAndCaicos),
+    ("VP6", Country::PitcairnIsland),
+    ("VP6D", Country::DucieIsland),
+    ("VP8", Country::FalklandIslands),
+    ("VP9", Country::Bermuda),
+    ("VQ5", Country::TurksAndCaicos),
+    ("VQ9", Country::ChagosIslands),
+    ("VU4", Country::AndamanAndNicobar),
+    ("VU7", Country::LakshadweepIslands),
+    ("WH0", Country::MarianaIslands),
+    ("WH1", Country::BakerAndHowland),
+    ("WH2", Country::Guam),
+    ("WH3", Country::JohnstonIsland),
+    ("WH4", Country::MidwayIsland),
+    ("WH5", Country::PalmyraAndJarvis),
+    ("WH6", Country::Hawaii),
+    ("WH7", Country::Hawaii),
+    ("WH7K", Country::KureIsland),
+    ("WH8", Country::AmericanSamoa),
+    ("WH8S", Country::SwainsIsland),
+    ("WH9", Country::WakeIsland),
+    ("WL#", Country::Alaska),
+    ("WP1", Country::NavassaIsland),
+    ("WP2", Country::VirginIslands),
+    ("WP3", Country::PuertoRico),
+    ("WP4", Country::PuertoRico),
+    ("WP5", Country::DesecheoIsland),
+    ("XF4", Country::Revillagigedo),
+    ("YV0", Country::AvesIsland),
+    ("ZC4", Country::UkSovereignBaseAreasOnCyprus),
+    ("ZD7", Country::StHelena),
+    ("ZD8", Country::AscensionIsland),
+    ("ZD9", Country::TristanDaCunhaAndGough),
+    ("ZK3", Country::TokelauIslands),
+    ("ZL7", Country::ChathamIslands),
+    ("ZL8", Country::KermadecIslands),
+    ("ZL9", Country::NewZealandSubantarcticIslands),
+    ("ZS8", Country::PrinceEdwardAndMarion),
+];
+
+fn prefix_matches(prefix: &str, call: &[char]) -> bool {
+    prefix.len() <= call.len() &&
+        prefix.chars().zip(call).all(|(p, c)| p == *c || (p == '#' && c.is_ascii_digit()))
+}
+
+// Russian calls are in Asia when their call area is 8, 9 or 0, and in
+// Kaliningrad when it is 2 followed by F or K.
+fn russia(call: &[char]) -> Country {
+    match call.iter().position(|c| c.is_ascii_digit()).map(|i| &call[i..]) {
+        Some(['8', ..]) | Some(['9', ..]) | Some(['0', ..]) => Country::AsiaticRussia,
+        Some(['2', 'F', ..]) | Some(['2', 'K', ..]) => Country::Kaliningrad,
+        _ => Country::EuropeanRussia,
+    }
+}
+
+// CQ and ITU zones for the parts of the larger countries that lie outside
+// the entity's usual zones: by call area in the United States and Canada,
+// and by oblast (the letter after the digit) in Russia.
+fn regional_zones(country: Country, parts: &CallParts) -> Option<(u8, u8)> {
+    let prefix: Vec<char> = parts.prefix.chars().collect();
+    let area = *prefix.last().filter(|c| c.is_ascii_digit())?;
+    let oblast = if parts.prefix_override.is_none() && parts.base.starts_with(&parts.prefix) {
+        parts.base[parts.prefix.len()..].chars().next()
+    } else {
+        None
+    };
+
+    match country {
+        Country::UnitedStates => match area {
+            '5' | '0' => Some((4, 7)),
+            '6' | '7' => Some((3, 6)),
+            '8' | '9' => Some((4, 8)),
+            _ => None,
+        },
+        Country::Canada => match (&prefix[..], area) {
+            (['V', 'O', '2'], _) => Some((2, 9)),
+            (['V', 'Y', '0'], _) => Some((2, 4)),
+            (['V', 'Y', '1'], _) => Some((1, 2)),
+            (['V', 'Y', '2'], _) | (['V', 'O', '1'], _) => None,
+            (_, '2') => Some((5, 4)),
+            (_, '3') => Some((4, 4)),
+            (_, '4') | (_, '5') => Some((4, 3)),
+            (_, '6') => Some((4, 2)),
+            (_, '7') => Some((3, 2)),
+            (_, '8') => Some((1, 4)),
+            _ => None,
+        },
+        Country::EuropeanRussia | Country::AsiaticRussia => match (area, oblast?) {
+            ('1', 'O') | ('1', 'P') => Some((16, 20)),
+            ('1', 'Z') => Some((16, 19)),
+            ('9', 'H') | ('9', 'O') | ('9', 'U') | ('9', 'Y') | ('9', 'Z') => Some((18, 31)),
+            ('9', 'J') | ('9', 'K') => Some((17, 21)),
+            ('9', 'S') | ('9', 'W') => Some((16, 30)),
+            ('9', 'X') => Some((17, 20)),
+            ('0', 'A') | ('0', 'O') | ('0', 'S') | ('0', 'U') | ('0', 'W') => Some((18, 32)),
+            ('0', 'B') => Some((18, 22)),
+            ('0', 'C') | ('0', 'F') | ('0', 'L') => Some((19, 34)),
+            ('0', 'D') | ('0', 'J') => Some((19, 33)),
+            ('0', 'I') | ('0', 'Q') => Some((19, 24)),
+            ('0', 'K') => Some((19, 26)),
+            ('0', 'X') | ('0', 'Z') => Some((19, 35)),
+            ('0', 'Y') => Some((23, 32)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn zones(call: &Call) -> Result<(u8, u8), &'static str> {
+    let country = call.country()?;
+    let parts = call.parts().map_err(|_| "invalid callsign")?;
+    Ok(regional_zones(country, &parts).unwrap_or((country.cq_zone(), country.itu_zone())))
+}
+
 pub trait CountryInfo {
     fn country(&self) -> Result<Country,&'static str>;
+    fn continent(&self) -> Result<AdifContinent,&'static str>;
+    fn cq_zone(&self) -> Result<u8,&'static str>;
+    fn itu_zone(&self) -> Result<u8,&'static str>;
 }
 
 impl CountryInfo for Call {
     fn country(&self) -> Result<Country,&'static str> {
-        let prefix: Vec<char> = self.call().chars().take(2).collect();
-
-        match prefix.as_slice() {
-            [s, _] if s == &'1' || s == &'0' || s == &'Q' => Err("invalid callsign prefix"),
-            [_, s] if s == &'1' || s == &'0' => Err("invalid callsign prefix"),
-            ['A', s] if s >= &'A' && s <= &'L' => Ok(Country::UnitedStates),
-            ['A', s] if s >= &'M' && s <= &'O' => Ok(Country::Spain),
-            ['A', s] if s >= &'P' && s <= &'S' => Ok(Country::Pakistan),
-            ['A', s] if s >= &'T' && s <= &'W' => Ok(Country::India),
-            ['A', 'X'] => Ok(Country::Australia),
-            ['A', s] if s >= &'Y' && s <= &'Z' => Ok(Country::Argentina),
-            ['A', '2'] => Ok(Country::Botswana),
-            ['A', '3'] => Ok(Country::Tonga),
-            ['A', '4'] => Ok(Country::Oman),
-            ['A', '5'] => Ok(Country::Bhutan),
-            ['A', '6'] => Ok(Country::UnitedArabEmirates),
-            ['A', '7'] => Ok(Country::Qatar),
-            ['A', '8'] => Ok(Country::Liberia),
-            ['A', '9'] => Ok(Country::Bahrain),
-            ['B', s] if s >= &'M' && s <= &'Q' => Ok(Country::Taiwan),
-            ['B', s] if s >= &'U' && s <= &'X' => Ok(Country::Taiwan),
-            ['B', _] => Ok(Country::China),
-            ['C', s] if s >= &'A' && s <= &'E' => Ok(Country::Chile),
-            ['C', s] if s >= &'F' && s <= &'K' => Ok(Country::Canada),
-            ['C', s] if s >= &'L' && s <= &'M' => Ok(Country::Cuba),
-            ['C', 'N'] => Ok(Country::Morocco),
-            ['C', 'O'] => Ok(Country::Cuba),
-            ['C', 'P'] => Ok(Country::Bolivia),
-            ['C', 'U'] => Ok(Country::Azores),
-            ['C', s] if s >= &'Q' && s <= &'U' => Ok(Country::Portugal),
-            ['C', s] if s >= &'V' && s <= &'X' => Ok(Country::Uruguay),
-            ['C', s] if s >= &'Y' && s <= &'Z' => Ok(Country::Canada),
-            ['C', '2'] => Ok(Country::Nauru),
-            ['C', '3'] => Ok(Country::Andorra),
-            ['C', '4'] => Ok(Country::Cyprus),
-            ['C', '5'] => Ok(Country::Gambia),
-            ['C', '6'] => Ok(Country::Bahamas),
-            ['C', '7'] => Ok(Country::WorldMeteorologicalOrganization),
-            ['C', s] if s >= &'8' && s <= &'9' => Ok(Country::Mozambique),
-            ['D', s] if s >= &'A' && s <= &'R' => Ok(Country::Germany),
-            ['D', s] if s >= &'S' && s <= &'T' => Ok(Country::SouthKorea),
-            ['D', s] if s >= &'U' && s <= &'Z' => Ok(Country::Philippines),
-            ['D', s] if s >= &'2' && s <= &'3' => Ok(Country::Angola),
-            ['D', '4'] => Ok(Country::CapeVerde),
-            ['D', '5'] => Ok(Country::Liberia),
-            ['D', '6'] => Ok(Country::Comoros),
-            ['D', s] if s >= &'7' && s <= &'9' => Ok(Country::SouthKorea),
-            ['E',s] if s >= &'A' && s <= &'H' => Ok(Country::Spain),
-            ['E',s] if s >= &'I' && s <= &'J' => Ok(Country::Ireland),
-            ['E','K'] => Ok(Country::Armenia),
-            ['E','L'] => Ok(Country::Liberia),
-            ['E',s] if s >= &'M' && s <= &'O' => Ok(Country::Ukraine),
-            ['E',s] if s >= &'P' && s <= &'Q' => Ok(Country::Iran),
-            ['E','R'] => Ok(Country::Moldova),
-            ['E','S'] => Ok(Country::Estonia),
-            ['E','T'] => Ok(Country::Ethiopia),
-            ['E',s] if s >= &'U' && s <= &'W' => Ok(Country::Belarus),
-            ['E','X'] => Ok(Country::Kyrgyzstan),
-            ['E','Y'] => Ok(Country::Tajikistan),
-            ['E','Z'] => Ok(Country::Turkmenistan),
-            ['E','2'] => Ok(Country::Thailand),
-            ['E','3'] => Ok(Country::Eritrea),
-            ['E','4'] => Ok(Country::PalestinianAuthority),
-            ['E','5'] => Ok(Country::CookIslands),
-            ['E','6'] => Ok(Country::Niue),
-            ['E','7'] => Ok(Country::BosniaAndHerzegovina),
-            ['F', _] => Ok(Country::France),
-            ['G', _] => Ok(Country::UnitedKingdom),
-            ['H', 'A'] => Ok(Country::Hungary),
-            ['H', 'B'] => {
-                Ok(Country::Switzerland)
-            },
-            ['H', s] if s >= &'C' && s <= &'D' => Ok(Country::Ecuador),
-            ['H','E'] => Ok(Country::Switzerland),
-            ['H','F'] => Ok(Country::Poland),
-            ['H','G'] => Ok(Country::Hungary),
-            ['H','H'] => Ok(Country::Haiti),
-            ['H','I'] => Ok(Country::DominicanRepublic),
-            ['H',s] if s >= &'J' && s <= &'K' => Ok(Country::Colombia),
-            ['H','L'] => Ok(Country::SouthKorea),
-            ['H','M'] => Ok(Country::NorthKorea),
-            ['H','N'] => Ok(Country::Iraq),
-            ['H',s] if s >= &'O' && s <= &'P' => Ok(Country::Panama),
-            ['H',s] if s >= &'Q' && s <= &'R' => Ok(Country::Honduras),
-            ['H','S'] => Ok(Country::Thailand),
-            ['H','T'] => Ok(Country::Nicaragua),
-            ['H','U'] => Ok(Country::ElSalvador),
-            ['H','V'] => Ok(Country::VaticanCity),
-            ['H',s] if s >= &'W' && s <= &'Y' => Ok(Country::France),
-            ['H','Z'] => Ok(Country::SaudiArabia),
-            ['H','2'] => Ok(Country::Cyprus),
-            ['H','3'] => Ok(Country::Panama),
-            ['H','4'] => Ok(Country::SolomonIslands),
-            ['H',s] if s >= &'6' && s <= &'7' => Ok(Country::Nicaragua),
-            ['H',s] if s >= &'8' && s <= &'9' => Ok(Country::Panama),
-
-            ['I', _] => Ok(Country::Italy),
-            ['K', _] => Ok(Country::UnitedStates),
-            ['L', s] if s >= &'O' && s <= &'W' => Ok(Country::Argentina),
-            ['L', 'Y'] => Ok(Country::Lithuania),
-            ['M', _] => Ok(Country::UnitedKingdom),
-            ['N', _] => Ok(Country::UnitedStates),
-            ['O', 'E'] => Ok(Country::Austria),
-            ['O', s] if s >= &'K' && s <= &'L' => Ok(Country::Czechia),
-            ['O', 'M'] => Ok(Country::Slovakia),
-            ['P', s] if s >= &'A' && s <= &'I' => Ok(Country::Netherlands),
-            ['P', s] if s >= &'P' && s <= &'Y' => Ok(Country::Brazil),
-            ['R', _] => Ok(Country::Russia),
-            ['S', s] if s >= &'N' && s <= &'R' => Ok(Country::Poland),
-            ['S', '5'] => Ok(Country::Slovenia),
-            ['U', s] if s >= &'R' && s <= &'Z' => Ok(Country::Ukraine),
-            ['V', s] if s >= &'A' && s <= &'G' => Ok(Country::Canada),
-            ['V', s] if s >= &'H' && s <= &'N' => Ok(Country::Australia),
-            ['V', 'O'] => Ok(Country::Canada),
-            ['V', s] if s >= &'P' && s <= &'Q' => Ok(Country::UnitedKingdom),
-            ['V', 'R'] => Ok(Country::HongKong),
-            ['V', 'S'] => Ok(Country::UnitedKingdom),
-            ['V', s] if s >= &'T' && s <= &'W' => Ok(Country::India),
-            ['V', s] if s >= &'X' && s <= &'Y' => Ok(Country::Canada),
-            ['W', _] => Ok(Country::UnitedStates),
-            ['4','Z'] => Ok(Country::Israel),
+        let parts = self.parts().map_err(|_| "invalid callsign")?;
+        if parts.is_maritime_or_aeronautical() {
+            return Err("maritime and aeronautical mobile stations have no country");
+        }
+
+        // Without a prefix override the base call is matched directly, since
+        // some entities are told apart by the letter after the digit (VP2E is
+        // Anguilla).  A one-letter override (F/W1AW) is padded with a
+        // character that only matches the wildcard arms below.
+        let key = if parts.prefix_override.is_some() { &parts.prefix } else { &parts.base };
+        let mut prefix: Vec<char> = key.chars().take(4).collect();
+        if prefix.len() == 1 {
+            prefix.push('/');
+        }
+
+        let special = DXCC_PREFIXES.iter()
+            .filter(|(p, _)| prefix_matches(p, &prefix))
+            .max_by_key(|(p, _)| p.len());
+        if let Some((_, country)) = special {
+            return Ok(*country);
+        }
+
+        let country = match &prefix[..2] {
+            ['0', _] | ['1', _] | ['Q', _] => return Err("invalid callsign prefix"),
+            ['R', _] | ['U', 'A'..='I'] => russia(&prefix),
+            ['A', 'A'..='L'] => Country::UnitedStates,
+            ['A', 'M'..='O'] => Country::Spain,
+            ['A', 'P'..='S'] => Country::Pakistan,
+            ['A', 'T'..='W'] => Country::India,
+            ['A', 'X'] => Country::Australia,
+            ['A', 'Y'..='Z'] => Country::Argentina,
+            ['A', '2'] => Country::Botswana,
+            ['A', '3'] => Country::Tonga,
+            ['A', '4'] => Country::Oman,
+            ['A', '5'] => Country::Bhutan,
+            ['A', '6'] => Country::UnitedArabEmirates,
+            ['A', '7'] => Country::Qatar,
+            ['A', '8'] => Country::Liberia,
+            ['A', '9'] => Country::Bahrain,
+            ['B', 'M'..='Q'] => Country::Taiwan,
+            ['B', 'U'..='X'] => Country::Taiwan,
+            ['B', _] => Country::China,
+            ['C', 'A'..='E'] => Country::Chile,
+            ['C', 'F'..='K'] => Country::Canada,
+            ['C', 'L'..='M'] => Country::Cuba,
+            ['C', 'N'] => Country::Morocco,
+            ['C', 'O'] => Country::Cuba,
+            ['C', 'P'] => Country::Bolivia,
+            ['C', 'U'] => Country::Azores,
+            ['C', 'Q'..='T'] => Country::Portugal,
+            ['C', 'V'..='X'] => Country::Uruguay,
+            ['C', 'Y'..='Z'] => Country::Canada,
+            ['C', '2'] => Country::Nauru,
+            ['C', '3'] => Country::Andorra,
+            ['C', '4'] => Country::Cyprus,
+            ['C', '5'] => Country::Gambia,
+            ['C', '6'] => Country::Bahamas,
+            ['C', '8'..='9'] => Country::Mozambique,
+            ['D', 'A'..='R'] => Country::Germany,
+            ['D', 'S'..='T'] => Country::SouthKorea,
+            ['D', 'U'..='Z'] => Country::Philippines,
+            ['D', '2'..='3'] => Country::Angola,
+            ['D', '4'] => Country::CapeVerde,
+            ['D', '5'] => Country::Liberia,
+            ['D', '6'] => Country::Comoros,
+            ['D', '7'..='9'] => Country::SouthKorea,
+            ['E', 'A'..='H'] => Country::Spain,
+            ['E', 'I'..='J'] => Country::Ireland,
+            ['E', 'K'] => Country::Armenia,
+            ['E', 'L'] => Country::Liberia,
+            ['E', 'M'..='O'] => Country::Ukraine,
+            ['E', 'P'..='Q'] => Country::Iran,
+            ['E', 'R'] => Country::Moldova,
+            ['E', 'S'] => Country::Estonia,
+            ['E', 'T'] => Country::Ethiopia,
+            ['E', 'U'..='W'] => Country::Belarus,
+            ['E', 'X'] => Country::Kyrgyzstan,
+            ['E', 'Y'] => Country::Tajikistan,
+            ['E', 'Z'] => Country::Turkmenistan,
+            ['E', '2'] => Country::Thailand,
+            ['E', '3'] => Country::Eritrea,
+            ['E', '4'] => Country::PalestinianAuthority,
+            ['E', '5'] => Country::SouthCookIslands,
+            ['E', '6'] => Country::Niue,
+            ['E', '7'] => Country::BosniaAndHerzegovina,
+            ['F', 'G'] => Country::Guadeloupe,
+            ['F', 'H'] => Country::Mayotte,
+            ['F', 'J'] => Country::SaintBarthelemy,
+            ['F', 'K'] => Country::NewCaledonia,
+            ['F', 'M'] => Country::Martinique,
+            ['F', 'O'] => Country::FrenchPolynesia,
+            ['F', 'P'] => Country::StPierreAndMiquelon,
+            ['F', 'R'] => Country::ReunionIsland,
+            ['F', 'S'] => Country::SaintMartin,
+            ['F', 'W'] => Country::WallisAndFutuna,
+            ['F', 'Y'] => Country::FrenchGuiana,
+            ['F', _] => Country::France,
+            ['G', 'D'] | ['G', 'T'] | ['M', 'D'] | ['M', 'T'] | ['2', 'D'] | ['2', 'T'] => Country::IsleOfMan,
+            ['G', 'I'] | ['G', 'N'] | ['M', 'I'] | ['M', 'N'] | ['2', 'I'] | ['2', 'N'] => Country::NorthernIreland,
+            ['G', 'J'] | ['G', 'H'] | ['M', 'J'] | ['M', 'H'] | ['2', 'J'] | ['2', 'H'] => Country::Jersey,
+            ['G', 'M'] | ['G', 'S'] | ['M', 'M'] | ['M', 'S'] | ['2', 'M'] | ['2', 'S'] => Country::Scotland,
+            ['G', 'U'] | ['G', 'P'] | ['M', 'U'] | ['M', 'P'] | ['2', 'U'] | ['2', 'P'] => Country::Guernsey,
+            ['G', 'W'] | ['G', 'C'] | ['M', 'W'] | ['M', 'C'] | ['2', 'W'] | ['2', 'C'] => Country::Wales,
+            ['G', _] | ['M', _] | ['2', _] => Country::England,
+            ['H', 'A'] => Country::Hungary,
+            ['H', 'B'] => Country::Switzerland,
+            ['H', 'C'..='D'] => Country::Ecuador,
+            ['H', 'E'] => Country::Switzerland,
+            ['H', 'F'] => Country::Poland,
+            ['H', 'G'] => Country::Hungary,
+            ['H', 'H'] => Country::Haiti,
+            ['H', 'I'] => Country::DominicanRepublic,
+            ['H', 'J'..='K'] => Country::Colombia,
+            ['H', 'L'] => Country::SouthKorea,
+            ['H', 'M'] => Country::NorthKorea,
+            ['H', 'N'] => Country::Iraq,
+            ['H', 'O'..='P'] => Country::Panama,
+            ['H', 'Q'..='R'] => Country::Honduras,
+            ['H', 'S'] => Country::Thailand,
+            ['H', 'T'] => Country::Nicaragua,
+            ['H', 'U'] => Country::ElSalvador,
+            ['H', 'V'] => Country::VaticanCity,
+            ['H', 'W'..='Y'] => Country::France,
+            ['H', 'Z'] => Country::SaudiArabia,
+            ['H', '2'] => Country::Cyprus,
+            ['H', '3'] => Country::Panama,
+            ['H', '4'] => Country::SolomonIslands,
+            ['H', '6'..='7'] => Country::Nicaragua,
+            ['H', '8'..='9'] => Country::Panama,
+            ['I', _] => Country::Italy,
+            ['J', 'A'..='S'] => Country::Japan,
+            ['J', 'T'..='V'] => Country::Mongolia,
+            ['J', 'W'] => Country::Svalbard,
+            ['J', 'X'] => Country::JanMayen,
+            ['J', 'Y'] => Country::Jordan,
+            ['J', 'Z'] => Country::Indonesia,
+            ['J', '2'] => Country::Djibouti,
+            ['J', '3'] => Country::Grenada,
+            ['J', '4'] => Country::Greece,
+            ['J', '5'] => Country::GuineaBissau,
+            ['J', '6'] => Country::StLucia,
+            ['J', '7'] => Country::Dominica,
+            ['J', '8'] => Country::StVincent,
+            ['K', _] | ['N', _] | ['W', _] => Country::UnitedStates,
+            ['L', 'A'..='N'] => Country::Norway,
+            ['L', 'O'..='W'] => Country::Argentina,
+            ['L', 'X'] => Country::Luxembourg,
+            ['L', 'Y'] => Country::Lithuania,
+            ['L', 'Z'] => Country::Bulgaria,
+            ['L', '2'..='9'] => Country::Argentina,
+            ['O', 'A'..='C'] => Country::Peru,
+            ['O', 'D'] => Country::Lebanon,
+            ['O', 'E'] => Country::Austria,
+            ['O', 'F'..='J'] => Country::Finland,
+            ['O', 'K'..='L'] => Country::Czechia,
+            ['O', 'M'] => Country::Slovakia,
+            ['O', 'N'..='T'] => Country::Belgium,
+            ['O', 'X'] => Country::Greenland,
+            ['O', 'Y'] => Country::FaroeIslands,
+            ['O', 'U'..='Z'] => Country::Denmark,
+            ['P', 'A'..='I'] => Country::Netherlands,
+            ['P', 'J'] => Country::Curacao,
+            ['P', 'K'..='O'] => Country::Indonesia,
+            ['P', 'P'..='Y'] => Country::Brazil,
+            ['P', 'Z'] => Country::Suriname,
+            ['P', '2'] => Country::PapuaNewGuinea,
+            ['P', '3'] => Country::Cyprus,
+            ['P', '4'] => Country::Aruba,
+            ['P', '5'] => Country::NorthKorea,
+            ['S', 'A'..='M'] => Country::Sweden,
+            ['S', 'N'..='R'] => Country::Poland,
+            ['S', 'S'] => Country::Egypt,
+            ['S', 'T'] => Country::Sudan,
+            ['S', 'U'] => Country::Egypt,
+            ['S', 'V'..='Z'] => Country::Greece,
+            ['S', '0'] => Country::WesternSahara,
+            ['S', '2'..='3'] => Country::Bangladesh,
+            ['S', '5'] => Country::Slovenia,
+            ['S', '6'] => Country::Singapore,
+            ['S', '7'] => Country::Seychelles,
+            ['S', '8'] => Country::SouthAfrica,
+            ['S', '9'] => Country::SaoTomeAndPrincipe,
+            ['T', 'A'..='C'] => Country::Turkey,
+            ['T', 'D'] => Country::Guatemala,
+            ['T', 'E'] => Country::CostaRica,
+            ['T', 'F'] => Country::Iceland,
+            ['T', 'G'] => Country::Guatemala,
+            ['T', 'H'] => Country::France,
+            ['T', 'I'] => Country::CostaRica,
+            ['T', 'J'] => Country::Cameroon,
+            ['T', 'K'] => Country::Corsica,
+            ['T', 'L'] => Country::CentralAfrica,
+            ['T', 'M'] => Country::France,
+            ['T', 'N'] => Country::RepublicOfTheCongo,
+            ['T', 'O'..='Q'] => Country::France,
+            ['T', 'R'] => Country::Gabon,
+            ['T', 'S'] => Country::Tunisia,
+            ['T', 'T'] => Country::Chad,
+            ['T', 'U'] => Country::CoteDIvoire,
+            ['T', 'V'..='X'] => Country::France,
+            ['T', 'Y'] => Country::Benin,
+            ['T', 'Z'] => Country::Mali,
+            ['T', '2'] => Country::Tuvalu,
+            ['T', '3'] => Country::WestKiribati,
+            ['T', '4'] => Country::Cuba,
+            ['T', '5'] => Country::Somalia,
+            ['T', '6'] => Country::Afghanistan,
+            ['T', '7'] => Country::SanMarino,
+            ['T', '8'] => Country::Palau,
+            ['T', '9'] => Country::BosniaAndHerzegovina,
+            ['U', 'J'..='M'] => Country::Uzbekistan,
+            ['U', 'N'..='Q'] => Country::Kazakhstan,
+            ['U', 'R'..='Z'] => Country::Ukraine,
+            ['V', 'A'..='G'] => Country::Canada,
+            ['V', 'H'..='N'] => Country::Australia,
+            ['V', 'O'] => Country::Canada,
+            ['V', 'R'] => Country::HongKong,
+            ['V', 'T'..='W'] => Country::India,
+            ['V', 'X'..='Y'] => Country::Canada,
+            ['V', '2'] => Country::AntiguaAndBarbuda,
+            ['V', '3'] => Country::Belize,
+            ['V', '4'] => Country::StKittsAndNevis,
+            ['V', '5'] => Country::Namibia,
+            ['V', '6'] => Country::Micronesia,
+            ['V', '7'] => Country::MarshallIslands,
+            ['V', '8'] => Country::Brunei,
+            ['X', 'A'..='I'] => Country::Mexico,
+            ['X', 'J'..='O'] => Country::Canada,
+            ['X', 'P'] => Country::Greenland,
+            ['X', 'Q'..='R'] => Country::Chile,
+            ['X', 'S'] => Country::China,
+            ['X', 'T'] => Country::BurkinaFaso,
+            ['X', 'U'] => Country::Cambodia,
+            ['X', 'V'] => Country::Vietnam,
+            ['X', 'W'] => Country::Laos,
+            ['X', 'X'] => Country::Macao,
+            ['X', 'Y'..='Z'] => Country::Myanmar,
+            ['Y', 'A'] => Country::Afghanistan,
+            ['Y', 'B'..='H'] => Country::Indonesia,
+            ['Y', 'I'] => Country::Iraq,
+            ['Y', 'J'] => Country::Vanuatu,
+            ['Y', 'K'] => Country::Syria,
+            ['Y', 'L'] => Country::Latvia,
+            ['Y', 'M'] => Country::Turkey,
+            ['Y', 'N'] => Country::Nicaragua,
+            ['Y', 'O'..='R'] => Country::Romania,
+            ['Y', 'S'] => Country::ElSalvador,
+            ['Y', 'T'..='U'] => Country::Serbia,
+            ['Y', 'V'..='Y'] => Country::Venezuela,
+            ['Y', 'Z'] => Country::Serbia,
+            ['Y', '2'..='9'] => Country::Germany,
+            ['Z', 'A'] => Country::Albania,
+            ['Z', 'B'] => Country::Gibraltar,
+            ['Z', 'E'] => Country::Zimbabwe,
+            ['Z', 'F'] => Country::CaymanIslands,
+            ['Z', 'K'..='M'] => Country::NewZealand,
+            ['Z', 'P'] => Country::Paraguay,
+            ['Z', 'R'..='U'] => Country::SouthAfrica,
+            ['Z', 'V'..='Z'] => Country::Brazil,
+            ['Z', '2'] => Country::Zimbabwe,
+            ['Z', '3'] => Country::NorthMacedonia,
+            ['Z', '6'] => Country::Kosovo,
+            ['Z', '8'] => Country::SouthSudan,
+            ['3', 'A'] => Country::Monaco,
+            ['3', 'B'] => Country::Mauritius,
+            ['3', 'C'] => Country::EquatorialGuinea,
+            ['3', 'D'] => Country::Fiji,
+            ['3', 'E'..='F'] => Country::Panama,
+            ['3', 'G'] => Country::Chile,
+            ['3', 'H'..='U'] => Country::China,
+            ['3', 'V'] => Country::Tunisia,
+            ['3', 'W'] => Country::Vietnam,
+            ['3', 'X'] => Country::Guinea,
+            ['3', 'Y'] => Country::Bouvet,
+            ['3', 'Z'] => Country::Poland,
+            ['4', 'A'..='C'] => Country::Mexico,
+            ['4', 'D'..='I'] => Country::Philippines,
+            ['4', 'J'..='K'] => Country::Azerbaijan,
+            ['4', 'L'] => Country::Georgia,
+            ['4', 'M'] => Country::Venezuela,
+            ['4', 'O'] => Country::Montenegro,
+            ['4', 'P'..='S'] => Country::SriLanka,
+            ['4', 'T'] => Country::Peru,
+            ['4', 'V'] => Country::Haiti,
+            ['4', 'W'] => Country::TimorLeste,
+            ['4', 'X'..='Z'] => Country::Israel,
+            ['5', 'A'] => Country::Libya,
+            ['5', 'B'] => Country::Cyprus,
+            ['5', 'C'..='G'] => Country::Morocco,
+            ['5', 'H'..='I'] => Country::Tanzania,
+            ['5', 'J'..='K'] => Country::Colombia,
+            ['5', 'L'..='M'] => Country::Liberia,
+            ['5', 'N'..='O'] => Country::Nigeria,
+            ['5', 'P'..='Q'] => Country::Denmark,
+            ['5', 'R'..='S'] => Country::Madagascar,
+            ['5', 'T'] => Country::Mauritania,
+            ['5', 'U'] => Country::Niger,
+            ['5', 'V'] => Country::Togo,
+            ['5', 'W'] => Country::Samoa,
+            ['5', 'X'] => Country::Uganda,
+            ['5', 'Y'..='Z'] => Country::Kenya,
+            ['6', 'A'..='B'] => Country::Egypt,
+            ['6', 'C'] => Country::Syria,
+            ['6', 'D'..='J'] => Country::Mexico,
+            ['6', 'K'..='N'] => Country::SouthKorea,
+            ['6', 'O'] => Country::Somalia,
+            ['6', 'P'..='S'] => Country::Pakistan,
+            ['6', 'T'..='U'] => Country::Sudan,
+            ['6', 'V'..='W'] => Country::Senegal,
+            ['6', 'X'] => Country::Madagascar,
+            ['6', 'Y'] => Country::Jamaica,
+            ['6', 'Z'] => Country::Liberia,
+            ['7', 'A'..='I'] => Country::Indonesia,
+            ['7', 'J'..='N'] => Country::Japan,
+            ['7', 'O'] => Country::Yemen,
+            ['7', 'P'] => Country::Lesotho,
+            ['7', 'Q'] => Country::Malawi,
+            ['7', 'R'] => Country::Algeria,
+            ['7', 'S'] => Country::Sweden,
+            ['7', 'T'..='Y'] => Country::Algeria,
+            ['7', 'Z'] => Country::SaudiArabia,
+            ['8', 'A'..='I'] => Country::Indonesia,
+            ['8', 'J'..='N'] => Country::Japan,
+            ['8', 'O'] => Country::Botswana,
+            ['8', 'P'] => Country::Barbados,
+            ['8', 'Q'] => Country::Maldives,
+            ['8', 'R'] => Country::Guyana,
+            ['8', 'S'] => Country::Sweden,
+            ['8', 'T'..='Y'] => Country::India,
+            ['8', 'Z'] => Country::SaudiArabia,
+            ['9', 'A'] => Country::Croatia,
+            ['9', 'B'..='D'] => Country::Iran,
+            ['9', 'E'..='F'] => Country::Ethiopia,
+            ['9', 'G'] => Country::Ghana,
+            ['9', 'H'] => Country::Malta,
+            ['9', 'I'..='J'] => Country::Zambia,
+            ['9', 'K'] => Country::Kuwait,
+            ['9', 'L'] => Country::SierraLeone,
+            ['9', 'M'] => Country::WestMalaysia,
+            ['9', 'N'] => Country::Nepal,
+            ['9', 'O'..='T'] => Country::DemocraticRepublicOfTheCongo,
+            ['9', 'U'] => Country::Burundi,
+            ['9', 'V'] => Country::Singapore,
+            ['9', 'W'] => Country::WestMalaysia,
+            ['9', 'X'] => Country::Rwanda,
+            ['9', 'Y'..='Z'] => Country::TrinidadAndTobago,
             _ => {
-                Err("unknown country prefix")
+                return Err("unknown country prefix")
             }
+        };
+
+        // Mount Athos stations sign /A after a Greek call.
+        if country == Country::Greece && parts.suffixes.contains(&CallSuffix::Other("A".to_string())) {
+            return Ok(Country::MountAthos);
         }
+        Ok(country)
+    }
+
+    fn continent(&self) -> Result<AdifContinent,&'static str> {
+        self.country().map(|country| country.continent())
+    }
+
+    fn cq_zone(&self) -> Result<u8,&'static str> {
+        zones(self).map(|(cq, _)| cq)
+    }
+
+    fn itu_zone(&self) -> Result<u8,&'static str> {
+        zones(self).map(|(_, itu)| itu)
     }
 }
 
@@ -405,6 +1159,30 @@ impl CountryInfo for Call {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_zones() {
+        let zones = |call: &str| {
+            let call = Call::new(call);
+            (call.continent().unwrap(), call.cq_zone().unwrap(), call.itu_zone().unwrap())
+        };
+        assert_eq!(zones("W1AW"), (AdifContinent::NA, 5, 8));
+        assert_eq!(zones("K6ABC"), (AdifContinent::NA, 3, 6));
+        assert_eq!(zones("W0XYZ"), (AdifContinent::NA, 4, 7));
+        assert_eq!(zones("W1AW/7"), (AdifContinent::NA, 3, 6));
+        assert_eq!(zones("KH6ABC"), (AdifContinent::OC, 31, 61));
+        assert_eq!(zones("VE1ABC"), (AdifContinent::NA, 5, 9));
+        assert_eq!(zones("VE3ABC"), (AdifContinent::NA, 4, 4));
+        assert_eq!(zones("VA7XY"), (AdifContinent::NA, 3, 2));
+        assert_eq!(zones("VY0ABC"), (AdifContinent::NA, 2, 4));
+        assert_eq!(zones("UA3ABC"), (AdifContinent::EU, 16, 29));
+        assert_eq!(zones("RZ1ZZ"), (AdifContinent::EU, 16, 19));
+        assert_eq!(zones("UA9AA"), (AdifContinent::AS, 17, 30));
+        assert_eq!(zones("RA9OA"), (AdifContinent::AS, 18, 31));
+        assert_eq!(zones("UA0XYZ"), (AdifContinent::AS, 19, 35));
+        assert_eq!(zones("JA1XYZ"), (AdifContinent::AS, 25, 45));
+        assert!(Call::new("W1AW/MM").cq_zone().is_err());
+    }
+
     #[test]
     fn test_name() {
         assert_eq!(Country::Canada.name(),"Canada");
@@ -414,11 +1192,88 @@ mod tests {
         assert_eq!(Country::UnitedStates.code(),"us");
     }
 
+    #[test]
+    fn test_dxcc() {
+        assert_eq!(Country::UnitedStates.dxcc(), 291);
+        assert_eq!(Country::from_dxcc(339), Some(Country::Japan));
+        assert_eq!(Country::from_dxcc(0), None);
+        assert_eq!(Country::from_dxcc(73), None);
+        assert_eq!(Country::from_adif_name("FEDERAL REPUBLIC OF GERMANY"), Some(Country::Germany));
+        assert_eq!(Country::from_adif_name("new zealand"), Some(Country::NewZealand));
+        assert_eq!(Country::from_adif_name("Narnia"), None);
+
+        assert_eq!(Country::ALL.iter().filter(|c| !c.is_deleted()).count(), 340);
+        assert!(Country::FormerGermany.is_deleted());
+        for country in Country::ALL {
+            assert_eq!(Country::from_dxcc(country.dxcc()), Some(*country));
+            assert!((1..=40).contains(&country.cq_zone()), "{:?}", country);
+            assert!((1..=90).contains(&country.itu_zone()), "{:?}", country);
+        }
+
+        assert_eq!(Country::Japan.continent(), AdifContinent::AS);
+        assert_eq!(Country::Hawaii.cq_zone(), 31);
+        assert_eq!(Country::NewZealand.itu_zone(), 60);
+    }
+
+    #[test]
+    fn test_from_adif_record() {
+        let mut record = crate::adif::AdifRecord::new();
+        record.adir_field_values.insert("country".to_string(), "JAPAN".to_string());
+        assert_eq!(Country::from_adif_record(&record, false), Some(Country::Japan));
+        record.adir_field_values.insert("dxcc".to_string(), "170".to_string());
+        assert_eq!(Country::from_adif_record(&record, false), Some(Country::NewZealand));
+        assert_eq!(Country::from_adif_record(&record, true), None);
+    }
+
     // CountryInfo trait
     #[test]
     fn test_callsign_country() {
         let call = Call::new("KK4WJS");
         assert_eq!(call.country().unwrap(), Country::UnitedStates);
+
+        let country = |call: &str| Call::new(call).country();
+        assert_eq!(country("W1AW"), Ok(Country::UnitedStates));
+        assert_eq!(country("JA1XYZ"), Ok(Country::Japan));
+        assert_eq!(country("1A0KM"), Ok(Country::SovereignMilitaryOrderOfMalta));
+        assert_eq!(country("1S1A"), Ok(Country::SpratlyIslands));
+        assert_eq!(country("ZL2ABC"), Ok(Country::NewZealand));
+        assert_eq!(country("XE1ABC"), Ok(Country::Mexico));
+        assert_eq!(country("ZS6ABC"), Ok(Country::SouthAfrica));
+        assert_eq!(country("SM5ABC"), Ok(Country::Sweden));
+        assert_eq!(country("LA9ABC"), Ok(Country::Norway));
+        assert_eq!(country("KH6ABC"), Ok(Country::Hawaii));
+        assert_eq!(country("NL7AB"), Ok(Country::Alaska));
+        assert_eq!(country("KL0AB"), Ok(Country::Alaska));
+        assert_eq!(country("KP4ABC"), Ok(Country::PuertoRico));
+        assert_eq!(country("KH7KA"), Ok(Country::KureIsland));
+        assert_eq!(country("G4ABC"), Ok(Country::England));
+        assert_eq!(country("MM0ABC"), Ok(Country::Scotland));
+        assert_eq!(country("2W0ABC"), Ok(Country::Wales));
+        assert_eq!(country("UA9ABC"), Ok(Country::AsiaticRussia));
+        assert_eq!(country("RA3ABC"), Ok(Country::EuropeanRussia));
+        assert_eq!(country("UA2FAB"), Ok(Country::Kaliningrad));
+        assert_eq!(country("FT5XO"), Ok(Country::KerguelenIslands));
+        assert_eq!(country("SV2ASP/A"), Ok(Country::MountAthos));
+        assert_eq!(country("4U1ITU"), Ok(Country::ItuHq));
+        assert_eq!(country("3DA0XYZ"), Ok(Country::Eswatini));
+        assert_eq!(country("S01WS"), Ok(Country::WesternSahara));
+        assert!(country("Q1ABC").is_err());
     }
 
+    #[test]
+    fn test_compound_callsign_country() {
+        assert_eq!(Call::new("VE3/W1AW").country(), Ok(Country::Canada));
+        assert_eq!(Call::new("W1AW/VE3").country(), Ok(Country::Canada));
+        assert_eq!(Call::new("VP2E/W1AW").country(), Ok(Country::Anguilla));
+        assert_eq!(Call::new("VP2EAB").country(), Ok(Country::Anguilla));
+        assert_eq!(Call::new("VP9AA").country(), Ok(Country::Bermuda));
+        assert_eq!(Call::new("F/W1AW").country(), Ok(Country::France));
+        assert_eq!(Call::new("M/W1AW").country(), Ok(Country::England));
+        assert_eq!(Call::new("MM/W1AW").country(), Ok(Country::Scotland));
+        assert_eq!(Call::new("AM/W1AW").country(), Ok(Country::Spain));
+        assert_eq!(Call::new("G4ABC/P").country(), Ok(Country::England));
+        assert_eq!(Call::new("KH6/K1ABC/QRP").country(), Ok(Country::Hawaii));
+        assert!(Call::new("W1AW/MM").country().is_err());
+        assert!(Call::new("W1-AW").country().is_err());
+    }
 }
@@ -0,0 +1,516 @@
+//
+// src/cty.rs: AD1C country files (cty.dat and cty.csv)
+//
+// Contest loggers resolve callsigns with the country files published at
+// country-files.com rather than with a fixed prefix table, since those files
+// track prefix reallocations, individual calls that don't follow their
+// prefix ("=" entries) and calls in unusual CQ or ITU zones.  This module
+// loads either format from a local file and indexes it for lookups, falling
+// back to the built-in table in crate::countries for calls it doesn't cover.
+//
+// cty.dat gives longitudes as positive west and time offsets as hours behind
+// UTC; both are converted here to the usual positive east convention.
+//
+
+use crate::{Call,Country,CountryInfo};
+use crate::adif::AdifContinent;
+use crate::call::CallSuffix;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CtyEntity {
+    pub name: String,
+    pub primary_prefix: String,
+    pub dxcc: Option<u32>,
+    pub continent: AdifContinent,
+    pub cq_zone: u8,
+    pub itu_zone: u8,
+    // (latitude, longitude) in degrees, north and east positive.
+    pub coord: Option<(f64, f64)>,
+    // Hours ahead of UTC.
+    pub utc_offset: Option<f64>,
+    // Entities marked with '*' count for CQ's WAE list but not for DXCC
+    // (e.g., Sicily).
+    pub wae_only: bool,
+}
+
+impl CtyEntity {
+    pub fn country(&self) -> Option<Country> {
+        self.dxcc.and_then(Country::from_dxcc)
+    }
+}
+
+impl From<Country> for CtyEntity {
+    fn from(country: Country) -> CtyEntity {
+        CtyEntity {
+            name: country.name().to_string(),
+            primary_prefix: String::new(),
+            dxcc: Some(country.dxcc()),
+            continent: country.continent(),
+            cq_zone: country.cq_zone(),
+            itu_zone: country.itu_zone(),
+            coord: None,
+            utc_offset: None,
+            wae_only: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CtyError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for CtyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CtyError::Io(e) => write!(f, "{}", e),
+            CtyError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for CtyError {}
+
+impl From<std::io::Error> for CtyError {
+    fn from(e: std::io::Error) -> CtyError {
+        CtyError::Io(e)
+    }
+}
+
+// A prefix or exact call, with the values it overrides for its entity.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CtyAlias {
+    entity: usize,
+    cq_zone: Option<u8>,
+    itu_zone: Option<u8>,
+    continent: Option<AdifContinent>,
+    coord: Option<(f64, f64)>,
+    utc_offset: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CtyDat {
+    entities: Vec<CtyEntity>,
+    prefixes: HashMap<String, CtyAlias>,
+    calls: HashMap<String, CtyAlias>,
+}
+
+impl CtyDat {
+    // Loads cty.csv if the file name ends in ".csv", and cty.dat otherwise.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<CtyDat, CtyError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => CtyDat::parse_csv(&text),
+            _ => CtyDat::parse_dat(&text),
+        }
+    }
+
+    // Each entity is a header line of eight fields, each followed by ':'
+    // (name, CQ zone, ITU zone, continent, latitude, longitude, time offset
+    // and primary prefix), then a comma-separated list of aliases that ends
+    // with ';' and may span several lines.
+    pub fn parse_dat(text: &str) -> Result<CtyDat, CtyError> {
+        let mut cty = CtyDat::default();
+        let names = country_names();
+        for record in text.split(';') {
+            if record.trim().is_empty() {
+                continue;
+            }
+
+            let at = start_line(text, record);
+            let fields: Vec<&str> = record.splitn(9, ':').collect();
+            if fields.len() < 9 {
+                return Err(parse_error(at, "expected eight fields ending with ':'"));
+            }
+            let fields: Vec<&str> = fields.iter().map(|f| f.trim()).collect();
+            let (lat, lon) = (parse_number(fields[4], at)?, parse_number(fields[5], at)?);
+            let dxcc = dat_country(&names, fields[0], fields[7]).map(|country| country.dxcc());
+            let entity = new_entity(fields[0], fields[7], dxcc, fields[3], fields[1], fields[2],
+                (lat, -lon), -parse_number(fields[6], at)?, at)?;
+            let aliases: Vec<&str> = fields[8].split(',').map(|a| a.trim()).collect();
+            cty.add(entity, &aliases, at)?;
+        }
+        Ok(cty)
+    }
+
+    // Each line is: primary prefix, name, DXCC entity code, continent, CQ
+    // zone, ITU zone, latitude, longitude, time offset, and a space-separated
+    // list of aliases ending with ';'.
+    pub fn parse_csv(text: &str) -> Result<CtyDat, CtyError> {
+        let mut cty = CtyDat::default();
+        for (i, line) in text.lines().enumerate() {
+            let at = i + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.rsplitn(9, ',').map(|f| f.trim()).collect();
+            let head: Vec<&str> = match fields.get(8) {
+                Some(head) => head.splitn(2, ',').map(|f| f.trim()).collect(),
+                None => return Err(parse_error(at, "expected ten comma-separated fields")),
+            };
+            if head.len() < 2 {
+                return Err(parse_error(at, "expected ten comma-separated fields"));
+            }
+
+            let dxcc = fields[7].parse::<u32>()
+                .map_err(|_| parse_error(at, &format!("invalid DXCC entity code \"{}\"", fields[7])))?;
+            let (lat, lon) = (parse_number(fields[3], at)?, parse_number(fields[2], at)?);
+            let entity = new_entity(head[1], head[0], Some(dxcc), fields[6], fields[5], fields[4],
+                (lat, -lon), -parse_number(fields[1], at)?, at)?;
+            let aliases: Vec<&str> = fields[0].trim_end_matches(';').split_whitespace().collect();
+            cty.add(entity, &aliases, at)?;
+        }
+        Ok(cty)
+    }
+
+    pub fn entities(&self) -> &[CtyEntity] {
+        &self.entities
+    }
+
+    // The entity for a call, with any zone, continent, location or time
+    // offset overrides for the matching alias applied.  Exact calls are
+    // checked first and then the longest matching prefix; entities that only
+    // count for WAE are skipped.  Maritime and aeronautical mobile stations
+    // have no entity.
+    pub fn lookup(&self, call: &Call) -> Option<CtyEntity> {
+        let (exact, key) = lookup_keys(call)?;
+        if let Some(alias) = exact.iter().find_map(|call| self.calls.get(call)) {
+            return Some(self.resolve_alias(alias));
+        }
+
+        (1..=key.len()).rev()
+            .filter_map(|len| self.prefixes.get(&key[..len]))
+            .find(|alias| !self.entities[alias.entity].wae_only)
+            .map(|alias| self.resolve_alias(alias))
+    }
+
+    fn resolve_alias(&self, alias: &CtyAlias) -> CtyEntity {
+        let mut entity = self.entities[alias.entity].clone();
+        entity.cq_zone = alias.cq_zone.unwrap_or(entity.cq_zone);
+        entity.itu_zone = alias.itu_zone.unwrap_or(entity.itu_zone);
+        entity.continent = alias.continent.unwrap_or(entity.continent);
+        entity.coord = alias.coord.or(entity.coord);
+        entity.utc_offset = alias.utc_offset.or(entity.utc_offset);
+        entity
+    }
+
+    fn add(&mut self, entity: CtyEntity, aliases: &[&str], at: usize) -> Result<(), CtyError> {
+        let index = self.entities.len();
+        self.entities.push(entity);
+        for alias in aliases.iter().filter(|a| !a.is_empty()) {
+            let (name, exact, alias) = parse_alias(alias, index, at)?;
+            let map = if exact { &mut self.calls } else { &mut self.prefixes };
+            map.entry(name).or_insert(alias);
+        }
+        Ok(())
+    }
+}
+
+// The entity for a call from a loaded country file if there is one, and
+// otherwise, or if the file has no match, from the built-in prefix table.
+pub fn resolve(cty: Option<&CtyDat>, call: &Call) -> Option<CtyEntity> {
+    cty.and_then(|cty| cty.lookup(call))
+        .or_else(|| call.country().ok().map(CtyEntity::from))
+}
+
+// The calls to check for exact-call entries, and the string to match against
+// prefixes.  A portable call's own prefix is ignored in favour of the
+// location it's operating from, so "KH6/W1AW" matches prefixes with "KH6"
+// and "W1AW/4" with "W4".  Maritime and aeronautical mobile stations have
+// no entity.
+pub(crate) fn lookup_keys(call: &Call) -> Option<(Vec<String>, String)> {
+    let parts = call.parts().ok()?;
+    if parts.is_maritime_or_aeronautical() {
+        return None;
+    }
+
+    let has_area = parts.suffixes.iter().any(|s| matches!(s, CallSuffix::CallArea(_)));
+    if parts.prefix_override.is_some() || has_area {
+        Some((vec![call.call()], parts.prefix))
+    } else {
+        Some((vec![call.call(), parts.base.clone()], parts.base))
+    }
+}
+
+fn start_line(text: &str, record: &str) -> usize {
+    let offset = record.as_ptr() as usize - text.as_ptr() as usize;
+    let leading = record.len() - record.trim_start().len();
+    text[..offset + leading].matches('\n').count() + 1
+}
+
+fn parse_error(line: usize, message: &str) -> CtyError {
+    CtyError::Parse { line, message: message.to_string() }
+}
+
+fn parse_number(s: &str, at: usize) -> Result<f64, CtyError> {
+    s.trim().parse::<f64>().map_err(|_| parse_error(at, &format!("invalid number \"{}\"", s.trim())))
+}
+
+fn parse_zone(s: &str, at: usize) -> Result<u8, CtyError> {
+    s.trim().parse::<u8>().map_err(|_| parse_error(at, &format!("invalid zone \"{}\"", s.trim())))
+}
+
+fn parse_continent(s: &str, at: usize) -> Result<AdifContinent, CtyError> {
+    s.trim().parse().map_err(|_| parse_error(at, &format!("invalid continent \"{}\"", s.trim())))
+}
+
+// cty.dat names that differ from the built-in ones by more than the
+// abbreviations that normalized_name() evens out.
+const DAT_NAMES: &[(&str, Country)] = &[
+    ("Agalega & St. Brandon", Country::AgalegaAndStBrandon),
+    ("Asiatic Turkey", Country::Turkey),
+    ("Dem. Rep. of the Congo", Country::DemocraticRepublicOfTheCongo),
+    ("DPR of Korea", Country::NorthKorea),
+    ("Eastern Kiribati", Country::EastKiribati),
+    ("European Turkey", Country::Turkey),
+    ("Fed. Rep. of Germany", Country::Germany),
+    ("N.Z. Subantarctic Is.", Country::NewZealandSubantarcticIslands),
+    ("Pr. Edward & Marion Is.", Country::PrinceEdwardAndMarion),
+    ("Republic of South Sudan", Country::SouthSudan),
+    ("Rodriguez Island", Country::RodriguesIsland),
+    ("Sov Mil Order of Malta", Country::SovereignMilitaryOrderOfMalta),
+    ("St. Peter & St. Paul", Country::StPeterAndStPaulRocks),
+    ("Trindade & Martim Vaz", Country::TrindadeAndMartimVaz),
+    ("UK Base Areas on Cyprus", Country::UkSovereignBaseAreasOnCyprus),
+    ("US Virgin Islands", Country::VirginIslands),
+    ("Vietnam", Country::Vietnam),
+    ("Western Kiribati", Country::WestKiribati),
+];
+
+// Reduces a name to lowercase words, with "Island", "Is." and "I." all
+// written "i", "Saint" as "st", and "the", "of" and "and" dropped, so that
+// "Crozet Island" matches "Crozet I.".
+fn normalized_name(name: &str) -> String {
+    name.to_lowercase()
+        .replace('&', " and ")
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !matches!(*word, "" | "the" | "of" | "and"))
+        .map(|word| match word {
+            "island" | "islands" | "is" => "i",
+            "saint" => "st",
+            "republic" => "rep",
+            _ => word,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Built-in entities by normalized name and ADIF name.  Current entities
+// take precedence over deleted ones with the same name.
+fn country_names() -> HashMap<String, Country> {
+    let mut names = HashMap::new();
+    let deleted_first = Country::ALL.iter().filter(|c| c.is_deleted())
+        .chain(Country::ALL.iter().filter(|c| !c.is_deleted()));
+    for country in deleted_first {
+        names.insert(normalized_name(country.adif_name()), *country);
+        names.insert(normalized_name(country.name()), *country);
+    }
+    names
+}
+
+// cty.dat has no entity codes, so they come from the entity's name or,
+// failing that, from the built-in table's entity for its primary prefix.
+// Entities sharing a prefix are told apart in cty.dat with a lowercase
+// letter ("FT/w" is Crozet Island); the built-in entity for "FT" would be
+// a guess, so those are left without a code.
+fn dat_country(names: &HashMap<String, Country>, name: &str, prefix: &str) -> Option<Country> {
+    let prefix = prefix.trim_start_matches('*');
+    DAT_NAMES.iter()
+        .find(|(dat, _)| dat.eq_ignore_ascii_case(name))
+        .map(|(_, country)| *country)
+        .or_else(|| names.get(&normalized_name(name)).copied())
+        .or_else(|| if prefix.contains('/') { None } else { Call::new(prefix).country().ok() })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn new_entity(name: &str, prefix: &str, dxcc: Option<u32>, continent: &str, cq: &str, itu: &str,
+    coord: (f64, f64), utc_offset: f64, at: usize) -> Result<CtyEntity, CtyError> {
+    let wae_only = prefix.starts_with('*');
+    let prefix = prefix.trim_start_matches('*');
+
+    Ok(CtyEntity {
+        name: name.to_string(),
+        primary_prefix: prefix.to_string(),
+        dxcc,
+        continent: parse_continent(continent, at)?,
+        cq_zone: parse_zone(cq, at)?,
+        itu_zone: parse_zone(itu, at)?,
+        coord: Some(coord),
+        utc_offset: Some(utc_offset),
+        wae_only,
+    })
+}
+
+// Splits an alias such as "=K1ABC(4)[7]" into the prefix or call, whether
+// it is an exact call, and its overrides: (CQ zone), [ITU zone],
+// <latitude/longitude>, {continent} and ~time offset~.
+fn parse_alias(alias: &str, entity: usize, at: usize) -> Result<(String, bool, CtyAlias), CtyError> {
+    let exact = alias.starts_with('=');
+    let alias = alias.trim_start_matches('=');
+    let end = alias.find(|c| "([<{~".contains(c)).unwrap_or(alias.len());
+    let name = alias[..end].to_uppercase();
+    let mut result = CtyAlias { entity, ..CtyAlias::default() };
+
+    let mut rest = &alias[end..];
+    while let Some(open) = rest.chars().next() {
+        let close = match open {
+            '(' => ')',
+            '[' => ']',
+            '<' => '>',
+            '{' => '}',
+            '~' => '~',
+            _ => return Err(parse_error(at, &format!("unexpected '{}' in \"{}\"", open, alias))),
+        };
+        let len = rest[1..].find(close)
+            .ok_or_else(|| parse_error(at, &format!("missing '{}' in \"{}\"", close, alias)))?;
+        let value = &rest[1..len + 1];
+        match open {
+            '(' => result.cq_zone = Some(parse_zone(value, at)?),
+            '[' => result.itu_zone = Some(parse_zone(value, at)?),
+            '{' => result.continent = Some(parse_continent(value, at)?),
+            '~' => result.utc_offset = Some(-parse_number(value, at)?),
+            _ => {
+                let (lat, lon) = value.split_once('/')
+                    .ok_or_else(|| parse_error(at, &format!("invalid location \"{}\"", value)))?;
+                result.coord = Some((parse_number(lat, at)?, -parse_number(lon, at)?));
+            }
+        }
+        rest = &rest[len + 2..];
+    }
+
+    Ok((name, exact, result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CTY_DAT: &str = "\
+Sov Mil Order of Malta:   15:  28:  EU:   41.90:   -12.43:    -1.0:  1A:
+    1A;
+Hawaii:                   31:  61:  OC:   21.12:   157.48:    10.0:  KH6:
+    AH6,AH7,KH6,KH7,NH6,NH7,WH6,WH7,=KH6/W1AW;
+United States:            05:  08:  NA:   37.53:    91.67:     5.0:  K:
+    AA,AB,K,N,W,=W1AW(5)[8],K0(4)[7],
+    W6(3)[6]{NA}<36.0/120.0>~8.0~;
+Sicily:                   15:  28:  EU:   37.50:   -14.00:    -1.0:  *IT9:
+    IT9,IW9;
+Italy:                    15:  28:  EU:   42.82:   -12.58:    -1.0:  I:
+    I,IT,IW;
+";
+
+    #[test]
+    fn test_parse_dat() {
+        let cty = CtyDat::parse_dat(CTY_DAT).unwrap();
+        assert_eq!(cty.entities().len(), 5);
+        let us = &cty.entities()[2];
+        assert_eq!(us.name, "United States");
+        assert_eq!(us.dxcc, Some(291));
+        assert_eq!(us.coord, Some((37.53, -91.67)));
+        assert_eq!(us.utc_offset, Some(-5.0));
+        assert_eq!(cty.entities()[0].dxcc, Some(246));
+        assert_eq!(cty.entities()[1].dxcc, Some(110));
+        assert!(cty.entities()[3].wae_only);
+
+        let lookup = |call: &str| cty.lookup(&Call::new(call)).unwrap();
+        assert_eq!(lookup("KH6ABC").name, "Hawaii");
+        assert_eq!(lookup("K1ABC").name, "United States");
+        assert_eq!(lookup("K1ABC").cq_zone, 5);
+        assert_eq!(lookup("K0ABC").cq_zone, 4);
+        assert_eq!(lookup("K0ABC").itu_zone, 7);
+        let w6 = lookup("W6ABC");
+        assert_eq!((w6.cq_zone, w6.itu_zone), (3, 6));
+        assert_eq!(w6.coord, Some((36.0, -120.0)));
+        assert_eq!(w6.utc_offset, Some(-8.0));
+
+        assert_eq!(lookup("KH6/W1AW").name, "Hawaii");
+        assert_eq!(lookup("KH6/K1ABC").name, "Hawaii");
+        assert_eq!(lookup("W1AW/P").name, "United States");
+        assert_eq!(lookup("IT9ABC").name, "Italy");
+        assert_eq!(lookup("IT9ABC").country(), Some(Country::Italy));
+        assert!(cty.lookup(&Call::new("JA1ABC")).is_none());
+        assert!(cty.lookup(&Call::new("K1ABC/MM")).is_none());
+    }
+
+    #[test]
+    fn test_dat_names() {
+        let cty = CtyDat::parse_dat("\
+Crozet Island:            39:  68:  AF:  -46.42:   -51.75:    -5.0:  FT/w:
+    FT0W,FT4W,FT5W,FT8W;
+Swains Island:            32:  62:  OC:  -11.05:   171.25:    11.0:  KH8/s:
+    =KH8SI;
+Sov Mil Order of Malta:   15:  28:  EU:   41.90:   -12.43:    -1.0:  1A:
+    1A;
+Fed. Rep. of Germany:     14:  28:  EU:   51.00:   -10.00:    -1.0:  DL:
+    DA,DL;
+Temotu Province:          28:  51:  OC:  -10.72:  -165.80:   -11.0:  H40:
+    H40;
+Nowhere Island:           28:  51:  OC:  -10.72:  -165.80:   -11.0:  ZZ/n:
+    ZZ9;
+").unwrap();
+        let country = |call: &str| cty.lookup(&Call::new(call)).unwrap().country();
+        assert_eq!(country("FT5WQ"), Some(Country::CrozetIsland));
+        assert_eq!(country("KH8SI"), Some(Country::SwainsIsland));
+        assert_eq!(country("1A0KM"), Some(Country::SovereignMilitaryOrderOfMalta));
+        assert_eq!(country("DL1ABC"), Some(Country::Germany));
+        assert_eq!(country("H40AA"), Some(Country::TemotuProvince));
+        assert_eq!(cty.entities()[5].dxcc, None);
+
+        assert_eq!(normalized_name("Amsterdam & St. Paul Is."), "amsterdam st paul i");
+        assert_eq!(normalized_name("Saint Paul Island"), normalized_name("St. Paul I."));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        match CtyDat::parse_dat("Hawaii: 31: 61: OC: 21.12: 157.48: 10.0: KH6:\n    KH6;\n\
+            Nowhere: 99x: 61: OC: 0: 0: 0: X0:\n    X0;\n") {
+            Err(CtyError::Parse { line, message }) => {
+                assert_eq!(line, 3);
+                assert_eq!(message, "invalid zone \"99x\"");
+            },
+            other => panic!("{:?}", other),
+        }
+        assert!(CtyDat::parse_dat("Hawaii: 31: 61: OC: 21.12\n").is_err());
+        assert!(CtyDat::parse_dat("Hawaii: 31: 61: OC: 21.12: 157.48: 10.0: KH6:\n    KH6(31;\n").is_err());
+        assert!(CtyDat::parse_csv("K,United States,291,NA,5,8\n").is_err());
+        assert!(CtyDat::load("/nonexistent/cty.dat").is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let cty = CtyDat::parse_csv("\
+1A,Sov Mil Order of Malta,246,EU,15,28,41.9,-12.43,-1.0,1A;
+KH6,Hawaii,110,OC,31,61,21.12,157.48,10.0,AH6 AH7 KH6 KH7 NH6 NH7 WH6 WH7;
+K,United States,291,NA,5,8,37.53,91.67,5.0,AA AB K N W =W1AW(5)[8] K0(4)[7];
+").unwrap();
+        assert_eq!(cty.entities().len(), 3);
+        let hawaii = cty.lookup(&Call::new("NH7XY")).unwrap();
+        assert_eq!(hawaii.dxcc, Some(110));
+        assert_eq!(hawaii.coord, Some((21.12, -157.48)));
+        assert_eq!(hawaii.utc_offset, Some(-10.0));
+        assert_eq!(cty.lookup(&Call::new("K0ABC")).unwrap().cq_zone, 4);
+        assert_eq!(cty.lookup(&Call::new("1A0KM")).unwrap().continent, AdifContinent::EU);
+    }
+
+    #[test]
+    fn test_resolve_fallback() {
+        let cty = CtyDat::parse_dat(CTY_DAT).unwrap();
+        let call = Call::new("JA1ABC");
+        assert!(cty.lookup(&call).is_none());
+        let japan = resolve(Some(&cty), &call).unwrap();
+        assert_eq!(japan.dxcc, Some(339));
+        assert_eq!(japan.cq_zone, 25);
+        assert_eq!(japan.coord, None);
+        assert_eq!(resolve(None, &call), Some(japan));
+
+        let hawaii = resolve(Some(&cty), &Call::new("KH6ABC")).unwrap();
+        assert_eq!(hawaii.dxcc, Some(110));
+        assert_eq!(hawaii.coord, Some((21.12, -157.48)));
+        assert!(resolve(Some(&cty), &Call::new("JA1ABC/MM")).is_none());
+    }
+}
@@ -1,6 +1,10 @@
 use crate::Call;
 use crate::lotw::LoTWStatus;
 
+mod privileges;
+
+pub use self::privileges::{permitted, privileges, Privilege, PrivilegeError};
+
 #[derive(Debug, Serialize, Deserialize,Clone,PartialEq)]
 pub enum OperatorClass {
     Tech,
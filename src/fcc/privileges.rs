@@ -0,0 +1,232 @@
+use crate::{Frequency, Mode, ModeCategory};
+use super::OperatorClass;
+use super::OperatorClass::*;
+use std::fmt;
+
+// Frequency privileges of each license class under 47 CFR Part 97 (97.301 for
+// the segments, 97.305 for the emissions allowed in them), for stations in
+// ITU Region 2.  The legacy Tech Plus class has the same privileges as Tech.
+// Power limits (e.g., 200 W for Novice and Tech on HF) aren't modeled.
+#[derive(Debug, PartialEq)]
+pub struct Privilege {
+    pub lower: Frequency,
+    pub upper: Frequency,
+    pub classes: &'static [OperatorClass],
+    pub modes: &'static [ModeCategory],
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PrivilegeError {
+    OutsideAmateurBands(Frequency),
+    ClassNotPermitted(OperatorClass, Frequency),
+    ModeNotPermitted(OperatorClass, Mode, Frequency),
+}
+
+impl fmt::Display for PrivilegeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PrivilegeError::OutsideAmateurBands(freq) =>
+                write!(f, "{} is outside the amateur bands", freq),
+            PrivilegeError::ClassNotPermitted(class, freq) =>
+                write!(f, "{:?} class operators have no privileges at {}", class, freq),
+            PrivilegeError::ModeNotPermitted(class, mode, freq) =>
+                write!(f, "{} is not permitted for {:?} class operators at {}", mode, class, freq),
+        }
+    }
+}
+
+impl std::error::Error for PrivilegeError {}
+
+impl Privilege {
+    pub fn contains(&self, freq: Frequency) -> bool {
+        self.lower <= freq && freq <= self.upper
+    }
+}
+
+const EXTRA: &[OperatorClass] = &[Extra];
+const ADVANCED: &[OperatorClass] = &[Extra, Advanced];
+const GENERAL: &[OperatorClass] = &[Extra, Advanced, General];
+const TECH: &[OperatorClass] = &[Extra, Advanced, General, TechPlus, Tech];
+const NOVICE: &[OperatorClass] = &[Extra, Advanced, General, TechPlus, Tech, Novice];
+const NOVICE_HF: &[OperatorClass] = &[TechPlus, Tech, Novice];
+const NOVICE_ONLY: &[OperatorClass] = &[Novice];
+
+const CW: &[ModeCategory] = &[ModeCategory::CW];
+const DATA: &[ModeCategory] = &[ModeCategory::CW, ModeCategory::Digital];
+const PHONE: &[ModeCategory] = &[ModeCategory::CW, ModeCategory::Phone, ModeCategory::Image];
+const CW_PHONE: &[ModeCategory] = &[ModeCategory::CW, ModeCategory::Phone];
+const CHANNEL: &[ModeCategory] = &[ModeCategory::CW, ModeCategory::Phone, ModeCategory::Digital];
+const ALL: &[ModeCategory] = &[ModeCategory::CW, ModeCategory::Phone, ModeCategory::Digital,
+    ModeCategory::Image];
+
+// 60m channels are given by their USB dial frequency and are 2.8 kHz wide.
+const fn channel(dial: u64) -> Privilege {
+    privilege(dial, dial + 2_800, GENERAL, CHANNEL)
+}
+
+const fn privilege(lower: u64, upper: u64, classes: &'static [OperatorClass],
+    modes: &'static [ModeCategory]) -> Privilege {
+    Privilege {
+        lower: Frequency::from_hz(lower),
+        upper: Frequency::from_hz(upper),
+        classes,
+        modes,
+    }
+}
+
+const PRIVILEGES: &[Privilege] = &[
+    // 2200m and 630m
+    privilege(135_700, 137_800, GENERAL, ALL),
+    privilege(472_000, 479_000, GENERAL, ALL),
+    // 160m
+    privilege(1_800_000, 2_000_000, GENERAL, ALL),
+    // 80m
+    privilege(3_500_000, 3_525_000, EXTRA, DATA),
+    privilege(3_525_000, 3_600_000, GENERAL, DATA),
+    privilege(3_525_000, 3_600_000, NOVICE_HF, CW),
+    privilege(3_600_000, 3_700_000, EXTRA, PHONE),
+    privilege(3_700_000, 3_800_000, ADVANCED, PHONE),
+    privilege(3_800_000, 4_000_000, GENERAL, PHONE),
+    // 60m
+    channel(5_330_500),
+    channel(5_346_500),
+    channel(5_357_000),
+    channel(5_371_500),
+    channel(5_403_500),
+    // 40m
+    privilege(7_000_000, 7_025_000, EXTRA, DATA),
+    privilege(7_025_000, 7_125_000, GENERAL, DATA),
+    privilege(7_025_000, 7_125_000, NOVICE_HF, CW),
+    privilege(7_125_000, 7_175_000, ADVANCED, PHONE),
+    privilege(7_175_000, 7_300_000, GENERAL, PHONE),
+    // 30m
+    privilege(10_100_000, 10_150_000, GENERAL, DATA),
+    // 20m
+    privilege(14_000_000, 14_025_000, EXTRA, DATA),
+    privilege(14_025_000, 14_150_000, GENERAL, DATA),
+    privilege(14_150_000, 14_175_000, EXTRA, PHONE),
+    privilege(14_175_000, 14_225_000, ADVANCED, PHONE),
+    privilege(14_225_000, 14_350_000, GENERAL, PHONE),
+    // 17m
+    privilege(18_068_000, 18_110_000, GENERAL, DATA),
+    privilege(18_110_000, 18_168_000, GENERAL, PHONE),
+    // 15m
+    privilege(21_000_000, 21_025_000, EXTRA, DATA),
+    privilege(21_025_000, 21_200_000, GENERAL, DATA),
+    privilege(21_025_000, 21_200_000, NOVICE_HF, CW),
+    privilege(21_200_000, 21_225_000, EXTRA, PHONE),
+    privilege(21_225_000, 21_275_000, ADVANCED, PHONE),
+    privilege(21_275_000, 21_450_000, GENERAL, PHONE),
+    // 12m
+    privilege(24_890_000, 24_930_000, GENERAL, DATA),
+    privilege(24_930_000, 24_990_000, GENERAL, PHONE),
+    // 10m
+    privilege(28_000_000, 28_300_000, NOVICE, DATA),
+    privilege(28_300_000, 28_500_000, NOVICE_HF, CW_PHONE),
+    privilege(28_300_000, 29_700_000, GENERAL, PHONE),
+    // 6m and 2m
+    privilege(50_000_000, 50_100_000, TECH, CW),
+    privilege(50_100_000, 54_000_000, TECH, ALL),
+    privilege(144_000_000, 144_100_000, TECH, CW),
+    privilege(144_100_000, 148_000_000, TECH, ALL),
+    // 1.25m
+    privilege(219_000_000, 220_000_000, TECH, DATA),
+    privilege(222_000_000, 225_000_000, NOVICE, ALL),
+    // UHF and microwave
+    privilege(420_000_000, 450_000_000, TECH, ALL),
+    privilege(902_000_000, 928_000_000, TECH, ALL),
+    privilege(1_240_000_000, 1_300_000_000, TECH, ALL),
+    privilege(1_270_000_000, 1_295_000_000, NOVICE_ONLY, ALL),
+    privilege(2_300_000_000, 2_310_000_000, TECH, ALL),
+    privilege(2_390_000_000, 2_450_000_000, TECH, ALL),
+    privilege(5_650_000_000, 5_925_000_000, TECH, ALL),
+    privilege(10_000_000_000, 10_500_000_000, TECH, ALL),
+    privilege(24_000_000_000, 24_250_000_000, TECH, ALL),
+    privilege(47_000_000_000, 47_200_000_000, TECH, ALL),
+    privilege(76_000_000_000, 81_000_000_000, TECH, ALL),
+    privilege(122_250_000_000, 123_000_000_000, TECH, ALL),
+    privilege(134_000_000_000, 141_000_000_000, TECH, ALL),
+    privilege(241_000_000_000, 250_000_000_000, TECH, ALL),
+];
+
+// Returns the privileges held by a license class.
+pub fn privileges(class: &OperatorClass) -> Vec<&'static Privilege> {
+    PRIVILEGES.iter().filter(|p| p.classes.contains(class)).collect()
+}
+
+// Checks whether an operator of the given class could operate the mode at the
+// frequency.  On success this returns the privilege that allows it; otherwise
+// the error says whether the frequency, the class, or the mode was the problem.
+pub fn permitted(freq: Frequency, mode: &Mode, class: &OperatorClass)
+    -> Result<&'static Privilege, PrivilegeError> {
+    let segments: Vec<&'static Privilege> =
+        PRIVILEGES.iter().filter(|p| p.contains(freq)).collect();
+    if segments.is_empty() {
+        return Err(PrivilegeError::OutsideAmateurBands(freq));
+    }
+
+    let category = mode.category();
+    let mut class_found = false;
+    for segment in segments {
+        if segment.classes.contains(class) {
+            if segment.modes.contains(&category) {
+                return Ok(segment);
+            }
+            class_found = true;
+        }
+    }
+
+    if class_found {
+        Err(PrivilegeError::ModeNotPermitted(class.clone(), mode.clone(), freq))
+    } else {
+        Err(PrivilegeError::ClassNotPermitted(class.clone(), freq))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn khz(khz: u64) -> Frequency {
+        Frequency::from_hz(khz * 1_000)
+    }
+
+    #[test]
+    fn test_permitted() {
+        let mode = |name: &str| name.parse::<Mode>().unwrap();
+
+        assert!(permitted(khz(14_250), &mode("USB"), &General).is_ok());
+        assert_eq!(permitted(khz(14_250), &mode("USB"), &Tech),
+            Err(PrivilegeError::ClassNotPermitted(Tech, khz(14_250))));
+        assert_eq!(permitted(khz(14_010), &mode("CW"), &General),
+            Err(PrivilegeError::ClassNotPermitted(General, khz(14_010))));
+        assert!(permitted(khz(14_010), &mode("CW"), &Extra).is_ok());
+        assert_eq!(permitted(khz(14_074), &mode("USB"), &Extra),
+            Err(PrivilegeError::ModeNotPermitted(Extra, mode("USB"), khz(14_074))));
+        assert!(permitted(khz(14_074), &mode("FT8"), &General).is_ok());
+        assert!(permitted(khz(14_200), &mode("USB"), &Advanced).is_ok());
+        assert!(permitted(khz(14_200), &mode("USB"), &General).is_err());
+
+        assert!(permitted(khz(7_030), &mode("CW"), &Novice).is_ok());
+        assert!(permitted(khz(7_074), &mode("FT8"), &Tech).is_err());
+        assert!(permitted(khz(28_400), &mode("USB"), &TechPlus).is_ok());
+        assert!(permitted(khz(28_074), &mode("FT8"), &Tech).is_ok());
+        assert!(permitted(khz(146_520), &mode("FM"), &Tech).is_ok());
+        assert!(permitted(khz(146_520), &mode("FM"), &Novice).is_err());
+        assert!(permitted(khz(223_500), &mode("FM"), &Novice).is_ok());
+        assert!(permitted(khz(5_357), &mode("FT8"), &General).is_ok());
+        assert!(permitted(khz(5_360), &mode("FT8"), &General).is_err());
+
+        assert_eq!(permitted(khz(11_000), &mode("CW"), &Extra),
+            Err(PrivilegeError::OutsideAmateurBands(khz(11_000))));
+        assert_eq!(permitted(khz(14_250), &mode("USB"), &Tech).unwrap_err().to_string(),
+            "Tech class operators have no privileges at 14.25 MHz");
+    }
+
+    #[test]
+    fn test_privileges() {
+        let extra = privileges(&Extra);
+        assert!(extra.len() > privileges(&General).len());
+        assert!(privileges(&Novice).iter().all(|p| p.lower < khz(30_000) || p.lower >= khz(222_000)));
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt;
+use std::str::FromStr;
+
+// A radio frequency, stored as a whole number of Hz so that it can be compared
+// and converted without the rounding errors of floating point.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Frequency(u64);
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum FrequencyError {
+    Empty,
+    InvalidNumber(String),
+    InvalidUnit(String),
+    TooLarge(String),
+}
+
+impl fmt::Display for FrequencyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrequencyError::Empty => write!(f, "empty frequency"),
+            FrequencyError::InvalidNumber(s) => write!(f, "\"{}\" is not a valid frequency", s),
+            FrequencyError::InvalidUnit(s) => write!(f, "\"{}\" is not a frequency unit", s),
+            FrequencyError::TooLarge(s) => write!(f, "frequency \"{}\" is too large", s),
+        }
+    }
+}
+
+impl std::error::Error for FrequencyError {}
+
+const HZ_PER_KHZ: u64 = 1_000;
+const HZ_PER_MHZ: u64 = 1_000_000;
+
+impl Frequency {
+    pub const fn from_hz(hz: u64) -> Frequency {
+        Frequency(hz)
+    }
+
+    // Negative and non-finite values become 0 Hz.
+    pub fn from_khz(khz: f64) -> Frequency {
+        Frequency((khz * HZ_PER_KHZ as f64).round() as u64)
+    }
+
+    pub fn from_mhz(mhz: f64) -> Frequency {
+        Frequency((mhz * HZ_PER_MHZ as f64).round() as u64)
+    }
+
+    pub fn hz(&self) -> u64 {
+        self.0
+    }
+
+    pub fn khz(&self) -> f64 {
+        self.0 as f64 / HZ_PER_KHZ as f64
+    }
+
+    pub fn mhz(&self) -> f64 {
+        self.0 as f64 / HZ_PER_MHZ as f64
+    }
+
+    // Parse a decimal number of MHz, as used by the ADIF "freq" field
+    // (e.g., "14.074").  Digits beyond 1 Hz resolution are rounded.
+    pub fn parse_mhz(s: &str) -> Result<Frequency, FrequencyError> {
+        parse_decimal(s, 6)
+    }
+
+    // Parse a decimal number of kHz (e.g., "14074.5").
+    pub fn parse_khz(s: &str) -> Result<Frequency, FrequencyError> {
+        parse_decimal(s, 3)
+    }
+
+    // Format as MHz with as many decimal places as needed ("14.074").
+    pub fn format_mhz(&self) -> String {
+        format_decimal(self.0, HZ_PER_MHZ, 6)
+    }
+
+    // Format as kHz with as many decimal places as needed ("14074").
+    pub fn format_khz(&self) -> String {
+        format_decimal(self.0, HZ_PER_KHZ, 3)
+    }
+}
+
+impl From<u64> for Frequency {
+    fn from(hz: u64) -> Frequency {
+        Frequency(hz)
+    }
+}
+
+// For callers that kept frequencies in Hz as i32, as Band did.  Negative
+// values become 0 Hz.
+impl From<i32> for Frequency {
+    fn from(hz: i32) -> Frequency {
+        Frequency(hz.max(0) as u64)
+    }
+}
+
+impl From<u32> for Frequency {
+    fn from(hz: u32) -> Frequency {
+        Frequency(u64::from(hz))
+    }
+}
+
+impl From<Frequency> for u64 {
+    fn from(freq: Frequency) -> u64 {
+        freq.0
+    }
+}
+
+// Parses a number with an optional unit ("14.074", "14.074 MHz", "14074 kHz",
+// "14074000 Hz").  Without a unit the number is taken to be MHz, as in ADIF.
+impl FromStr for Frequency {
+    type Err = FrequencyError;
+
+    fn from_str(s: &str) -> Result<Frequency, FrequencyError> {
+        let s = s.trim();
+        let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        let decimals = match unit.to_lowercase().as_str() {
+            "" | "mhz" => 6,
+            "khz" => 3,
+            "hz" => 0,
+            "ghz" => 9,
+            _ => return Err(FrequencyError::InvalidUnit(unit.to_string())),
+        };
+        parse_decimal(number, decimals)
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} MHz", self.format_mhz())
+    }
+}
+
+// Parse an unsigned decimal number, scaling it by 10^decimals into Hz.
+fn parse_decimal(s: &str, decimals: u32) -> Result<Frequency, FrequencyError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(FrequencyError::Empty);
+    }
+
+    let invalid = || FrequencyError::InvalidNumber(s.to_string());
+    let too_large = || FrequencyError::TooLarge(s.to_string());
+    let (whole, fraction) = match s.find('.') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, ""),
+    };
+    if (whole.is_empty() && fraction.is_empty()) ||
+        !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let scale = 10u64.pow(decimals);
+    let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| too_large())? };
+    let mut hz = whole.checked_mul(scale).ok_or_else(too_large)?;
+
+    let digits = fraction.as_bytes();
+    let mut place = scale;
+    for &digit in digits.iter().take(decimals as usize) {
+        place /= 10;
+        hz = hz.checked_add((digit - b'0') as u64 * place).ok_or_else(too_large)?;
+    }
+    if digits.len() > decimals as usize && digits[decimals as usize] >= b'5' {
+        hz = hz.checked_add(1).ok_or_else(too_large)?;
+    }
+
+    Ok(Frequency(hz))
+}
+
+fn format_decimal(hz: u64, scale: u64, decimals: usize) -> String {
+    let s = format!("{}.{:0width$}", hz / scale, hz % scale, width = decimals);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Frequency::parse_mhz("14.074"), Ok(Frequency::from_hz(14_074_000)));
+        assert_eq!(Frequency::parse_mhz("1296.2001"), Ok(Frequency::from_hz(1_296_200_100)));
+        assert_eq!(Frequency::parse_mhz("10368.1"), Ok(Frequency::from_hz(10_368_100_000)));
+        assert_eq!(Frequency::parse_mhz(".5"), Ok(Frequency::from_hz(500_000)));
+        assert_eq!(Frequency::parse_mhz("7.0740005"), Ok(Frequency::from_hz(7_074_001)));
+        assert_eq!(Frequency::parse_khz("14074.5"), Ok(Frequency::from_hz(14_074_500)));
+        assert_eq!(Frequency::parse_mhz(""), Err(FrequencyError::Empty));
+        assert!(Frequency::parse_mhz("14,074").is_err());
+        assert!(Frequency::parse_mhz("-14").is_err());
+        assert!(Frequency::parse_mhz("99999999999999999999").is_err());
+        assert_eq!(Frequency::parse_mhz("18446744073709.551615"), Ok(Frequency::from_hz(u64::MAX)));
+        assert_eq!(Frequency::parse_mhz("18446744073709.999999"),
+            Err(FrequencyError::TooLarge("18446744073709.999999".to_string())));
+        assert!(Frequency::parse_mhz("18446744073709.5516155").is_err());
+
+        assert_eq!("14074 kHz".parse(), Ok(Frequency::from_hz(14_074_000)));
+        assert_eq!("10.368GHz".parse(), Ok(Frequency::from_hz(10_368_000_000)));
+        assert_eq!("3573000 Hz".parse(), Ok(Frequency::from_hz(3_573_000)));
+        assert_eq!("50.313".parse(), Ok(Frequency::from_hz(50_313_000)));
+        assert!("14.074 furlongs".parse::<Frequency>().is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        let freq = Frequency::from_hz(14_074_000);
+        assert_eq!(freq.format_mhz(), "14.074");
+        assert_eq!(freq.format_khz(), "14074");
+        assert_eq!(freq.to_string(), "14.074 MHz");
+        assert_eq!(Frequency::from_hz(144_000_000).format_mhz(), "144");
+        assert_eq!(Frequency::from_hz(14_074_500).format_khz(), "14074.5");
+        assert_eq!(Frequency::from_mhz(432.1).hz(), 432_100_000);
+        assert_eq!(Frequency::from_khz(7074.0).mhz(), 7.074);
+    }
+}
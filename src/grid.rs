@@ -4,17 +4,111 @@ use std::fmt;
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub enum GridError {
     InvalidLength(usize),
-    InvalidFormat
+    InvalidFormat,
+    InvalidCoordinate(f64, f64),
+    InvalidField(String),
+    InvalidSquare(String),
+    InvalidSubsquare(String),
+    InvalidExtendedSquare(String),
+    InvalidExtendedSubsquare(String)
 }
 impl fmt::Display for GridError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             GridError::InvalidLength(size) => write!(f, "Invalid Length: {}", size),
-            GridError::InvalidFormat => write!(f, "Invalid Format")
+            GridError::InvalidFormat => write!(f, "Invalid Format"),
+            GridError::InvalidCoordinate(lat, lon) => write!(f, "Invalid Coordinate: {}, {}", lat, lon),
+            GridError::InvalidField(pair) => write!(f, "Invalid Field: {} (expected AA-RR)", pair),
+            GridError::InvalidSquare(pair) => write!(f, "Invalid Square: {} (expected 00-99)", pair),
+            GridError::InvalidSubsquare(pair) => write!(f, "Invalid Subsquare: {} (expected aa-xx)", pair),
+            GridError::InvalidExtendedSquare(pair) =>
+                write!(f, "Invalid Extended Square: {} (expected 00-99)", pair),
+            GridError::InvalidExtendedSubsquare(pair) =>
+                write!(f, "Invalid Extended Subsquare: {} (expected aa-xx)", pair)
         }
     }
 }
 
+// Each pair of characters in a grid divides the cell given by the pairs before
+// it into a number of columns and rows.  Cell sizes in degrees are kept as
+// fractions (num / den) so that they can be computed exactly.
+struct GridPair {
+    base: char,
+    divisions: u32,
+    lon_num: f64,
+    lon_den: f64,
+    lat_num: f64,
+    lat_den: f64,
+    error: fn(String) -> GridError,
+}
+
+const GRID_PAIRS: [GridPair; 5] = [
+    // Field: 18 x 18, 20 by 10 degrees
+    GridPair { base: 'A', divisions: 18, lon_num: 20.0, lon_den: 1.0, lat_num: 10.0, lat_den: 1.0,
+        error: GridError::InvalidField },
+    // Square: 10 x 10, 2 by 1 degrees
+    GridPair { base: '0', divisions: 10, lon_num: 2.0, lon_den: 1.0, lat_num: 1.0, lat_den: 1.0,
+        error: GridError::InvalidSquare },
+    // Subsquare: 24 x 24, 5 by 2.5 minutes
+    GridPair { base: 'A', divisions: 24, lon_num: 1.0, lon_den: 12.0, lat_num: 1.0, lat_den: 24.0,
+        error: GridError::InvalidSubsquare },
+    // Extended square: 10 x 10, 30 by 15 seconds
+    GridPair { base: '0', divisions: 10, lon_num: 1.0, lon_den: 120.0, lat_num: 1.0, lat_den: 240.0,
+        error: GridError::InvalidExtendedSquare },
+    // Extended subsquare: 24 x 24, 1.25 by 0.625 seconds
+    GridPair { base: 'A', divisions: 24, lon_num: 1.0, lon_den: 2880.0, lat_num: 1.0, lat_den: 5760.0,
+        error: GridError::InvalidExtendedSubsquare },
+];
+
+// Mean radius of the Earth, as used for great-circle calculations.
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+pub const KM_PER_MILE: f64 = 1.609344;
+
+// The great-circle path from one point to another.  Distances are in km and
+// bearings in degrees clockwise from true north.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GreatCirclePath {
+    pub distance_km: f64,
+    pub bearing: f64,
+    pub long_path_km: f64,
+    pub long_path_bearing: f64,
+}
+
+impl GreatCirclePath {
+    pub fn between(from: (f64, f64), to: (f64, f64)) -> GreatCirclePath {
+        let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+        let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = lon2 - lon1;
+
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        let angle = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
+        let distance_km = EARTH_RADIUS_KM * angle;
+
+        let y = dlon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+        let bearing = (y.atan2(x).to_degrees() + 360.0) % 360.0;
+
+        GreatCirclePath {
+            distance_km,
+            bearing,
+            long_path_km: 2.0 * std::f64::consts::PI * EARTH_RADIUS_KM - distance_km,
+            long_path_bearing: (bearing + 180.0) % 360.0,
+        }
+    }
+
+    pub fn distance_mi(&self) -> f64 {
+        self.distance_km / KM_PER_MILE
+    }
+
+    pub fn long_path_mi(&self) -> f64 {
+        self.long_path_km / KM_PER_MILE
+    }
+}
+
+// A (latitude, longitude) pair, or a (height, width) size, in degrees.
+type Coord = (f64, f64);
+
 #[derive(Debug,Clone,PartialEq)]
 pub struct Grid {
     grid: String
@@ -45,19 +139,34 @@ impl Serialize for Grid {
 }
 
 impl Grid {
+    // Validates a grid of 4, 6, 8 or 10 characters, each pair within its legal
+    // range: field A-R, square 0-9, subsquare A-X, extended square 0-9 and
+    // extended subsquare A-X.  Letters are accepted in either case and stored
+    // in the usual form, with the field in upper case and subsquares in lower
+    // case (e.g., "EM73tk42ab").
     pub fn new<S: Into<String>>(grid: S) -> Result<Grid,GridError> {
         let grid = grid.into();
         let length = grid.len();
-        if length >= 4 && length <= 10 && (length % 2 == 0) {
-            let grid = Grid { grid: grid };
-            match grid.coord() {
-                Ok(_) => Ok(grid),
-                Err(e) => Err(e)
+        if !(4..=10).contains(&length) || length % 2 == 1 {
+            return Err(GridError::InvalidLength(length));
+        }
+        if !grid.is_ascii() {
+            return Err(GridError::InvalidFormat);
+        }
+
+        let mut normalized = String::with_capacity(length);
+        for (i, (pair, chars)) in GRID_PAIRS.iter().zip(grid.as_bytes().chunks(2)).enumerate() {
+            for &c in chars {
+                let c = if i == 0 { c.to_ascii_uppercase() } else { c.to_ascii_lowercase() };
+                if Self::pair_index(pair, c).is_none() {
+                    return Err((pair.error)(String::from_utf8_lossy(chars).into_owned()));
+                }
+                normalized.push(c as char);
             }
-        } else {
-            Err(GridError::InvalidLength(length))
         }
-    } 
+        Ok(Grid { grid: normalized })
+    }
+
     pub fn short(&self) -> String {
         let grid = self.grid.to_string();
         let first = &self.grid[0..4];
@@ -69,59 +178,211 @@ impl Grid {
         self.grid.to_string()
     }
 
-    pub fn coord(&self) -> Result<(f32,f32),GridError> {
-        let grid = self.grid.clone();
-        let grid : Vec<char> = grid.to_uppercase().chars().collect();
-        
-        match grid.len() {
-            4 => match (self.maybe_alpha_block([grid[0],grid[1]]), self.maybe_numeric_block([grid[2],grid[3]])) {
-                (Some((a,b)), Some((c,d))) => {
-                    let long = ((a - ('A' as u8)) as f32) * 20.0 + ((c as f32) - (('0' as u8) as f32) + 0.5) * 2.0 - 180.0;
-                    let lat = ((b - ('A' as u8)) as f32) * 10.0 + ((d as f32) - (('0' as u8) as f32) + 0.5) - 90.0;
-                    Ok((lat,long))
-                },
-                _ => Err(GridError::InvalidFormat)
-            },
-            6 => match (self.maybe_alpha_block([grid[0],grid[1]]), self.maybe_numeric_block([grid[2],grid[3]]), self.maybe_alpha_block([grid[4],grid[5]])) {
-                (Some((a,b)), Some((c,d)), Some((e,f))) => {
-    				let long = ((a - ('A' as u8)) as f32) * 20.0 + ((c - ('0' as u8)) as f32) * 2.0 + ((e as f32) - (('A' as u8) as f32) + 0.5) / 12.0 - 180.0;
-                    let lat = ((b - ('A' as u8)) as f32) * 10.0 + ((d - ('0' as u8)) as f32) + ((f as f32) - (('A' as u8) as f32) + 0.5) / 24.0 - 90.0;
-                    Ok((lat,long))
-                },
-                _ => Err(GridError::InvalidFormat)
-            },
-            8 => match (self.maybe_alpha_block([grid[0],grid[1]]), self.maybe_numeric_block([grid[2],grid[3]]), self.maybe_alpha_block([grid[4],grid[5]]), self.maybe_numeric_block([grid[6],grid[7]])) {
-                (Some((a,b)), Some((c,d)), Some((e,f)), Some((g,h))) => {
-				    let long = ((a - ('A' as u8)) as f32) * 20.0 + ((c - ('0' as u8)) as f32) * 2.0 + (e as f32 - (('A' as u8) as f32) + 0.0) / 12.0 + (g as f32 - (('0' as u8) as f32) + 0.5) / 120.0 - 180.0;
-				    let lat = ((b - ('A' as u8)) as f32) * 10.0 + ((d - ('0' as u8)) as f32) + (f as f32 - (('A' as u8) as f32) + 0.0) / 24.0 + (h as f32 - (('0' as u8) as f32) + 0.5) / 240.0 - 90.0;
-                    Ok((lat,long))
-                },
-                _ => Err(GridError::InvalidFormat)
-            },
-            10 => match (self.maybe_alpha_block([grid[0],grid[1]]), self.maybe_numeric_block([grid[2],grid[3]]), self.maybe_alpha_block([grid[4],grid[5]]), self.maybe_numeric_block([grid[6],grid[7]]), self.maybe_alpha_block([grid[8],grid[9]])) {
-                (Some((a,b)), Some((c,d)), Some((e,f)), Some((g,h)), Some((i,j))) => {
-			        let long = ((a - ('A' as u8)) as f32) * 20.0 + ((c - ('0' as u8)) as f32) * 2.0 + (e as f32 - (('A' as u8) as f32) + 0.0) / 12.0 + (g as f32 - (('0' as u8) as f32) + 0.0) / 120.0 + (i as f32 - (('A' as u8) as f32) + 0.5) / 120.0 / 24.0 - 180.0;
-				    let lat = ((b - ('A' as u8)) as f32) * 10.0 + ((d - ('0' as u8)) as f32) + (f as f32 - (('A' as u8) as f32) + 0.0) / 24.0 + (h as f32 - (('0' as u8) as f32) + 0.0) / 240.0 + (j as f32 - (('A' as u8) as f32) + 0.5) / 240.0 / 24.0 - 90.0;
-                    Ok((lat,long))
-                },
-                _ => Err(GridError::InvalidFormat)
-            },
-            _ => Err(GridError::InvalidLength(grid.len()))
-        }
-    }
-
-    fn maybe_alpha_block(&self, input:[char;2]) -> Option<(u8, u8)> {
-        match input {
-            [f,s] if f >= 'A' && f <= 'Z' && s >= 'A' && s <= 'Z' => Some((f as u8,s as u8)),
-            _ => None
-        }
-    }
-
-    fn maybe_numeric_block(&self, input:[char;2]) -> Option<(u8, u8)> {
-        match input {
-            [f,s] if f >= '0' && f <= '9' && s >= '0' && s <= '9' => Some((f as u8,s as u8)),
-            _ => None
+    // Builds the grid of the given length (4, 6, 8 or 10 characters) that
+    // contains a latitude and longitude.  Longitude 180 is the same meridian
+    // as -180, and latitude 90 falls in the northernmost row.
+    pub fn from_coord(lat: f64, lon: f64, length: usize) -> Result<Grid,GridError> {
+        if !(4..=10).contains(&length) || length % 2 == 1 {
+            return Err(GridError::InvalidLength(length));
+        }
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return Err(GridError::InvalidCoordinate(lat, lon));
+        }
+
+        let x = (lon + 180.0) % 360.0;
+        let y = lat + 90.0;
+        let mut grid = String::with_capacity(length);
+        for (i, pair) in GRID_PAIRS.iter().take(length / 2).enumerate() {
+            let column = ((x * pair.lon_den / pair.lon_num).floor() as u32) % pair.divisions;
+            let row = ((y * pair.lat_den / pair.lat_num).floor() as u32).min(
+                Self::rows_below(i) * pair.divisions - 1) % pair.divisions;
+            for index in &[column, row] {
+                let c = (pair.base as u8 + *index as u8) as char;
+                grid.push(if pair.base == 'A' && i > 0 { c.to_ascii_lowercase() } else { c });
+            }
+        }
+        Grid::new(grid)
+    }
+
+    // The center of the grid as (latitude, longitude).
+    pub fn coord(&self) -> Result<(f64,f64),GridError> {
+        let ((lat, lon), (height, width)) = self.cell()?;
+        Ok((lat + height / 2.0, lon + width / 2.0))
+    }
+
+    // The southwest and northeast corners of the grid, each as (latitude,
+    // longitude).
+    pub fn bounding_box(&self) -> ((f64,f64),(f64,f64)) {
+        let ((lat, lon), (height, width)) = self.cell().expect("grid was validated by Grid::new");
+        ((lat, lon), (lat + height, lon + width))
+    }
+
+    // Whether the point lies in this grid.  The southern and western edges
+    // belong to the grid; the northern and eastern edges to its neighbours.
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        match Grid::from_coord(lat, lon, self.grid.len()) {
+            Ok(grid) => grid.grid.eq_ignore_ascii_case(&self.grid),
+            Err(_) => false
+        }
+    }
+
+    // Whether another grid lies within this one (EM73 contains EM73tk, and
+    // every grid contains itself).
+    pub fn contains_grid(&self, other: &Grid) -> bool {
+        other.grid.len() >= self.grid.len() &&
+            other.grid[..self.grid.len()].eq_ignore_ascii_case(&self.grid)
+    }
+
+    // The grid one level less precise that contains this one, if any.
+    pub fn parent(&self) -> Option<Grid> {
+        match self.grid.len() {
+            4 => None,
+            len => Some(Grid { grid: self.grid[..len - 2].to_string() })
+        }
+    }
+
+    // The grids one level more precise that this one is divided into, in
+    // order from the southwest corner, or an empty list for 10-character
+    // grids.
+    pub fn children(&self) -> Vec<Grid> {
+        let pair = match GRID_PAIRS.get(self.grid.len() / 2) {
+            Some(pair) => pair,
+            None => return Vec::new()
+        };
+        let (sw, _) = self.bounding_box();
+        let (height, width) = (pair.lat_num / pair.lat_den, pair.lon_num / pair.lon_den);
+
+        let mut children = Vec::new();
+        for row in 0..pair.divisions {
+            for column in 0..pair.divisions {
+                let lat = sw.0 + (row as f64 + 0.5) * height;
+                let lon = sw.1 + (column as f64 + 0.5) * width;
+                children.extend(Grid::from_coord(lat, lon, self.grid.len() + 2));
+            }
+        }
+        children
+    }
+
+    // The grids of the same precision that border this one, including those
+    // that only touch a corner.  Grids wrap around at the antimeridian but not
+    // over the poles, so grids at the top or bottom row have five neighbours.
+    pub fn neighbours(&self) -> Vec<Grid> {
+        let (lat, lon) = self.center();
+        let ((_, _), (height, width)) = self.cell().expect("grid was validated by Grid::new");
+
+        let mut neighbours = Vec::new();
+        for dlat in &[-1.0, 0.0, 1.0] {
+            for dlon in &[-1.0, 0.0, 1.0] {
+                let nlat = lat + dlat * height;
+                let nlon = (lon + dlon * width + 540.0) % 360.0 - 180.0;
+                if (*dlat == 0.0 && *dlon == 0.0) || !(-90.0..=90.0).contains(&nlat) {
+                    continue;
+                }
+                neighbours.extend(Grid::from_coord(nlat, nlon, self.grid.len()));
+            }
+        }
+        neighbours
+    }
+
+    // The grids of the given length within tolerance_km of a point, so more
+    // than one when the point is on or near a grid line.  Rovers use this to
+    // activate several grids from a single spot.
+    pub fn grids_near(lat: f64, lon: f64, length: usize, tolerance_km: f64)
+        -> Result<Vec<Grid>,GridError> {
+        let center = Grid::from_coord(lat, lon, length)?;
+        let dlat = (tolerance_km / (EARTH_RADIUS_KM * std::f64::consts::PI / 180.0)).min(1.0);
+        let dlon = (dlat / lat.to_radians().cos().max(1e-6)).min(1.0);
+
+        let mut grids = vec![center];
+        for ylat in &[-dlat, 0.0, dlat] {
+            for xlon in &[-dlon, 0.0, dlon] {
+                let nlat = (lat + ylat).clamp(-90.0, 90.0);
+                let nlon = (lon + xlon + 540.0) % 360.0 - 180.0;
+                let grid = Grid::from_coord(nlat, nlon, length)?;
+                if !grids.contains(&grid) {
+                    grids.push(grid);
+                }
+            }
+        }
+        Ok(grids)
+    }
+
+    // Whether a point is within tolerance_km of the line between two grids of
+    // the given length.
+    pub fn on_grid_line(lat: f64, lon: f64, length: usize, tolerance_km: f64) -> bool {
+        Grid::grids_near(lat, lon, length, tolerance_km).is_ok_and(|grids| grids.len() > 1)
+    }
+
+    // Whether a point is within tolerance_km of a corner shared by four grids
+    // of the given length.
+    pub fn on_grid_corner(lat: f64, lon: f64, length: usize, tolerance_km: f64) -> bool {
+        Grid::grids_near(lat, lon, length, tolerance_km).is_ok_and(|grids| grids.len() >= 4)
+    }
+
+    // The southwest corner of the grid as (latitude, longitude), and its size
+    // in degrees as (height, width).
+    fn cell(&self) -> Result<(Coord,Coord),GridError> {
+        let grid = self.grid.as_bytes();
+        if grid.len() < 4 || grid.len() > 10 || grid.len() % 2 == 1 {
+            return Err(GridError::InvalidLength(grid.len()));
         }
+
+        let mut lat = -90.0;
+        let mut lon = -180.0;
+        for (pair, chars) in GRID_PAIRS.iter().zip(grid.chunks(2)) {
+            let invalid = || (pair.error)(String::from_utf8_lossy(chars).into_owned());
+            let x = Self::pair_index(pair, chars[0]).ok_or_else(invalid)?;
+            let y = Self::pair_index(pair, chars[1]).ok_or_else(invalid)?;
+            lon += x as f64 * pair.lon_num / pair.lon_den;
+            lat += y as f64 * pair.lat_num / pair.lat_den;
+        }
+
+        let last = &GRID_PAIRS[grid.len() / 2 - 1];
+        Ok(((lat, lon), (last.lat_num / last.lat_den, last.lon_num / last.lon_den)))
+    }
+
+    // The number of rows of pair i's cells that the pairs before it divide
+    // the globe into (so that latitude 90 can be clamped to the last row).
+    fn rows_below(i: usize) -> u32 {
+        GRID_PAIRS.iter().take(i).map(|pair| pair.divisions).product()
+    }
+
+    // The path from the center of this grid to the center of another.
+    pub fn path(&self, other: &Grid) -> GreatCirclePath {
+        GreatCirclePath::between(self.center(), other.center())
+    }
+
+    // The path from the center of this grid to a latitude and longitude.
+    pub fn path_to(&self, lat: f64, lon: f64) -> GreatCirclePath {
+        GreatCirclePath::between(self.center(), (lat, lon))
+    }
+
+    pub fn distance_km(&self, other: &Grid) -> f64 {
+        self.path(other).distance_km
+    }
+
+    pub fn distance_mi(&self, other: &Grid) -> f64 {
+        self.path(other).distance_mi()
+    }
+
+    pub fn bearing(&self, other: &Grid) -> f64 {
+        self.path(other).bearing
+    }
+
+    pub fn long_path_bearing(&self, other: &Grid) -> f64 {
+        self.path(other).long_path_bearing
+    }
+
+    // Grid::new only accepts grids whose coordinates can be computed.
+    fn center(&self) -> (f64, f64) {
+        self.coord().expect("grid was validated by Grid::new")
+    }
+
+    // The column or row that a character of a pair stands for, if it is
+    // within the pair's range.  Letters may be in either case.
+    fn pair_index(pair: &GridPair, c: u8) -> Option<u32> {
+        let index = c.to_ascii_uppercase().checked_sub(pair.base as u8)? as u32;
+        if index < pair.divisions { Some(index) } else { None }
     }
 }
 
@@ -135,7 +396,26 @@ mod tests {
         assert_eq!(grid, Err(GridError::InvalidLength(5)));
 
         let grid = Grid::new("EMtk73");
-        assert_eq!(grid, Err(GridError::InvalidFormat));
+        assert_eq!(grid, Err(GridError::InvalidSquare("tk".to_string())));
+
+        assert_eq!(Grid::new("ZZ99"), Err(GridError::InvalidField("ZZ".to_string())));
+        assert_eq!(Grid::new("RS00"), Err(GridError::InvalidField("RS".to_string())));
+        assert_eq!(Grid::new("EM73ty"), Err(GridError::InvalidSubsquare("ty".to_string())));
+        assert_eq!(Grid::new("EM73tk4a"), Err(GridError::InvalidExtendedSquare("4a".to_string())));
+        assert_eq!(Grid::new("EM73tk42az"),
+            Err(GridError::InvalidExtendedSubsquare("az".to_string())));
+        assert_eq!(Grid::new("EM73é"), Err(GridError::InvalidFormat));
+        assert_eq!(Grid::new("ZZ99").unwrap_err().to_string(), "Invalid Field: ZZ (expected AA-RR)");
+        assert!(Grid::new("RR99xx9x").is_err());
+        assert!(Grid::new("AA00aa00aa").is_ok());
+        assert!(Grid::new("RR99xx99xx").is_ok());
+    }
+
+    #[test]
+    fn test_normalized_case() {
+        assert_eq!(Grid::new("em73TK42AB").unwrap().full(), "EM73tk42ab");
+        assert_eq!(Grid::new("Fn31").unwrap(), Grid::new("FN31").unwrap());
+        assert_eq!(Grid::new("em73tk").unwrap().to_string(), "EM73tk");
     }
 
     #[test]
@@ -146,4 +426,105 @@ mod tests {
         eprintln!("{:?}", grid.coord().unwrap());
     }
 
+    #[test]
+    fn test_distance_and_bearing() {
+        let fn31 = Grid::new("FN31").unwrap();
+        let jo01 = Grid::new("JO01").unwrap();
+        let path = fn31.path(&jo01);
+        assert!((path.distance_km - 5523.93).abs() < 0.01, "{}", path.distance_km);
+        assert!((path.bearing - 51.709).abs() < 0.001, "{}", path.bearing);
+        assert!((path.long_path_bearing - 231.709).abs() < 0.001);
+        assert!((path.distance_km + path.long_path_km - 40030.2).abs() < 0.1);
+        assert!((fn31.distance_mi(&jo01) - path.distance_km / KM_PER_MILE).abs() < 1e-9);
+
+        let back = jo01.path(&fn31);
+        assert!((back.distance_km - path.distance_km).abs() < 1e-6);
+        assert!((back.bearing - 289.216).abs() < 0.001, "{}", back.bearing);
+
+        assert_eq!(fn31.distance_km(&fn31), 0.0);
+        let (lat, lon) = fn31.coord().unwrap();
+        assert!((fn31.path_to(lat + 1.0, lon).distance_km - 111.195).abs() < 0.001);
+        assert!((fn31.path_to(lat - 1.0, lon).bearing - 180.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_coord() {
+        assert_eq!(Grid::new("FN31").unwrap().coord(), Ok((41.5, -73.0)));
+        assert_eq!(Grid::new("JJ00").unwrap().coord(), Ok((0.5, 1.0)));
+        let (lat, lon) = Grid::new("EM73tk").unwrap().coord().unwrap();
+        assert!((lat - (33.0 + 10.5 / 24.0)).abs() < 1e-12);
+        assert!((lon - (-86.0 + 19.5 / 12.0)).abs() < 1e-12);
+        let (lat, lon) = Grid::new("EM73tk42").unwrap().coord().unwrap();
+        assert!((lat - (33.0 + 10.0 / 24.0 + 2.5 / 240.0)).abs() < 1e-12);
+        assert!((lon - (-86.0 + 19.0 / 12.0 + 4.5 / 120.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_geometry() {
+        let em73 = Grid::new("EM73").unwrap();
+        assert_eq!(em73.bounding_box(), ((33.0, -86.0), (34.0, -84.0)));
+        assert!(em73.contains(33.0, -86.0));
+        assert!(em73.contains(33.99, -84.01));
+        assert!(!em73.contains(34.0, -85.0));
+
+        let em73tk = Grid::new("EM73tk").unwrap();
+        assert!(em73.contains_grid(&em73tk));
+        assert!(em73.contains_grid(&em73));
+        assert!(!em73tk.contains_grid(&em73));
+        assert!(!Grid::new("EM74").unwrap().contains_grid(&em73tk));
+        assert_eq!(em73tk.parent(), Some(em73.clone()));
+        assert_eq!(em73.parent(), None);
+
+        let children = em73.children();
+        assert_eq!(children.len(), 576);
+        assert_eq!(children[0].full(), "EM73aa");
+        assert!(children.contains(&em73tk));
+        assert!(children.iter().all(|child| em73.contains_grid(child)));
+        assert_eq!(em73tk.children().len(), 100);
+        assert!(Grid::new("EM73tk42ab").unwrap().children().is_empty());
+
+        let mut neighbours: Vec<String> = em73.neighbours().iter().map(|g| g.full()).collect();
+        neighbours.sort();
+        assert_eq!(neighbours, vec!["EM62", "EM63", "EM64", "EM72", "EM74", "EM82", "EM83", "EM84"]);
+        let mut neighbours: Vec<String> = Grid::new("RR99").unwrap().neighbours()
+            .iter().map(|g| g.full()).collect();
+        neighbours.sort();
+        assert_eq!(neighbours, vec!["AR08", "AR09", "RR88", "RR89", "RR98"]);
+
+        // The corner of EM73, EM74, EM83 and EM84 is at 34N, 84W.
+        let near = Grid::grids_near(34.0001, -84.0001, 4, 0.05).unwrap();
+        assert_eq!(near.len(), 4);
+        assert!(Grid::on_grid_corner(34.0001, -84.0001, 4, 0.05));
+        assert!(Grid::on_grid_line(33.5, -84.0001, 4, 0.05));
+        assert!(!Grid::on_grid_corner(33.5, -84.0001, 4, 0.05));
+        assert!(!Grid::on_grid_line(33.5, -85.0, 4, 0.05));
+    }
+
+    #[test]
+    fn test_from_coord() {
+        assert_eq!(Grid::from_coord(41.714775, -72.727260, 6).unwrap().full(), "FN31pr");
+        assert_eq!(Grid::from_coord(33.44, -82.40, 4).unwrap().full(), "EM83");
+        assert_eq!(Grid::from_coord(-33.8688, 151.2093, 6).unwrap().full(), "QF56od");
+        assert_eq!(Grid::from_coord(90.0, 180.0, 4).unwrap().full(), "AR09");
+        assert_eq!(Grid::from_coord(-90.0, -180.0, 10).unwrap().full(), "AA00aa00aa");
+        assert_eq!(Grid::from_coord(0.0, 0.0, 5), Err(GridError::InvalidLength(5)));
+        assert_eq!(Grid::from_coord(91.0, 0.0, 4), Err(GridError::InvalidCoordinate(91.0, 0.0)));
+
+        // Every grid contains its own center, at every precision.
+        let points = [(41.714775, -72.727260), (-33.8688, 151.2093), (64.1466, -21.9426),
+            (-54.8019, -68.3030), (35.6762, 139.6503), (0.0, 0.0), (89.99, 179.99)];
+        for (lat, lon) in points.iter() {
+            for length in &[4, 6, 8, 10] {
+                let grid = Grid::from_coord(*lat, *lon, *length).unwrap();
+                let (clat, clon) = grid.coord().unwrap();
+                let again = Grid::from_coord(clat, clon, *length).unwrap();
+                assert_eq!(again, grid);
+
+                let pair = &GRID_PAIRS[length / 2 - 1];
+                assert!((clat - lat).abs() <= pair.lat_num / pair.lat_den / 2.0);
+                assert!((clon - lon).abs() <= pair.lon_num / pair.lon_den / 2.0);
+            }
+        }
+    }
+
 }
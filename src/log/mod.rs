@@ -1,6 +1,10 @@
-use crate::Call;
+use crate::{Call,Country,CountryInfo,Grid};
+use crate::adif::AdifContinent;
 use crate::adif::CallsignInfo;
-use crate::{Band,Mode};
+use crate::{Band,Frequency,Mode};
+use crate::bandplan::{self, BandPlanMismatch, IaruRegion};
+use crate::clublog::ClubLogCty;
+use crate::cty::CtyEntity;
 use chrono::prelude::*;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -16,6 +20,17 @@ pub struct LogEntry {
     pub rst_received: Option<String>,
     pub lotw_qsl_sent: bool,
     pub lotw_qsl_rcvd: bool,
+    // The DXCC entity of the other station, as logged.
+    #[serde(default)]
+    pub country: Option<Country>,
+    // The other station's continent and zones, as logged.  See continent(),
+    // cq_zone() and itu_zone() for the values to use when these are absent.
+    #[serde(default)]
+    pub continent: Option<AdifContinent>,
+    #[serde(default)]
+    pub cq_zone: Option<u8>,
+    #[serde(default)]
+    pub itu_zone: Option<u8>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,11 +39,24 @@ pub enum ImportError {
     MissingMyCall,
     MissingFrequency,
     MissingMode,
+    InvalidMode,
     MissingDateTime
 }
 
 impl LogEntry {
+    // Imports a record, accepting the MODE and SUBMODE values other programs
+    // write (see Mode::import).
     pub fn from_adif_record(record: &crate::adif::AdifRecord) -> Result<LogEntry,ImportError> {
+        LogEntry::import_adif_record(record, false)
+    }
+
+    // Imports a record, failing with InvalidMode unless MODE and SUBMODE are
+    // valid ADIF.
+    pub fn from_adif_record_strict(record: &crate::adif::AdifRecord) -> Result<LogEntry,ImportError> {
+        LogEntry::import_adif_record(record, true)
+    }
+
+    fn import_adif_record(record: &crate::adif::AdifRecord, strict: bool) -> Result<LogEntry,ImportError> {
         match (Call::call_from_adif_record(record), Call::my_call_from_adif_record(record), qso_freq(record), qso_date(record), record.adir_field_values.get("mode"), record.adir_field_values.get("comment"), record.adir_field_values.get("lotw_qsl_sent"), record.adir_field_values.get("lotw_qsl_rcvd")) {
             (Some(call), Some(my_call), Some(freq), Some(date), Some(mode), comment, lotw_sent, lotw_rcvd) => {
                 let comment =
@@ -47,18 +75,32 @@ impl LogEntry {
                         _ => false
                     };
 
+                let submode = record.adir_field_values.get("submode").map(|s| s.as_str());
+                let mode =
+                    match Mode::from_adif(mode, submode) {
+                        Some(mode) => mode,
+                        None if strict => return Err(ImportError::InvalidMode),
+                        None => Mode::import(mode, submode),
+                    };
+                let rst_sent = qso_report(record, "rst_sent", &mode);
+                let rst_received = qso_report(record, "rst_rcvd", &mode);
+
                 Ok(LogEntry {
                     id: uuid::Uuid::new_v4(),
                     from_id: my_call,
                     date: date,
                     call: call,
                     frequency: freq,
-                    mode: Mode::new(mode),
+                    mode,
                     comment: comment,
-                    rst_sent: None,
-                    rst_received: None,
+                    rst_sent: Some(rst_sent),
+                    rst_received: Some(rst_received),
                     lotw_qsl_sent: lotw_sent,
-                    lotw_qsl_rcvd: lotw_rcvd
+                    lotw_qsl_rcvd: lotw_rcvd,
+                    country: Country::from_adif_record(record, false),
+                    continent: qso_field(record, "cont"),
+                    cq_zone: qso_field(record, "cqz"),
+                    itu_zone: qso_field(record, "ituz"),
                 })
             },
             (None, _, _, _, _, _, _, _) => Err(ImportError::MissingCall),
@@ -68,41 +110,307 @@ impl LogEntry {
             (_, _, _, _, None, _, _, _) => Err(ImportError::MissingMode)
         }
     }
+
+    // Reports a mode that the region's band plan doesn't recommend at the
+    // QSO's frequency.
+    pub fn band_plan_mismatch(&self, region: IaruRegion) -> Option<BandPlanMismatch> {
+        bandplan::check_mode(region, self.frequency.freq(), &self.mode)
+    }
+
+    // The other station's entity when the QSO was made, which may differ
+    // from its entity today if the prefix has since been reallocated.
+    pub fn entity(&self, cty: &ClubLogCty) -> Option<CtyEntity> {
+        cty.lookup(&self.call, self.date)
+    }
+
+    // Fills in the country of entries that were logged without one.
+    pub fn resolve_country(&mut self, cty: &ClubLogCty) {
+        if self.country.is_none() {
+            self.country = self.entity(cty).and_then(|entity| entity.country());
+        }
+    }
+
+    // The logged continent and zones take precedence.  Otherwise they come
+    // from the call, which can place a station more precisely than its
+    // entity (a W6 is in CQ zone 3, not 5), unless the call's entity is not
+    // the one that was logged.
+    pub fn continent(&self) -> Option<AdifContinent> {
+        self.continent.or_else(|| self.by_call_or_country(|call| call.continent(), |country| country.continent()))
+    }
+
+    pub fn cq_zone(&self) -> Option<u8> {
+        self.cq_zone.or_else(|| self.by_call_or_country(|call| call.cq_zone(), |country| country.cq_zone()))
+    }
+
+    pub fn itu_zone(&self) -> Option<u8> {
+        self.itu_zone.or_else(|| self.by_call_or_country(|call| call.itu_zone(), |country| country.itu_zone()))
+    }
+
+    fn by_call_or_country<T>(&self, from_call: impl Fn(&Call) -> Result<T, &'static str>,
+        from_country: impl Fn(Country) -> T) -> Option<T> {
+        match (self.country, self.call.country()) {
+            (Some(logged), Ok(country)) if logged != country => Some(from_country(logged)),
+            (logged, _) => from_call(&self.call).ok().or_else(|| logged.map(from_country)),
+        }
+    }
+
+    // Distance between the two stations, if both of their grids are known.
+    pub fn distance_km(&self) -> Option<f64> {
+        match (self.from_id.grid(), self.call.grid()) {
+            (Some(mine), Some(theirs)) => Some(mine.distance_km(theirs)),
+            _ => None,
+        }
+    }
+
+    pub fn to_adif_record(&self) -> crate::adif::AdifRecord {
+        let mut record = crate::adif::AdifRecord::new();
+        let mut set = |name: &str, value: String| {
+            record.adir_field_values.insert(name.to_string(), value);
+        };
+
+        set("call", self.call.call());
+        set("station_callsign", self.from_id.call());
+        set("qso_date", self.date.format("%Y%m%d").to_string());
+        set("time_on", self.date.format("%H%M%S").to_string());
+        set("freq", self.frequency.freq().format_mhz());
+        if let Some(band) = self.frequency.band() {
+            set("band", band.to_string());
+        }
+        set("mode", self.mode.mode().to_string());
+        if let Some(submode) = self.mode.submode() {
+            set("submode", submode.to_string());
+        }
+        if let Some(comment) = &self.comment {
+            set("comment", comment.clone());
+        }
+        if let Some(rst) = &self.rst_sent {
+            set("rst_sent", rst.clone());
+        }
+        if let Some(rst) = &self.rst_received {
+            set("rst_rcvd", rst.clone());
+        }
+        set("lotw_qsl_sent", if self.lotw_qsl_sent { "Y" } else { "N" }.to_string());
+        set("lotw_qsl_rcvd", if self.lotw_qsl_rcvd { "Y" } else { "N" }.to_string());
+        if let Some(country) = self.country {
+            set("dxcc", country.dxcc().to_string());
+            set("country", country.adif_name().to_string());
+        }
+        if let Some(continent) = self.continent {
+            set("cont", continent.to_string());
+        }
+        if let Some(zone) = self.cq_zone {
+            set("cqz", zone.to_string());
+        }
+        if let Some(zone) = self.itu_zone {
+            set("ituz", zone.to_string());
+        }
+
+        record
+    }
 }
 
 fn qso_date(record: &crate::adif::AdifRecord) -> Option<DateTime<Utc>> {
-    match (record.adir_field_values.get("qso_date"), record.adir_field_values.get("time_on")) {
-        (Some(date), Some(time)) if date.len() == 8 && time.len() == 4 => {
-            let year = &date[0..4];
-            let month = &date[4..6];
-            let day = &date[6..8];
-            let hour = &time[0..2];
-            let min = &time[2..4];
-            match (year.parse(), month.parse(), day.parse(), hour.parse(), min.parse()) {
-                (Ok(year), Ok(month), Ok(day), Ok(hour), Ok(min)) => {
-                    let date = Utc.ymd(year, month, day).and_hms(hour, min, 0);
-                    Some(date)
-                },
-                _ => {
-                    None
-                }
-            }
+    match (record.date("qso_date"), record.time("time_on")) {
+        (Ok(Some(date)), Ok(Some(time))) => {
+            Some(Utc.from_utc_datetime(&date.and_time(time)))
         },
         _ => None
     }
 }
 
+// The QSO with the greatest distance between the stations (the "ODX"), and
+// that distance in km.  QSOs without both grids are skipped.
+pub fn odx(entries: &[LogEntry]) -> Option<(&LogEntry, f64)> {
+    entries.iter()
+        .filter_map(|entry| entry.distance_km().map(|km| (entry, km)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+// Like odx(), but measured from a fixed grid (such as the home QTH) rather
+// than from the grid recorded for each QSO.
+pub fn odx_from<'a>(home: &Grid, entries: &'a [LogEntry]) -> Option<(&'a LogEntry, f64)> {
+    entries.iter()
+        .filter_map(|entry| entry.call.grid().as_ref().map(|grid| (entry, home.distance_km(grid))))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+// Reports missing from the record get the default for the QSO's mode.
+fn qso_report(record: &crate::adif::AdifRecord, name: &str, mode: &Mode) -> String {
+    match record.adir_field_values.get(name) {
+        Some(report) if !report.trim().is_empty() => report.to_string(),
+        _ => mode.default_report().to_string(),
+    }
+}
+
+fn qso_field<T: std::str::FromStr>(record: &crate::adif::AdifRecord, name: &str) -> Option<T> {
+    record.adir_field_values.get(name).and_then(|value| value.trim().parse().ok())
+}
+
 fn qso_freq(record: &crate::adif::AdifRecord) -> Option<Band> {
     match record.adir_field_values.get("freq") {
-        Some(freq) => {
-            match freq.parse::<f32>() {
-                Ok(freq) => {
-                    let freq: i32 = (freq * 1000000.0) as i32;
-                    Some(Band::new(freq))
-                },
-                _ => None,
-            }
-        },
+        Some(freq) => Frequency::parse_mhz(freq).ok().map(Band::new),
         None => None,
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adif::AdifRecord;
+
+    // A record with everything an import needs, and any other fields.  The
+    // defaults are replaced by fields of the same name in "extra".
+    fn qso(extra: &[(&str, &str)]) -> AdifRecord {
+        let mut fields = vec![("call", "W1AW"), ("station_callsign", "KK4WJS"),
+            ("qso_date", "20200102"), ("time_on", "123456"), ("freq", "14.074"),
+            ("mode", "FT8")];
+        fields.extend_from_slice(extra);
+        AdifRecord::from_pairs(&fields)
+    }
+
+    #[test]
+    fn test_from_adif_record() {
+        let entry = LogEntry::from_adif_record(&qso(&[])).unwrap();
+        assert_eq!(entry.date, Utc.with_ymd_and_hms(2020, 1, 2, 12, 34, 56).unwrap());
+        assert_eq!(entry.frequency, Band::TwentyMeters(Frequency::from_hz(14_074_000)));
+        assert_eq!(entry.rst_sent.as_deref(), Some("-10"));
+        assert_eq!(entry.rst_received.as_deref(), Some("-10"));
+        assert_eq!(entry.country, None);
+
+        let record = qso(&[("freq", "7.030"), ("mode", "CW"), ("rst_sent", "579"),
+            ("dxcc", "291"), ("country", "CANADA")]);
+        let entry = LogEntry::from_adif_record(&record).unwrap();
+        assert_eq!(entry.rst_sent.as_deref(), Some("579"));
+        assert_eq!(entry.rst_received.as_deref(), Some("599"));
+        assert_eq!(entry.country, Some(Country::UnitedStates));
+        let exported = entry.to_adif_record();
+        assert_eq!(exported.adir_field_values["dxcc"], "291");
+        assert_eq!(exported.adir_field_values["country"], "UNITED STATES OF AMERICA");
+
+        let record = qso(&[("qso_date", "2020010"), ("time_on", "1234")]);
+        assert!(matches!(LogEntry::from_adif_record(&record),
+            Err(ImportError::MissingDateTime)));
+
+        // An invalid grid is dropped rather than failing the import.
+        let record = qso(&[("gridsquare", "ZZ99"), ("my_gridsquare", "EM73")]);
+        let entry = LogEntry::from_adif_record(&record).unwrap();
+        assert_eq!(entry.call.grid(), &None);
+        assert_eq!(entry.from_id.grid(), &Some(Grid::new("EM73").unwrap()));
+    }
+
+    #[test]
+    fn test_resolve_country() {
+        let cty = ClubLogCty::parse("<clublog><entities>\
+            <entity><adif>28</adif><name>CANAL ZONE</name><cqz>7</cqz><cont>NA</cont></entity>\
+            <entity><adif>291</adif><name>UNITED STATES OF AMERICA</name><cqz>5</cqz><cont>NA</cont></entity>\
+            </entities><prefixes>\
+            <prefix><call>K</call><adif>291</adif></prefix>\
+            <prefix><call>KZ5</call><adif>28</adif><end>1979-09-30T23:59:59+00:00</end></prefix>\
+            </prefixes></clublog>").unwrap();
+        let entry = |date: &str| {
+            let record = qso(&[("call", "KZ5AB"), ("qso_date", date), ("freq", "14.025"),
+                ("mode", "CW")]);
+            LogEntry::from_adif_record(&record).unwrap()
+        };
+
+        let mut old = entry("19780102");
+        old.resolve_country(&cty);
+        assert_eq!(old.country, Some(Country::CanalZone));
+        assert_eq!(old.entity(&cty).unwrap().cq_zone, 7);
+
+        let mut recent = entry("19850102");
+        recent.resolve_country(&cty);
+        assert_eq!(recent.country, Some(Country::UnitedStates));
+    }
+
+    #[test]
+    fn test_zones() {
+        let entry = |call: &str, extra: &[(&str, &str)]| {
+            let mut values = vec![("call", call), ("freq", "14.025"), ("mode", "CW")];
+            values.extend_from_slice(extra);
+            LogEntry::from_adif_record(&qso(&values)).unwrap()
+        };
+
+        let w6 = entry("W6ABC", &[]);
+        assert_eq!((w6.continent, w6.cq_zone, w6.itu_zone), (None, None, None));
+        assert_eq!((w6.continent(), w6.cq_zone(), w6.itu_zone()), (Some(AdifContinent::NA), Some(3), Some(6)));
+
+        let logged = entry("W6ABC", &[("cqz", "4"), ("ituz", "7"), ("cont", "NA")]);
+        assert_eq!((logged.cq_zone(), logged.itu_zone()), (Some(4), Some(7)));
+        assert_eq!(logged.continent, Some(AdifContinent::NA));
+        let exported = logged.to_adif_record();
+        assert_eq!(exported.adir_field_values["cqz"], "4");
+        assert_eq!(exported.adir_field_values["ituz"], "7");
+        assert_eq!(exported.adir_field_values["cont"], "NA");
+
+        let other = entry("W6ABC", &[("dxcc", "110")]);
+        assert_eq!((other.continent(), other.cq_zone()), (Some(AdifContinent::OC), Some(31)));
+        let ua0 = entry("UA0XYZ", &[("dxcc", "15")]);
+        assert_eq!(ua0.cq_zone(), Some(19));
+        assert_eq!(entry("W1AW/MM", &[]).cq_zone(), None);
+    }
+
+    #[test]
+    fn test_odx() {
+        let entry = |call: &str, grid: &str| {
+            let record = qso(&[("call", call), ("gridsquare", grid), ("my_gridsquare", "EM73")]);
+            LogEntry::from_adif_record(&record).unwrap()
+        };
+        let mut entries = vec![entry("W1AW", "FN31"), entry("JA1XYZ", "PM95"),
+            entry("G4ABC", "IO91")];
+        entries[0].from_id.set_grid(None);
+
+        let (best, km) = odx(&entries).unwrap();
+        assert_eq!(best.call.call(), "JA1XYZ");
+        assert!((km - entries[1].distance_km().unwrap()).abs() < 1e-9);
+        assert_eq!(entries[0].distance_km(), None);
+
+        let home = Grid::new("FN31").unwrap();
+        let (best, _) = odx_from(&home, &entries).unwrap();
+        assert_eq!(best.call.call(), "JA1XYZ");
+        assert!(odx(&[]).is_none());
+    }
+
+    #[test]
+    fn test_adif_mode() {
+        let record = qso(&[("freq", "14.080"), ("mode", "MFSK"), ("submode", "FT4")]);
+        let entry = LogEntry::from_adif_record(&record).unwrap();
+        assert_eq!(entry.mode, Mode::with_submode(crate::adif::AdifSubmode::FT4));
+
+        let exported = entry.to_adif_record();
+        assert_eq!(exported.adir_field_values["mode"], "MFSK");
+        assert_eq!(exported.adir_field_values["submode"], "FT4");
+        assert_eq!(exported.adir_field_values["freq"], "14.08");
+        assert_eq!(exported.adir_field_values["band"], "20m");
+        assert_eq!(exported.adir_field_values["time_on"], "123456");
+        assert_eq!(entry.band_plan_mismatch(IaruRegion::Region2), None);
+        let reimported = LogEntry::from_adif_record(&exported).unwrap();
+        assert_eq!(reimported.mode, entry.mode);
+        assert_eq!(reimported.date, entry.date);
+
+        let record = qso(&[("freq", "14.080"), ("mode", "CW"), ("submode", "FT4")]);
+        assert!(matches!(LogEntry::from_adif_record_strict(&record),
+            Err(ImportError::InvalidMode)));
+        assert_eq!(LogEntry::from_adif_record(&record).unwrap().mode,
+            Mode::new(crate::adif::AdifMode::CW));
+
+        // Modes written by other programs import, except in strict mode.
+        let import = |mode: &str| {
+            let record = qso(&[("freq", "14.080"), ("mode", mode)]);
+            assert!(matches!(LogEntry::from_adif_record_strict(&record),
+                Err(ImportError::InvalidMode)));
+            LogEntry::from_adif_record(&record).unwrap()
+        };
+        let usb = import("USB");
+        assert_eq!(usb.mode, Mode::with_submode(crate::adif::AdifSubmode::USB));
+        assert_eq!(usb.rst_sent.as_deref(), Some("59"));
+        assert_eq!(import("FT4").mode, Mode::with_submode(crate::adif::AdifSubmode::FT4));
+        let digiu = import("DIGIU");
+        assert_eq!(digiu.mode, Mode::Other("DIGIU".to_string()));
+        assert_eq!(digiu.to_adif_record().adir_field_values["mode"], "DIGIU");
+
+        let record = qso(&[("freq", "14.080"), ("mode", "SSB"), ("submode", "USB")]);
+        let entry = LogEntry::from_adif_record(&record).unwrap();
+        assert!(entry.band_plan_mismatch(IaruRegion::Region2).is_some());
+    }
+}
@@ -0,0 +1,378 @@
+use crate::adif::{AdifMode, AdifSubmode};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+// A mode as ADIF records it: one of the ADIF modes, optionally narrowed down
+// to one of that mode's submodes (e.g., SSB/USB or MFSK/FT4).  Modes that ADIF
+// doesn't define, as found in logs from other programs, are kept as Other.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Mode {
+    Adif { mode: AdifMode, submode: Option<AdifSubmode> },
+    Other(String),
+}
+
+// The groups of modes used for awards (following ARRL DXCC): phone includes
+// digital voice, and digital includes everything that isn't phone, CW, or an
+// image mode.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum ModeCategory {
+    Phone,
+    CW,
+    Digital,
+    Image,
+}
+
+// How signal reports are given: readability and strength ("59"), plus tone
+// ("599"), or the signal-to-noise ratio in dB used by the WSJT family ("-10").
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum ReportFormat {
+    RS,
+    RST,
+    Decibels,
+}
+
+// The modes a transceiver reports over CAT (hamlib, flrig, and friends).  These
+// describe how the rig demodulates rather than what's on the air, so the data
+// modes don't correspond to any one ADIF mode.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum RigMode {
+    USB,
+    LSB,
+    CW,
+    CWR,
+    AM,
+    FM,
+    NFM,
+    RTTY,
+    RTTYR,
+    DigiU,
+    DigiL,
+    PktFM,
+}
+
+impl Mode {
+    pub const fn new(mode: AdifMode) -> Mode {
+        Mode::Adif { mode, submode: None }
+    }
+
+    pub fn with_submode(submode: AdifSubmode) -> Mode {
+        Mode::Adif { mode: submode.mode(), submode: Some(submode) }
+    }
+
+    // Build a mode from the values of the ADIF MODE and SUBMODE fields.  MODE
+    // may be an import-only value (e.g., "PSK31"), which becomes a submode.
+    // Returns None if either value is invalid or the submode doesn't belong to
+    // the mode.
+    pub fn from_adif(mode: &str, submode: Option<&str>) -> Option<Mode> {
+        let (mode, imported) = AdifMode::from_import(mode).ok()?;
+        let submode = match submode {
+            Some(submode) => Some(submode.parse::<AdifSubmode>().ok()?),
+            None => imported,
+        };
+
+        match submode {
+            Some(submode) if submode.mode() != mode => None,
+            _ => Some(Mode::Adif { mode, submode }),
+        }
+    }
+
+    // Build a mode from the MODE and SUBMODE fields as other programs write
+    // them, which isn't always valid ADIF: MODE may be a submode ("USB",
+    // "FT4") or a rig mode ("DIGIU", with the data mode as SUBMODE, if any).
+    // Anything else is kept as an Other mode.
+    pub fn import(mode: &str, submode: Option<&str>) -> Mode {
+        if let Some(adif) = Mode::from_adif(mode, submode) {
+            return adif;
+        }
+        if let Ok(named) = mode.parse::<Mode>() {
+            return named;
+        }
+
+        let data_mode = submode.and_then(|submode| submode.parse::<Mode>().ok());
+        RigMode::new(mode)
+            .and_then(|rig| Mode::from_rig_mode(rig, data_mode))
+            .unwrap_or_else(|| Mode::Other(mode.to_string()))
+    }
+
+    // The mode a QSO was made in, given the mode the rig was in.  For the data
+    // modes, where the rig can't tell, this is whatever "data_mode" the
+    // caller supplies (typically from the digital mode software).
+    pub fn from_rig_mode(rig: RigMode, data_mode: Option<Mode>) -> Option<Mode> {
+        match rig {
+            RigMode::USB => Some(Mode::with_submode(AdifSubmode::USB)),
+            RigMode::LSB => Some(Mode::with_submode(AdifSubmode::LSB)),
+            RigMode::CW | RigMode::CWR => Some(Mode::new(AdifMode::CW)),
+            RigMode::AM => Some(Mode::new(AdifMode::AM)),
+            RigMode::FM | RigMode::NFM => Some(Mode::new(AdifMode::FM)),
+            RigMode::RTTY | RigMode::RTTYR => Some(Mode::new(AdifMode::RTTY)),
+            RigMode::DigiU | RigMode::DigiL | RigMode::PktFM => data_mode,
+        }
+    }
+
+    // Modes ADIF doesn't define count as digital, the catch-all category.
+    pub fn category(&self) -> ModeCategory {
+        let mode = match self {
+            Mode::Adif { mode, .. } => mode,
+            Mode::Other(_) => return ModeCategory::Digital,
+        };
+
+        match mode {
+            AdifMode::AM | AdifMode::FM | AdifMode::SSB | AdifMode::C4FM |
+            AdifMode::DIGITALVOICE | AdifMode::DSTAR => ModeCategory::Phone,
+            AdifMode::CW => ModeCategory::CW,
+            AdifMode::ATV | AdifMode::FAX | AdifMode::SSTV => ModeCategory::Image,
+            _ => ModeCategory::Digital,
+        }
+    }
+
+    pub fn report_format(&self) -> ReportFormat {
+        let (mode, submode) = match self {
+            Mode::Adif { mode, submode } => (mode, submode),
+            Mode::Other(_) => return self.category().report_format(),
+        };
+
+        match submode {
+            Some(AdifSubmode::FT4) | Some(AdifSubmode::FST4) |
+            Some(AdifSubmode::FST4W) | Some(AdifSubmode::JS8) |
+            Some(AdifSubmode::Q65) => return ReportFormat::Decibels,
+            _ => (),
+        }
+
+        match mode {
+            AdifMode::FT8 | AdifMode::FSK441 | AdifMode::ISCAT | AdifMode::JT4 |
+            AdifMode::JT6M | AdifMode::JT9 | AdifMode::JT44 | AdifMode::JT65 |
+            AdifMode::MSK144 | AdifMode::QRA64 | AdifMode::WSPR =>
+                ReportFormat::Decibels,
+            _ => self.category().report_format(),
+        }
+    }
+
+    // The report to assume when a log doesn't record one.
+    pub fn default_report(&self) -> &'static str {
+        self.report_format().default_report()
+    }
+
+    // The ADIF MODE field value, or an Other mode's name as logged.
+    pub fn mode(&self) -> &str {
+        match self {
+            Mode::Adif { mode, .. } => mode.as_str(),
+            Mode::Other(name) => name,
+        }
+    }
+
+    // The ADIF SUBMODE field value, if any.
+    pub fn submode(&self) -> Option<&'static str> {
+        match self {
+            Mode::Adif { submode, .. } => submode.map(|submode| submode.as_str()),
+            Mode::Other(_) => None,
+        }
+    }
+}
+
+impl From<AdifMode> for Mode {
+    fn from(mode: AdifMode) -> Mode {
+        Mode::new(mode)
+    }
+}
+
+impl From<AdifSubmode> for Mode {
+    fn from(submode: AdifSubmode) -> Mode {
+        Mode::with_submode(submode)
+    }
+}
+
+// Parses the name of either a mode or a submode, as an operator would write it
+// ("CW", "USB", "FT8", "FT4").
+impl FromStr for Mode {
+    type Err = crate::adif::AdifEnumError;
+
+    fn from_str(name: &str) -> Result<Mode, Self::Err> {
+        match name.parse::<AdifMode>() {
+            Ok(mode) => Ok(Mode::new(mode)),
+            Err(error) => name.parse::<AdifSubmode>()
+                .map(Mode::with_submode)
+                .map_err(|_| error)
+        }
+    }
+}
+
+// Displays the most specific name: the submode if there is one.
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mode::Adif { submode: Some(submode), .. } => write!(f, "{}", submode),
+            Mode::Adif { mode, submode: None } => write!(f, "{}", mode),
+            Mode::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+// Modes are serialized by name, as displayed ("CW", "USB", "FT4").  Earlier
+// versions serialized a flat enum, so its variant names ("DigiU", "Multipsk")
+// and its {"Other": name} form are accepted too.
+impl Serialize for Mode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[derive(Deserialize)]
+enum LegacyMode {
+    Other(String),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SerializedMode {
+    Name(String),
+    Legacy(LegacyMode),
+}
+
+impl<'de> Deserialize<'de> for Mode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = match SerializedMode::deserialize(deserializer)? {
+            SerializedMode::Name(name) => name,
+            SerializedMode::Legacy(LegacyMode::Other(name)) => name,
+        };
+        Ok(Mode::import(&name, None))
+    }
+}
+
+impl ModeCategory {
+    fn report_format(&self) -> ReportFormat {
+        match self {
+            ModeCategory::Phone | ModeCategory::Image => ReportFormat::RS,
+            ModeCategory::CW | ModeCategory::Digital => ReportFormat::RST,
+        }
+    }
+}
+
+impl ReportFormat {
+    pub fn default_report(&self) -> &'static str {
+        match self {
+            ReportFormat::RS => "59",
+            ReportFormat::RST => "599",
+            ReportFormat::Decibels => "-10",
+        }
+    }
+}
+
+impl RigMode {
+    // Recognizes the mode names used by hamlib and flrig, along with the
+    // spellings common in logging software.
+    pub fn new(name: &str) -> Option<RigMode> {
+        match name.to_uppercase().as_str() {
+            "USB" => Some(RigMode::USB),
+            "LSB" => Some(RigMode::LSB),
+            "CW" | "CW-U" => Some(RigMode::CW),
+            "CWR" | "CW-R" | "CW-L" => Some(RigMode::CWR),
+            "AM" => Some(RigMode::AM),
+            "FM" => Some(RigMode::FM),
+            "NFM" | "FM-N" => Some(RigMode::NFM),
+            "RTTY" | "FSK" => Some(RigMode::RTTY),
+            "RTTYR" | "RTTY-R" | "FSK-R" => Some(RigMode::RTTYR),
+            "DIGU" | "DIGIU" | "PKTUSB" | "USB-D" | "DATA-U" => Some(RigMode::DigiU),
+            "DIGL" | "DIGIL" | "PKTLSB" | "LSB-D" | "DATA-L" => Some(RigMode::DigiL),
+            "PKTFM" | "FM-D" | "DATA-FM" => Some(RigMode::PktFM),
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("CW".parse::<Mode>(), Ok(Mode::new(AdifMode::CW)));
+        let usb = "usb".parse::<Mode>().unwrap();
+        assert_eq!(usb.mode(), "SSB");
+        assert_eq!(usb.submode(), Some("USB"));
+        assert_eq!(usb.to_string(), "USB");
+        assert_eq!("FT4".parse::<Mode>().unwrap().mode(), "MFSK");
+        assert!("DigiU".parse::<Mode>().is_err());
+    }
+
+    #[test]
+    fn test_from_adif() {
+        assert_eq!(Mode::from_adif("MFSK", Some("FT4")),
+            Some(Mode::with_submode(AdifSubmode::FT4)));
+        assert_eq!(Mode::from_adif("PSK31", None),
+            Some(Mode::with_submode(AdifSubmode::PSK31)));
+        assert_eq!(Mode::from_adif("FT8", None), Some(Mode::new(AdifMode::FT8)));
+        assert_eq!(Mode::from_adif("CW", Some("FT4")), None);
+        assert_eq!(Mode::from_adif("FT4", None), None);
+    }
+
+    #[test]
+    fn test_import() {
+        assert_eq!(Mode::import("MFSK", Some("FT4")), Mode::with_submode(AdifSubmode::FT4));
+        assert_eq!(Mode::import("USB", None), Mode::with_submode(AdifSubmode::USB));
+        assert_eq!(Mode::import("FT4", None), Mode::with_submode(AdifSubmode::FT4));
+        assert_eq!(Mode::import("CW", Some("FT4")), Mode::new(AdifMode::CW));
+        assert_eq!(Mode::import("DIGIU", Some("FT8")), Mode::new(AdifMode::FT8));
+        assert_eq!(Mode::import("pktusb", Some("JS8")), Mode::with_submode(AdifSubmode::JS8));
+        assert_eq!(Mode::import("CWR", None), Mode::new(AdifMode::CW));
+
+        let digiu = Mode::import("DIGIU", None);
+        assert_eq!(digiu, Mode::Other("DIGIU".to_string()));
+        assert_eq!((digiu.mode(), digiu.submode()), ("DIGIU", None));
+        assert_eq!(digiu.category(), ModeCategory::Digital);
+        assert_eq!(digiu.default_report(), "599");
+    }
+
+    #[test]
+    fn test_serde() {
+        let json = r#"["FT8","USB","FT4","NFM","DigiU","Multipsk",{"Other":"MFSK16"},{"Other":"Contestia"}]"#;
+        let modes: Vec<Mode> = serde_json::from_str(json).unwrap();
+        assert_eq!(modes[..4], [Mode::new(AdifMode::FT8), Mode::with_submode(AdifSubmode::USB),
+            Mode::with_submode(AdifSubmode::FT4), Mode::new(AdifMode::FM)]);
+        assert_eq!(modes[4], Mode::Other("DigiU".to_string()));
+        assert_eq!(modes[5], Mode::Other("Multipsk".to_string()));
+        assert_eq!(modes[6], Mode::with_submode(AdifSubmode::MFSK16));
+        assert_eq!(modes[7], Mode::Other("Contestia".to_string()));
+
+        let serialized = serde_json::to_string(&modes).unwrap();
+        assert_eq!(serialized, r#"["FT8","USB","FT4","FM","DigiU","Multipsk","MFSK16","Contestia"]"#);
+        assert_eq!(serde_json::from_str::<Vec<Mode>>(&serialized).unwrap(), modes);
+    }
+
+    #[test]
+    fn test_category() {
+        let mode = |name: &str| name.parse::<Mode>().unwrap();
+        assert_eq!(mode("USB").category(), ModeCategory::Phone);
+        assert_eq!(mode("DSTAR").category(), ModeCategory::Phone);
+        assert_eq!(mode("CW").category(), ModeCategory::CW);
+        assert_eq!(mode("FT8").category(), ModeCategory::Digital);
+        assert_eq!(mode("RTTY").category(), ModeCategory::Digital);
+        assert_eq!(mode("SSTV").category(), ModeCategory::Image);
+
+        assert_eq!(mode("LSB").default_report(), "59");
+        assert_eq!(mode("CW").default_report(), "599");
+        assert_eq!(mode("PSK31").default_report(), "599");
+        assert_eq!(mode("FT8").default_report(), "-10");
+        assert_eq!(mode("FT4").report_format(), ReportFormat::Decibels);
+        assert_eq!(mode("JT65").report_format(), ReportFormat::Decibels);
+        assert_eq!(mode("MFSK16").report_format(), ReportFormat::RST);
+    }
+
+    #[test]
+    fn test_rig_mode() {
+        let ft8 = Mode::new(AdifMode::FT8);
+        assert_eq!(RigMode::new("pktusb"), Some(RigMode::DigiU));
+        assert_eq!(Mode::from_rig_mode(RigMode::DigiU, Some(ft8.clone())), Some(ft8.clone()));
+        assert_eq!(Mode::from_rig_mode(RigMode::DigiU, None), None);
+        assert_eq!(Mode::from_rig_mode(RigMode::LSB, Some(ft8)),
+            Some(Mode::with_submode(AdifSubmode::LSB)));
+        assert_eq!(Mode::from_rig_mode(RigMode::CWR, None),
+            Some(Mode::new(AdifMode::CW)));
+    }
+}
@@ -0,0 +1,337 @@
+//
+// src/xml.rs: minimal XML reader and writer helpers
+//
+// Both ADX files and Club Log's cty.xml are plain, data-oriented XML: elements,
+// attributes, text, and the occasional comment or CDATA section.  This module
+// reads that subset into a small in-memory tree.  It does not support DTDs,
+// namespaces, or custom entities, none of which appear in those formats.
+//
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlElement {
+    pub name: String,
+    pub offset: usize,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlNode>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "XML error near byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl XmlElement {
+    // Returns the value of the named attribute, compared case-insensitively.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|node| match node {
+            XmlNode::Element(e) => Some(e),
+            XmlNode::Text(_) => None,
+        })
+    }
+
+    // Returns the first child element with the given name (case-insensitive).
+    pub fn element(&self, name: &str) -> Option<&XmlElement> {
+        self.elements().find(|e| e.name.eq_ignore_ascii_case(name))
+    }
+
+    // Returns the trimmed text of the first child element with the given name.
+    pub fn child_text(&self, name: &str) -> Option<String> {
+        self.element(name).map(|e| e.text().trim().to_string())
+    }
+
+    // Returns the concatenated text content of this element's direct children.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.children {
+            if let XmlNode::Text(t) = node {
+                text.push_str(t);
+            }
+        }
+        text
+    }
+}
+
+// Elements are read recursively, so nesting is limited to keep malformed or
+// hostile input from overflowing the stack.  Neither ADX nor cty.xml nests
+// more than a few levels deep.
+const XML_MAX_DEPTH: usize = 64;
+
+struct XmlReader<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> XmlReader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn error<S: Into<String>>(&self, message: S) -> XmlError {
+        XmlError { offset: self.pos, message: message.into() }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    // Skip past the next occurrence of "terminator", which must be present.
+    fn skip_past(&mut self, terminator: &str) -> Result<&'a str, XmlError> {
+        match self.rest().find(terminator) {
+            Some(index) => {
+                let skipped = &self.rest()[..index];
+                self.pos += index + terminator.len();
+                Ok(skipped)
+            }
+            None => Err(self.error(format!("expected \"{}\"", terminator))),
+        }
+    }
+
+    // Skip the XML declaration, processing instructions, comments, and
+    // DOCTYPE declarations, along with any whitespace around them.
+    fn skip_misc(&mut self) -> Result<(), XmlError> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<!DOCTYPE") || rest.starts_with("<!doctype") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn read_name(&mut self) -> Result<String, XmlError> {
+        let rest = self.rest();
+        let len = rest.find(|c: char| c.is_whitespace() || c == '/' || c == '>' || c == '=')
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    fn read_element(&mut self) -> Result<XmlElement, XmlError> {
+        if !self.rest().starts_with('<') {
+            return Err(self.error("expected \"<\""));
+        }
+        let offset = self.pos;
+        self.pos += 1;
+        let name = self.read_name()?;
+        let mut element = XmlElement { name, offset, attributes: Vec::new(), children: Vec::new() };
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            if rest.is_empty() {
+                return Err(self.error("unexpected end of input in tag"));
+            }
+
+            let attr_name = self.read_name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error(format!("expected \"=\" after attribute \"{}\"", attr_name)));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(q) if q == '"' || q == '\'' => q,
+                _ => return Err(self.error("expected quoted attribute value")),
+            };
+            self.pos += 1;
+            let start = self.pos;
+            let raw = self.skip_past(&quote.to_string())?;
+            let value = unescape(raw).map_err(|message| XmlError { offset: start, message })?;
+            element.attributes.push((attr_name, value));
+        }
+
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.pos += 2;
+                let close = self.read_name()?;
+                if close != element.name {
+                    return Err(self.error(format!(
+                        "expected \"</{}>\", but found \"</{}>\"", element.name, close)));
+                }
+                self.skip_whitespace();
+                self.skip_past(">")?;
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                let text = self.skip_past("]]>")?;
+                element.children.push(XmlNode::Text(text.to_string()));
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                if self.depth >= XML_MAX_DEPTH {
+                    return Err(self.error(format!("elements nested more than {} deep", XML_MAX_DEPTH)));
+                }
+                self.depth += 1;
+                let child = self.read_element()?;
+                self.depth -= 1;
+                element.children.push(XmlNode::Element(child));
+            } else if rest.is_empty() {
+                return Err(self.error(format!("unexpected end of input in \"{}\"", element.name)));
+            } else {
+                let start = self.pos;
+                let len = rest.find('<').unwrap_or(rest.len());
+                self.pos += len;
+                let text = unescape(&rest[..len]).map_err(|message| XmlError { offset: start, message })?;
+                element.children.push(XmlNode::Text(text));
+            }
+        }
+    }
+}
+
+// Parse a complete XML document and return its root element.
+pub fn xml_parse(input: &str) -> Result<XmlElement, XmlError> {
+    // Skip any byte order mark, but keep offsets relative to the whole input.
+    let bom = if input.starts_with('\u{feff}') { '\u{feff}'.len_utf8() } else { 0 };
+    let mut reader = XmlReader { input, pos: bom, depth: 1 };
+    reader.skip_misc()?;
+    let root = reader.read_element()?;
+    reader.skip_misc()?;
+    if !reader.rest().is_empty() {
+        return Err(reader.error("unexpected content after the root element"));
+    }
+    Ok(root)
+}
+
+fn unescape(raw: &str) -> Result<String, String> {
+    if !raw.contains('&') {
+        return Ok(raw.to_string());
+    }
+
+    let mut output = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(index) = rest.find('&') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+        let end = rest.find(';').ok_or_else(|| "unterminated entity reference".to_string())?;
+        let entity = &rest[..end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if entity.starts_with("#x") || entity.starts_with("#X") {
+                    u32::from_str_radix(&entity[2..], 16).ok()
+                } else if let Some(decimal) = entity.strip_prefix('#') {
+                    decimal.parse::<u32>().ok()
+                } else {
+                    None
+                };
+                code.and_then(std::char::from_u32)
+                    .ok_or_else(|| format!("unsupported entity \"&{};\"", entity))?
+            }
+        };
+        output.push(c);
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+//
+// Escape a string for use as element text or an attribute value.  Carriage
+// returns are written as character references because XML readers are
+// required to normalize a literal CR/LF pair to a single LF.
+//
+pub fn xml_escape(s: &str) -> String {
+    let mut output = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '&' => output.push_str("&amp;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&apos;"),
+            '\r' => output.push_str("&#13;"),
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_document() {
+        let doc = xml_parse(r#"<?xml version="1.0"?>
+            <!-- comment -->
+            <ROOT a="1" b='x &amp; y'>
+                <CHILD>one &lt;two&gt; &#51;</CHILD>
+                <EMPTY/>
+                <DATA><![CDATA[<raw>]]></DATA>
+            </ROOT>"#).unwrap();
+        assert_eq!(doc.name, "ROOT");
+        assert_eq!(doc.attribute("B"), Some("x & y"));
+        assert_eq!(doc.element("child").unwrap().text(), "one <two> 3");
+        assert_eq!(doc.element("EMPTY").unwrap().children.len(), 0);
+        assert_eq!(doc.element("DATA").unwrap().text(), "<raw>");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(xml_parse("<A><B></A>").is_err());
+        assert!(xml_parse("<A>").is_err());
+        assert!(xml_parse("<A>&bogus;</A>").is_err());
+        assert!(xml_parse("<A/><B/>").is_err());
+
+        let nested = |depth: usize| format!("{}{}", "<A>".repeat(depth), "</A>".repeat(depth));
+        assert!(xml_parse(&nested(XML_MAX_DEPTH)).is_ok());
+        let error = xml_parse(&nested(100_000)).unwrap_err();
+        assert_eq!(error.message, "elements nested more than 64 deep");
+        assert_eq!(error.offset, XML_MAX_DEPTH * 3);
+    }
+
+    #[test]
+    fn test_escape() {
+        let escaped = xml_escape("a<b>&\"c\"\r\n");
+        let doc = xml_parse(&format!("<A x=\"{0}\">{0}</A>", escaped)).unwrap();
+        assert_eq!(doc.text(), "a<b>&\"c\"\r\n");
+        assert_eq!(doc.attribute("x"), Some("a<b>&\"c\"\r\n"));
+    }
+}